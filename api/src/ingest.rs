@@ -6,6 +6,7 @@ use resolve_path::PathResolveExt;
 use sea_orm::Database;
 use serde::Deserialize;
 
+use db::queries::capabilities;
 use db::queries::ingest::{self, IngestIngredient, IngestUnit};
 
 #[derive(Args, Debug)]
@@ -61,6 +62,15 @@ pub async fn run(
     ingest::insert_recipes(&db, &recipes)
         .await
         .context("Ingesting recipes")?;
+    ingest::insert_recipe_tags(&db, &recipes)
+        .await
+        .context("Ingesting recipe tags")?;
+    ingest::insert_reference_catalog(&db, &recipes)
+        .await
+        .context("Ingesting capabilities")?;
+    capabilities::learn_capability_schemas(&db)
+        .await
+        .context("Learning capability settings")?;
 
     Ok(())
 }
@@ -1,13 +1,14 @@
-use axum::http::{HeaderMap, Request};
+use axum::http::{HeaderMap, HeaderValue, Request, header};
 use axum::response::IntoResponse;
 use axum::{
     body::Body,
     extract::{Path, Query},
 };
+use chrono::{DateTime, Utc};
 use color_eyre::eyre::{Context, OptionExt as _};
 use http_body_util::BodyExt;
 use sea_orm::DatabaseConnection;
-use std::{io::Cursor, sync::LazyLock};
+use std::sync::LazyLock;
 use tracing::{Instrument as _, debug, debug_span, error, info, warn};
 
 pub(crate) static CERT: &[u8] = include_bytes!("../../ca_stuff/server.crt");
@@ -39,41 +40,188 @@ impl IntoResponse for AppError {
     }
 }
 
+/// The validators we hand out for a resource, used to answer conditional
+/// requests from the device.
+struct Validators {
+    etag: String,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+impl Validators {
+    fn new(etag: &str, last_modified: Option<DateTime<Utc>>) -> Self {
+        Self {
+            etag: format!("\"{etag}\""),
+            last_modified,
+        }
+    }
+
+    /// Whether the request's conditional headers say the client already has
+    /// this version.
+    fn not_modified(&self, headers: &HeaderMap) -> bool {
+        db::etag::not_modified(headers, &self.etag, self.last_modified)
+    }
+
+    fn headers(&self, cache_control: &'static str) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+
+        headers.insert(
+            header::ETAG,
+            HeaderValue::from_str(&self.etag).context("Building etag header")?,
+        );
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        );
+
+        if let Some(last_modified) = self.last_modified {
+            headers.insert(
+                header::LAST_MODIFIED,
+                HeaderValue::from_str(
+                    &last_modified
+                        .format("%a, %d %b %Y %H:%M:%S GMT")
+                        .to_string(),
+                )
+                .context("Building last modified header")?,
+            );
+        }
+
+        Ok(headers)
+    }
+}
+
+/// Picks the caching related headers out of an upstream response.
+fn upstream_cache_headers(upstream: &HeaderMap) -> HeaderMap {
+    [header::ETAG, header::CACHE_CONTROL, header::LAST_MODIFIED]
+        .into_iter()
+        .filter_map(|name| {
+            let value = upstream.get(&name)?.clone();
+            Some((name, value))
+        })
+        .collect()
+}
+
+#[derive(serde::Deserialize)]
+struct RecipeOptions {
+    /// Scales the recipe to serve this many, for recipes of our own
+    serves: Option<u8>,
+}
+
 #[derive(serde::Deserialize)]
 struct ImageDimensions {
     width: u32,
     height: u32,
 }
 
+/// Serves one of our stored images resized to `dims`, in whichever format the
+/// device prefers.
+async fn resized_image(
+    stored: &db::queries::images::StoredImage,
+    dims: &ImageDimensions,
+    headers: &HeaderMap,
+) -> Result<axum::response::Response> {
+    let format = db::queries::images::negotiate_format(
+        headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok()),
+        None,
+    );
+
+    // resized and converted variants are different representations, so
+    // they each need their own etag
+    let validators = Validators::new(
+        &format!(
+            "{}-{}x{}.{}",
+            stored.etag,
+            dims.width,
+            dims.height,
+            format.extensions_str()[0]
+        ),
+        stored.modified_at,
+    );
+    let mut cache_headers = validators.headers("public, no-cache")?;
+    cache_headers.insert(header::VARY, HeaderValue::from_static("accept"));
+
+    if validators.not_modified(headers) {
+        return Ok((axum::http::StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    let image =
+        db::queries::images::get_resized(db().await, stored, dims.width, dims.height, format)
+            .await
+            .context("Resizing image")?;
+
+    Ok((
+        axum::http::StatusCode::OK,
+        cache_headers,
+        [(axum::http::header::CONTENT_TYPE, format.to_mime_type())],
+        image,
+    )
+        .into_response())
+}
+
 #[axum::debug_handler]
 async fn recipe_hero(
     Path(recipe_id): Path<String>,
     Query(dims): Query<ImageDimensions>,
     headers: HeaderMap,
 ) -> Result<axum::response::Response> {
-    if let Ok(mut image) = db::queries::images::get_image(db().await, &recipe_id).await {
+    if let Ok(stored) = db::queries::images::get_hero_image(db().await, &recipe_id).await {
         info!(recipe_id = recipe_id, "Found custom image");
 
-        let decoded = image::ImageReader::new(Cursor::new(&image))
-            .with_guessed_format()
-            .context("Guessing image format")?
-            .decode()
-            .context("Decoding image")?;
+        return resized_image(&stored, &dims, &headers).await;
+    }
 
-        let resized = decoded.resize_to_fill(
-            dims.width,
-            dims.height,
-            image::imageops::FilterType::Triangle,
+    // custom recipes without a photo would 404 upstream, so draw one instead
+    let placeholder =
+        match db::queries::placeholders::find_placeholder(db().await, &recipe_id).await {
+            Ok(placeholder) => placeholder,
+            Err(err) => {
+                error!(recipe_id = recipe_id, err = ?err, "Couldn't look up placeholder");
+                None
+            }
+        };
+
+    if let Some(placeholder) = placeholder {
+        info!(recipe_id = recipe_id, "Drawing placeholder image");
+
+        let format = db::queries::images::negotiate_format(
+            headers
+                .get(header::ACCEPT)
+                .and_then(|accept| accept.to_str().ok()),
+            None,
+        );
+
+        let validators = Validators::new(
+            &format!(
+                "{}-{}x{}.{}",
+                placeholder.etag,
+                dims.width,
+                dims.height,
+                format.extensions_str()[0]
+            ),
+            None,
         );
+        let mut cache_headers = validators.headers("public, no-cache")?;
+        cache_headers.insert(header::VARY, HeaderValue::from_static("accept"));
 
-        image.clear();
-        resized
-            .write_to(Cursor::new(&mut image), image::ImageFormat::WebP)
-            .context("Converting image")?;
+        if validators.not_modified(&headers) {
+            return Ok((axum::http::StatusCode::NOT_MODIFIED, cache_headers).into_response());
+        }
+
+        let image = db::queries::placeholders::get_placeholder(
+            db().await,
+            &placeholder,
+            dims.width,
+            dims.height,
+            format,
+        )
+        .await
+        .context("Drawing placeholder")?;
 
         return Ok((
             axum::http::StatusCode::OK,
-            [(axum::http::header::CONTENT_TYPE, "image/webp")],
+            cache_headers,
+            [(axum::http::header::CONTENT_TYPE, format.to_mime_type())],
             image,
         )
             .into_response());
@@ -114,16 +262,256 @@ async fn recipe_hero(
     ))
 }
 
+#[axum::debug_handler]
+async fn recipe_step_image(
+    Path((recipe_id, image_id)): Path<(String, String)>,
+    Query(dims): Query<ImageDimensions>,
+    headers: HeaderMap,
+) -> Result<axum::response::Response> {
+    debug!(
+        recipe_id = recipe_id,
+        image_id = image_id,
+        "Serving step image"
+    );
+
+    let Some(stored) = db::queries::images::get_attached_image(
+        db().await,
+        &recipe_id,
+        &image_id,
+        db::entities::sea_orm_active_enums::ImageKind::Step,
+    )
+    .await?
+    else {
+        return Ok(axum::http::StatusCode::NOT_FOUND.into_response());
+    };
+
+    resized_image(&stored, &dims, &headers).await
+}
+
+#[axum::debug_handler]
+async fn recipe_ingredient_image(
+    Path((recipe_id, image_id)): Path<(String, String)>,
+    Query(dims): Query<ImageDimensions>,
+    headers: HeaderMap,
+) -> Result<axum::response::Response> {
+    debug!(
+        recipe_id = recipe_id,
+        image_id = image_id,
+        "Serving ingredient image"
+    );
+
+    let Some(stored) = db::queries::images::get_attached_image(
+        db().await,
+        &recipe_id,
+        &image_id,
+        db::entities::sea_orm_active_enums::ImageKind::Ingredient,
+    )
+    .await?
+    else {
+        return Ok(axum::http::StatusCode::NOT_FOUND.into_response());
+    };
+
+    resized_image(&stored, &dims, &headers).await
+}
+
+#[axum::debug_handler]
+async fn author_avatar(
+    Path(author_id): Path<i64>,
+    headers: HeaderMap,
+) -> Result<axum::response::Response> {
+    debug!(author_id = author_id, "Serving author avatar");
+
+    let Some((avatar, etag)) = db::queries::authors::get_avatar(db().await, author_id).await?
+    else {
+        return Ok(axum::http::StatusCode::NOT_FOUND.into_response());
+    };
+
+    let validators = Validators::new(&etag, None);
+    let cache_headers = validators.headers("public, no-cache")?;
+
+    if validators.not_modified(&headers) {
+        return Ok((axum::http::StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    Ok((
+        axum::http::StatusCode::OK,
+        cache_headers,
+        [(axum::http::header::CONTENT_TYPE, "image/png")],
+        avatar,
+    )
+        .into_response())
+}
+
+/// Points the media attached to a custom recipe's steps and ingredients, and
+/// its author's avatar, at the routes we serve them from.
+fn link_media(recipe: &mut types::Recipe, domain: Option<&str>) {
+    let base = match domain {
+        Some(domain) => format!("https://{domain}/media/images/recipes/{}", recipe.id),
+        None => format!("/media/images/recipes/{}", recipe.id),
+    };
+
+    let steps = recipe
+        .steps
+        .iter_mut()
+        .flat_map(|s| s.media.iter_mut().map(|m| ("steps", m)));
+    let ingredients = recipe
+        .ingredients
+        .iter_mut()
+        .flat_map(|i| i.media.iter_mut().map(|m| ("ingredients", m)));
+
+    for (kind, media) in steps.chain(ingredients) {
+        if media.url.is_none() {
+            media.url = Some(format!("{base}/{kind}/{}", media.id));
+        }
+    }
+
+    if let Some(domain) = domain
+        && recipe.author.image.starts_with('/')
+    {
+        recipe.author.image = format!("https://{domain}{}", recipe.author.image);
+    }
+}
+
+/// Picks which of `available` locales best suits the device's
+/// `Accept-Language`. Exact matches win, then ones in the same language.
+fn preferred_locale<'a>(headers: &HeaderMap, available: &[&'a str]) -> Option<&'a str> {
+    let accept = headers.get(header::ACCEPT_LANGUAGE)?.to_str().ok()?;
+
+    let mut wanted = accept
+        .split(',')
+        .filter_map(|part| {
+            let mut params = part.trim().split(';');
+            let tag = params.next()?.trim();
+            let q = params
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (!tag.is_empty() && tag != "*").then_some((tag, q))
+        })
+        .collect::<Vec<_>>();
+    wanted.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let language = |tag: &str| tag.split(['-', '_']).next().unwrap_or(tag).to_lowercase();
+
+    wanted.iter().find_map(|(tag, _)| {
+        available
+            .iter()
+            .find(|l| {
+                l.replace('_', "-")
+                    .eq_ignore_ascii_case(&tag.replace('_', "-"))
+            })
+            .or_else(|| available.iter().find(|l| language(l) == language(tag)))
+            .copied()
+    })
+}
+
+/// Which appliance profile the device asking is, going by its `User-Agent`.
+/// `None` when that can't be told, in which case it's served everything.
+async fn device_appliance(headers: &HeaderMap) -> Option<String> {
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|ua| ua.to_str().ok())
+        .unwrap_or_default();
+
+    // not knowing the appliance just means not filtering recipes by it
+    match db::queries::appliances::appliance_for_user_agent(db().await, user_agent).await {
+        Ok(appliance) => appliance,
+        Err(err) => {
+            warn!(err = ?err, "Couldn't tell which appliance is asking");
+            None
+        }
+    }
+}
+
 #[axum::debug_handler]
 async fn recipe(
     Path(recipe_id): Path<String>,
+    Query(options): Query<RecipeOptions>,
     headers: HeaderMap,
-) -> Result<axum::Json<types::Recipe>> {
-    if let Ok(custom) = db::queries::recipes::get_recipe(db().await, &recipe_id).await {
+) -> Result<axum::response::Response> {
+    let appliance = device_appliance(&headers).await;
+
+    let custom = db::queries::recipes::get_recipe(db().await, &recipe_id)
+        .await
+        .ok()
+        // drafts and scheduled recipes stay in the editor
+        .filter(|r| r.is_live(Utc::now()))
+        // and recipes for another appliance are left to upstream
+        .filter(|r| appliance.as_ref().is_none_or(|a| *a == r.appliance));
+
+    // serve the translation in the device's language, when there is one
+    let custom = match custom {
+        Some(custom) => {
+            let available = std::iter::once(custom.locale.as_str())
+                .chain(
+                    custom
+                        .forked_into_other_locales
+                        .iter()
+                        .map(|f| f.locale.as_str()),
+                )
+                .collect::<Vec<_>>();
+            let fork = preferred_locale(&headers, &available)
+                .filter(|l| *l != custom.locale)
+                .and_then(|l| {
+                    custom
+                        .forked_into_other_locales
+                        .iter()
+                        .find(|f| f.locale == l)
+                });
+
+            let translation = match fork {
+                Some(fork) => db::queries::recipes::get_recipe(db().await, &fork.id)
+                    .await
+                    .ok()
+                    .filter(|r| r.is_live(Utc::now())),
+                None => None,
+            };
+
+            Some(translation.unwrap_or(custom))
+        }
+        None => None,
+    };
+
+    let custom = match (custom, options.serves) {
+        (Some(custom), Some(serves)) => {
+            let (scaled, warnings) =
+                db::queries::recipes::scale(db().await, &custom.id, serves).await?;
+            debug!(
+                recipe_id = recipe_id,
+                serves,
+                ?warnings,
+                "Scaled custom recipe"
+            );
+
+            Some(scaled)
+        }
+        (custom, _) => custom,
+    };
+
+    if let Some(mut custom) = custom {
         info!(recipe_id = recipe_id, "Found custom recipe");
+
+        let validators = Validators::new(&custom.etag, Some(custom.modified_at));
+        let mut cache_headers = validators.headers("private, no-cache")?;
+        // which translation is served depends on the device's language
+        cache_headers.insert(header::VARY, HeaderValue::from_static("accept-language"));
+
+        if validators.not_modified(&headers) {
+            debug!(recipe_id = recipe_id, "Custom recipe not modified");
+
+            return Ok((axum::http::StatusCode::NOT_MODIFIED, cache_headers).into_response());
+        }
+
+        link_media(
+            &mut custom,
+            headers
+                .get(header::HOST)
+                .and_then(|host| host.to_str().ok()),
+        );
+
         debug!(recipe = ?custom, "Full recipe json");
 
-        return Ok(axum::Json(custom));
+        return Ok((cache_headers, axum::Json(custom)).into_response());
     }
 
     info!(recipe_id = recipe_id, "Falling back on server recipe");
@@ -143,12 +531,22 @@ async fn recipe(
         .send()
         .instrument(debug_span!("fallback_request"))
         .await
-        .context("Making fallback proxy request")?
-        .json::<types::Recipe>()
-        .await
-        .context("Reading json")?;
+        .context("Making fallback proxy request")?;
 
-    Ok(axum::Json(resp))
+    // the device's conditional headers were passed along, so upstream may
+    // answer without a body
+    if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok((
+            axum::http::StatusCode::NOT_MODIFIED,
+            upstream_cache_headers(resp.headers()),
+        )
+            .into_response());
+    }
+
+    let cache_headers = upstream_cache_headers(resp.headers());
+    let resp = resp.json::<types::Recipe>().await.context("Reading json")?;
+
+    Ok((cache_headers, axum::Json(resp)).into_response())
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -179,7 +577,17 @@ async fn collections_saved_recipes(headers: HeaderMap) -> Result<axum::Json<Reci
         .await
         .context("Reading json")?;
 
-    let mut custom = db::queries::recipes::list_recipe_items(db().await, None, None, false).await?;
+    let appliance = device_appliance(&headers).await;
+
+    let mut custom = db::queries::recipes::list_recipe_items(
+        db().await,
+        None,
+        None,
+        false,
+        true,
+        appliance.as_deref(),
+    )
+    .await?;
 
     custom.extend(resp.items);
     resp.items = custom;
@@ -245,6 +653,18 @@ pub async fn run() -> color_eyre::Result<()> {
             "/media/images/recipes/{recipe_id}/hero",
             axum::routing::get(recipe_hero),
         )
+        .route(
+            "/media/images/recipes/{recipe_id}/steps/{image_id}",
+            axum::routing::get(recipe_step_image),
+        )
+        .route(
+            "/media/images/recipes/{recipe_id}/ingredients/{image_id}",
+            axum::routing::get(recipe_ingredient_image),
+        )
+        .route(
+            "/media/images/authors/{author_id}",
+            axum::routing::get(author_avatar),
+        )
         .fallback(axum::routing::any(api_fallback));
 
     let t_443 = tokio::spawn({
@@ -11,6 +11,10 @@ pub struct Model {
     pub name: String,
     pub image: String,
     pub url: String,
+    #[sea_orm(column_type = "Blob", nullable)]
+    pub avatar: Option<Vec<u8>>,
+    pub avatar_e_tag: Option<String>,
+    pub is_default: bool,
     #[sea_orm(has_many)]
     pub recipes: HasMany<super::recipe::Entity>,
 }
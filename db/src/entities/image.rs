@@ -1,15 +1,32 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0
 
+use super::sea_orm_active_enums::ImageKind;
 use sea_orm::entity::prelude::*;
 
 #[sea_orm::model]
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "image")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: String,
     #[sea_orm(column_type = "Blob")]
     pub data: Vec<u8>,
+    pub e_tag: String,
+    pub modified_at: Option<DateTimeUtc>,
+    pub content_type: String,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub file_name: Option<String>,
+    pub recipe_id: String,
+    pub position: i64,
+    pub is_hero: bool,
+    #[sea_orm(column_type = "Double")]
+    pub focal_x: f64,
+    #[sea_orm(column_type = "Double")]
+    pub focal_y: f64,
+    pub kind: ImageKind,
+    #[sea_orm(has_many)]
+    pub image_variants: HasMany<super::image_variant::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
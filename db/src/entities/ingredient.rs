@@ -3,12 +3,14 @@
 use sea_orm::entity::prelude::*;
 
 #[sea_orm::model]
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "ingredient")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: String,
     pub name: String,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub density: Option<f64>,
     #[sea_orm(has_many, via = "ingredient_unit")]
     pub units: HasMany<super::unit::Entity>,
 }
@@ -2,10 +2,23 @@
 
 pub mod prelude;
 
+pub mod appliance_profile;
 pub mod author;
+pub mod capability;
+pub mod capability_phase;
+pub mod capability_schema;
 pub mod image;
+pub mod image_variant;
 pub mod ingredient;
 pub mod ingredient_unit;
+pub mod nominal_value;
+pub mod placeholder_image;
 pub mod preparation;
 pub mod recipe;
+pub mod recipe_alias;
+pub mod recipe_revision;
+pub mod recipe_tag;
+pub mod reference_setting;
+pub mod sea_orm_active_enums;
+pub mod tag;
 pub mod unit;
@@ -1,9 +1,21 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0
 
+pub use super::appliance_profile::Entity as ApplianceProfile;
 pub use super::author::Entity as Author;
+pub use super::capability::Entity as Capability;
+pub use super::capability_phase::Entity as CapabilityPhase;
+pub use super::capability_schema::Entity as CapabilitySchema;
 pub use super::image::Entity as Image;
+pub use super::image_variant::Entity as ImageVariant;
 pub use super::ingredient::Entity as Ingredient;
 pub use super::ingredient_unit::Entity as IngredientUnit;
+pub use super::nominal_value::Entity as NominalValue;
+pub use super::placeholder_image::Entity as PlaceholderImage;
 pub use super::preparation::Entity as Preparation;
 pub use super::recipe::Entity as Recipe;
+pub use super::recipe_alias::Entity as RecipeAlias;
+pub use super::recipe_revision::Entity as RecipeRevision;
+pub use super::recipe_tag::Entity as RecipeTag;
+pub use super::reference_setting::Entity as ReferenceSetting;
+pub use super::tag::Entity as Tag;
 pub use super::unit::Entity as Unit;
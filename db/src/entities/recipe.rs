@@ -26,6 +26,12 @@ pub struct Model {
     pub ingredients: Json,
     pub is_custom: bool,
     pub exposed_id: Option<String>,
+    pub deleted_at: Option<DateTimeUtc>,
+    pub state: String,
+    pub visibility: String,
+    pub fork_of: Option<String>,
+    pub appliance: String,
+    pub timing: Option<Json>,
     #[sea_orm(
         belongs_to,
         from = "author_id",
@@ -34,6 +40,14 @@ pub struct Model {
         on_delete = "NoAction"
     )]
     pub author: HasOne<super::author::Entity>,
+    #[sea_orm(has_many)]
+    pub placeholder_images: HasMany<super::placeholder_image::Entity>,
+    #[sea_orm(has_many)]
+    pub recipe_aliases: HasMany<super::recipe_alias::Entity>,
+    #[sea_orm(has_many)]
+    pub recipe_revisions: HasMany<super::recipe_revision::Entity>,
+    #[sea_orm(has_many, via = "recipe_tag")]
+    pub tags: HasMany<super::tag::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
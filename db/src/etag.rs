@@ -0,0 +1,41 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use chrono::{DateTime, Utc};
+use http::{HeaderMap, header};
+
+/// Derives an etag from some content.
+///
+/// `DefaultHasher::new` is only stable within a build, which is fine here:
+/// an etag changing after an upgrade just costs the device one re-download.
+pub fn from_content(content: impl Hash) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Whether a request's conditional headers say the client already has the
+/// version with `etag`, which is given quoted as it's sent. `If-None-Match`
+/// wins over `If-Modified-Since` when both are present.
+pub fn not_modified(headers: &HeaderMap, etag: &str, last_modified: Option<DateTime<Utc>>) -> bool {
+    let mut if_none_match = headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|t| t.trim())
+        .peekable();
+
+    if if_none_match.peek().is_some() {
+        return if_none_match.any(|t| t == "*" || t.trim_start_matches("W/") == etag);
+    }
+
+    let Some(last_modified) = last_modified else {
+        return false;
+    };
+
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+        .is_some_and(|since| last_modified.timestamp() <= since.timestamp())
+}
@@ -5,6 +5,7 @@ use sea_orm::{Database, DatabaseConnection};
 use tracing::info;
 
 pub mod entities;
+pub mod etag;
 pub mod queries;
 
 pub async fn connect() -> Result<DatabaseConnection, anyhow::Error> {
@@ -12,6 +13,12 @@ pub async fn connect() -> Result<DatabaseConnection, anyhow::Error> {
     info!(loc = loc, "Connecting to database");
     let db = Database::connect(loc).await?;
     migration::Migrator::up(&db, None).await?;
+    queries::catalog::seed_catalog(&db)
+        .await
+        .map_err(|e| anyhow::anyhow!("Seeding the reference catalog: {e:?}"))?;
+    queries::appliances::seed_appliances(&db)
+        .await
+        .map_err(|e| anyhow::anyhow!("Seeding appliance profiles: {e:?}"))?;
 
     Ok(db)
 }
@@ -0,0 +1,148 @@
+use color_eyre::eyre::WrapErr as _;
+use color_eyre::eyre::bail;
+use migration::OnConflict;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{DatabaseConnection, EntityTrait as _, PaginatorTrait as _, QueryOrder as _};
+use sea_orm::{QuerySelect as _, prelude::Json};
+use types::appliance::{ApplianceProfile, user_agent_matches};
+
+use crate::entities::appliance_profile;
+
+fn to_profile(a: appliance_profile::Model) -> color_eyre::Result<ApplianceProfile> {
+    Ok(ApplianceProfile {
+        user_agents: serde_path_to_error::deserialize(a.user_agents)
+            .context("Deserializing user agents")?,
+        capabilities: serde_path_to_error::deserialize(a.capabilities)
+            .context("Deserializing capabilities")?,
+        settings: serde_path_to_error::deserialize(a.settings)
+            .context("Deserializing setting limits")?,
+        capability_loads: a
+            .capability_loads
+            .map(serde_path_to_error::deserialize)
+            .transpose()
+            .context("Deserializing capability loads")?
+            .unwrap_or_default(),
+        id: a.id,
+        name: a.name,
+        bowl_capacity_ml: a.bowl_capacity_ml,
+        min_heating_ml: a.min_heating_ml,
+    })
+}
+
+pub async fn list_appliances(db: &DatabaseConnection) -> color_eyre::Result<Vec<ApplianceProfile>> {
+    appliance_profile::Entity::find()
+        .order_by_asc(appliance_profile::Column::Name)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(to_profile)
+        .collect()
+}
+
+/// Recipes written for an appliance that's since been removed are held to
+/// the built in one, like the editor does.
+pub async fn get_appliance(
+    db: &DatabaseConnection,
+    id: &str,
+) -> color_eyre::Result<ApplianceProfile> {
+    match appliance_profile::Entity::find_by_id(id).one(db).await? {
+        Some(model) => to_profile(model),
+        None => Ok(ApplianceProfile::builtin_or_default(id)),
+    }
+}
+
+/// The id of the appliance a request came from, going by its `User-Agent`.
+/// Requests matching none are taken to be from the Kenwood, which the proxy
+/// was first written for, as long as it hasn't been removed.
+pub async fn appliance_for_user_agent(
+    db: &DatabaseConnection,
+    user_agent: &str,
+) -> color_eyre::Result<Option<String>> {
+    // only what's needed to match, since this runs for every recipe fetched
+    let appliances: Vec<(String, Json)> = appliance_profile::Entity::find()
+        .select_only()
+        .columns([
+            appliance_profile::Column::Id,
+            appliance_profile::Column::UserAgents,
+        ])
+        .order_by_asc(appliance_profile::Column::Name)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let mut fallback = None;
+    for (id, user_agents) in appliances {
+        let user_agents: Vec<String> =
+            serde_path_to_error::deserialize(user_agents).context("Deserializing user agents")?;
+
+        if user_agent_matches(&user_agents, user_agent) {
+            return Ok(Some(id));
+        }
+        if id == ApplianceProfile::KENWOOD_COOKING_CHEF {
+            fallback = Some(id);
+        }
+    }
+
+    Ok(fallback)
+}
+
+/// Fills the appliances in from the built in ones if there aren't any yet.
+pub async fn seed_appliances(db: &DatabaseConnection) -> color_eyre::Result<()> {
+    if appliance_profile::Entity::find().count(db).await? > 0 {
+        return Ok(());
+    }
+
+    for appliance in ApplianceProfile::builtin() {
+        save_appliance(db, appliance).await?;
+    }
+
+    Ok(())
+}
+
+/// Adds an appliance, or updates it if it's already there.
+pub async fn save_appliance(
+    db: &DatabaseConnection,
+    appliance: ApplianceProfile,
+) -> color_eyre::Result<()> {
+    if appliance.id.trim().is_empty() {
+        bail!("An id is needed");
+    }
+    if appliance.name.trim().is_empty() {
+        bail!("A name is needed");
+    }
+
+    let user_agents: Vec<String> = appliance
+        .user_agents
+        .iter()
+        .map(|a| a.trim().to_owned())
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    appliance_profile::Entity::insert(appliance_profile::ActiveModel {
+        id: Set(appliance.id.trim().to_owned()),
+        name: Set(appliance.name.trim().to_owned()),
+        user_agents: Set(serde_json::to_value(&user_agents)?),
+        capabilities: Set(serde_json::to_value(&appliance.capabilities)?),
+        settings: Set(serde_json::to_value(&appliance.settings)?),
+        bowl_capacity_ml: Set(appliance.bowl_capacity_ml),
+        min_heating_ml: Set(appliance.min_heating_ml),
+        capability_loads: Set(Some(serde_json::to_value(&appliance.capability_loads)?)),
+    })
+    .on_conflict(
+        OnConflict::column(appliance_profile::Column::Id)
+            .update_columns([
+                appliance_profile::Column::Name,
+                appliance_profile::Column::UserAgents,
+                appliance_profile::Column::Capabilities,
+                appliance_profile::Column::Settings,
+                appliance_profile::Column::BowlCapacityMl,
+                appliance_profile::Column::MinHeatingMl,
+                appliance_profile::Column::CapabilityLoads,
+            ])
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
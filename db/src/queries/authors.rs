@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use ::image::{ImageFormat, imageops::FilterType};
+use color_eyre::eyre::OptionExt as _;
+use color_eyre::eyre::WrapErr as _;
+use color_eyre::eyre::bail;
+use migration::Expr;
+use sea_orm::ActiveValue::{NotSet, Set};
+use sea_orm::{ActiveModelTrait as _, ColumnTrait as _, DatabaseConnection, EntityTrait as _};
+use sea_orm::{PaginatorTrait as _, QueryFilter, QueryOrder as _, QuerySelect as _};
+
+use crate::entities::{author, prelude::*, recipe};
+
+/// How big avatars are stored and served, in pixels.
+const AVATAR_SIZE: u32 = 256;
+
+pub async fn list_authors(
+    db: &DatabaseConnection,
+) -> color_eyre::Result<Vec<types::AuthorProfile>> {
+    let authors = Author::find()
+        .order_by_asc(author::Column::Name)
+        .all(db)
+        .await?;
+
+    let recipe_counts = Recipe::find()
+        .select_only()
+        .column(recipe::Column::AuthorId)
+        .column_as(
+            sea_orm::ExprTrait::count(Expr::col(recipe::Column::Id)),
+            "count",
+        )
+        .filter(recipe::Column::DeletedAt.is_null())
+        .group_by(recipe::Column::AuthorId)
+        .into_tuple::<(i64, i64)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+    Ok(authors
+        .into_iter()
+        .map(|a| types::AuthorProfile {
+            recipe_count: recipe_counts.get(&a.id).copied().unwrap_or(0) as u64,
+            id: a.id,
+            name: a.name,
+            image: a.image,
+            url: a.url,
+            has_avatar: a.avatar.is_some(),
+            is_default: a.is_default,
+        })
+        .collect())
+}
+
+/// Who new recipes are written by, when one's been picked.
+pub async fn default_author(db: &DatabaseConnection) -> color_eyre::Result<Option<types::Author>> {
+    let author = Author::find()
+        .filter(author::Column::IsDefault.eq(true))
+        .one(db)
+        .await?;
+
+    Ok(author.map(|a| types::Author::served(a.id, a.name, a.image, a.url, a.avatar.is_some())))
+}
+
+/// Creates an author, or updates one when `id` is given, and returns its id.
+pub async fn save_author(
+    db: &DatabaseConnection,
+    id: Option<i64>,
+    name: &str,
+    image: &str,
+    url: &str,
+) -> color_eyre::Result<i64> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        bail!("Authors need a name");
+    }
+
+    let clashing = Author::find()
+        .filter(author::Column::Name.eq(name))
+        .filter(author::Column::Id.ne(id.unwrap_or(-1)))
+        .count(db)
+        .await?;
+
+    if clashing > 0 {
+        bail!("There's already an author called {name}");
+    }
+
+    let model = author::ActiveModel {
+        id: id.map_or(NotSet, Set),
+        name: Set(name.to_owned()),
+        image: Set(image.to_owned()),
+        url: Set(url.to_owned()),
+        avatar: NotSet,
+        avatar_e_tag: NotSet,
+        is_default: NotSet,
+    };
+
+    let saved = if id.is_some() {
+        model.update(db).await?
+    } else {
+        model.insert(db).await?
+    };
+
+    Ok(saved.id)
+}
+
+/// Deletes an author, as long as they haven't written anything.
+pub async fn delete_author(db: &DatabaseConnection, id: i64) -> color_eyre::Result<()> {
+    let recipes = Recipe::find()
+        .filter(recipe::Column::AuthorId.eq(id))
+        .count(db)
+        .await?;
+
+    if recipes > 0 {
+        bail!("This author still has {recipes} recipes, including ones in the trash");
+    }
+
+    Author::delete_by_id(id).exec(db).await?;
+
+    Ok(())
+}
+
+/// Makes an author the one new recipes are written by.
+pub async fn set_default_author(db: &DatabaseConnection, id: i64) -> color_eyre::Result<()> {
+    Author::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_eyre("Author not found")?;
+
+    Author::update_many()
+        .col_expr(author::Column::IsDefault, Expr::value(false))
+        .filter(author::Column::Id.ne(id))
+        .exec(db)
+        .await?;
+
+    Author::update_many()
+        .col_expr(author::Column::IsDefault, Expr::value(true))
+        .filter(author::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Stores an uploaded avatar, cropped square and shrunk down, since it's only
+/// ever shown small.
+pub async fn set_avatar(db: &DatabaseConnection, id: i64, data: Vec<u8>) -> color_eyre::Result<()> {
+    if data.len() > super::images::MAX_UPLOAD_BYTES {
+        bail!("Avatar is too large");
+    }
+
+    let (_, image) = super::images::decode_oriented(&data)?;
+    let image = image.resize_to_fill(AVATAR_SIZE, AVATAR_SIZE, FilterType::Lanczos3);
+
+    let mut avatar = Vec::new();
+    image
+        .write_to(Cursor::new(&mut avatar), ImageFormat::Png)
+        .context("Encoding avatar")?;
+
+    let etag = crate::etag::from_content(&avatar);
+
+    Author::update_many()
+        .col_expr(author::Column::Avatar, Expr::value(avatar))
+        .col_expr(author::Column::AvatarETag, Expr::value(etag))
+        .filter(author::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn remove_avatar(db: &DatabaseConnection, id: i64) -> color_eyre::Result<()> {
+    Author::update_many()
+        .col_expr(author::Column::Avatar, Expr::value(Option::<Vec<u8>>::None))
+        .col_expr(
+            author::Column::AvatarETag,
+            Expr::value(Option::<String>::None),
+        )
+        .filter(author::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// An author's uploaded avatar as PNG, along with its etag, or `None` if
+/// there's no such author or they haven't uploaded one.
+pub async fn get_avatar(
+    db: &DatabaseConnection,
+    id: i64,
+) -> color_eyre::Result<Option<(Vec<u8>, String)>> {
+    let author = Author::find_by_id(id).one(db).await?;
+
+    Ok(author.and_then(|a| a.avatar.zip(a.avatar_e_tag)))
+}
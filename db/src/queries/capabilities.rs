@@ -0,0 +1,215 @@
+use std::collections::{BTreeSet, HashMap};
+
+use color_eyre::eyre::WrapErr as _;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ColumnTrait as _, DatabaseConnection, EntityTrait as _, QueryFilter, QuerySelect};
+use sea_orm::{QueryOrder as _, TransactionTrait as _, prelude::Json};
+use types::schema::{self, SettingSchema, SettingValueKind};
+
+use crate::entities::{capability_schema, prelude::*, recipe};
+
+#[derive(Default)]
+struct SettingTally {
+    occurrences: u64,
+    kinds: BTreeSet<SettingValueKind>,
+    units: Vec<types::ReferenceUnit>,
+    nominal_value_ids: BTreeSet<String>,
+    min: Option<f64>,
+    max: Option<f64>,
+    /// How often each distinct value is used, keyed by `value_key`
+    values: HashMap<String, (u64, types::SettingValue)>,
+}
+
+struct CapabilityTally {
+    capability: types::ReferenceCapability,
+    occurrences: u64,
+    phases: BTreeSet<String>,
+    settings: HashMap<types::ReferenceSettingId, SettingTally>,
+}
+
+/// Identifies a value regardless of how its text is written.
+fn value_key(value: &types::SettingValue) -> String {
+    match value {
+        types::SettingValue::Numeric {
+            reference_unit,
+            value,
+            ..
+        } => format!(
+            "{value}{}",
+            reference_unit.as_ref().map_or("", |u| u.id.as_str())
+        ),
+        types::SettingValue::Boolean { value, .. } => value.to_string(),
+        types::SettingValue::Nominal {
+            reference_value, ..
+        } => reference_value.id.clone(),
+    }
+}
+
+fn tally_setting(tally: &mut SettingTally, setting: &types::CapabilitySetting) {
+    tally.occurrences += 1;
+    tally.kinds.insert(setting.value.kind());
+
+    match &setting.value {
+        types::SettingValue::Numeric {
+            reference_unit,
+            value,
+            ..
+        } => {
+            tally.min = Some(tally.min.map_or(*value, |m| m.min(*value)));
+            tally.max = Some(tally.max.map_or(*value, |m| m.max(*value)));
+
+            if let Some(unit) = reference_unit
+                && !tally.units.iter().any(|u| u.id == unit.id)
+            {
+                tally.units.push(unit.clone());
+            }
+        }
+        types::SettingValue::Nominal {
+            reference_value, ..
+        } => {
+            tally.nominal_value_ids.insert(reference_value.id.clone());
+        }
+        types::SettingValue::Boolean { .. } => {}
+    }
+
+    tally
+        .values
+        .entry(value_key(&setting.value))
+        .or_insert_with(|| (0, setting.value.clone()))
+        .0 += 1;
+}
+
+fn into_schema(tally: CapabilityTally) -> schema::CapabilitySchema {
+    let mut settings = tally
+        .settings
+        .into_iter()
+        .map(|(id, s)| {
+            SettingSchema {
+                id,
+                occurrences: s.occurrences,
+                kinds: s.kinds.into_iter().collect(),
+                units: s.units,
+                nominal_value_ids: s.nominal_value_ids.into_iter().collect(),
+                min: s.min,
+                max: s.max,
+                // ties go to the smallest key, so relearning gives the same answer
+                typical: s
+                    .values
+                    .into_iter()
+                    .max_by(|(a_key, a), (b_key, b)| a.0.cmp(&b.0).then_with(|| b_key.cmp(a_key)))
+                    .map(|(_, (_, v))| v),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    settings.sort_by(|a, b| {
+        b.occurrences
+            .cmp(&a.occurrences)
+            .then_with(|| a.id.to_string().cmp(&b.id.to_string()))
+    });
+
+    schema::CapabilitySchema {
+        capability: tally.capability,
+        occurrences: tally.occurrences,
+        phases: tally.phases.into_iter().collect(),
+        settings,
+    }
+}
+
+/// Goes through every official recipe to learn which settings, values and
+/// phases each capability is used with, replacing what was learned before.
+pub async fn learn_capability_schemas(db: &DatabaseConnection) -> color_eyre::Result<()> {
+    let recipes = Recipe::find()
+        .select_only()
+        .column(recipe::Column::Steps)
+        .filter(recipe::Column::IsCustom.eq(false))
+        .into_tuple::<Json>()
+        .all(db)
+        .await?;
+
+    let mut tallies = HashMap::<String, CapabilityTally>::new();
+
+    for steps in recipes {
+        let steps: Vec<types::RecipeStep> =
+            serde_path_to_error::deserialize(steps).context("Deserializing steps")?;
+
+        for capability in steps.iter().filter_map(|s| s.capability.as_ref()) {
+            let tally = tallies
+                .entry(capability.reference_capability.id.clone())
+                .or_insert_with(|| CapabilityTally {
+                    capability: capability.reference_capability.clone(),
+                    occurrences: 0,
+                    phases: BTreeSet::new(),
+                    settings: HashMap::new(),
+                });
+
+            tally.occurrences += 1;
+            tally.phases.insert(capability.phase.id.clone());
+
+            for setting in &capability.settings {
+                tally_setting(
+                    tally
+                        .settings
+                        .entry(setting.reference_setting.id)
+                        .or_default(),
+                    setting,
+                );
+            }
+        }
+    }
+
+    let models = tallies
+        .into_values()
+        .map(into_schema)
+        .map(|s| -> color_eyre::Result<_> {
+            Ok(capability_schema::ActiveModel {
+                capability_id: Set(s.capability.id.clone()),
+                name: Set(s.capability.name.clone()),
+                occurrences: Set(s.occurrences as i64),
+                phases: Set(serde_json::to_value(&s.phases)?),
+                settings: Set(serde_json::to_value(&s.settings)?),
+            })
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    // so nothing reads the schemas while they're half replaced
+    let txn = db.begin().await?;
+
+    CapabilitySchema::delete_many().exec(&txn).await?;
+
+    if !models.is_empty() {
+        CapabilitySchema::insert_many(models).exec(&txn).await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// What's been learned about each capability, most used first.
+pub async fn list_capability_schemas(
+    db: &DatabaseConnection,
+) -> color_eyre::Result<Vec<schema::CapabilitySchema>> {
+    let schemas = CapabilitySchema::find()
+        .order_by_desc(capability_schema::Column::Occurrences)
+        .order_by_asc(capability_schema::Column::Name)
+        .all(db)
+        .await?;
+
+    schemas
+        .into_iter()
+        .map(|s| {
+            Ok(schema::CapabilitySchema {
+                capability: types::ReferenceCapability {
+                    id: s.capability_id,
+                    name: s.name,
+                },
+                occurrences: s.occurrences as u64,
+                phases: serde_path_to_error::deserialize(s.phases)
+                    .context("Deserializing phases")?,
+                settings: serde_path_to_error::deserialize(s.settings)
+                    .context("Deserializing settings")?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,322 @@
+use std::str::FromStr as _;
+
+use color_eyre::eyre::WrapErr as _;
+use color_eyre::eyre::bail;
+use migration::{Expr, OnConflict};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ColumnTrait as _, DatabaseConnection, EntityTrait as _, PaginatorTrait as _};
+use sea_orm::{QueryFilter, QueryOrder as _};
+use types::catalog::{Catalog, NominalValue as CatalogValue};
+
+use crate::entities::{capability, capability_phase, nominal_value, prelude::*, reference_setting};
+
+/// Everything the editor can pick between.
+pub async fn get_catalog(db: &DatabaseConnection) -> color_eyre::Result<Catalog> {
+    let capabilities = Capability::find()
+        .order_by_asc(capability::Column::Name)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|c| types::ReferenceCapability {
+            id: c.id,
+            name: c.name,
+        })
+        .collect();
+
+    let phases = CapabilityPhase::find()
+        .order_by_asc(capability_phase::Column::Name)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|p| {
+            Ok(types::CapabilityPhase {
+                can_follow_phases: serde_path_to_error::deserialize(p.can_follow_phases)
+                    .context("Deserializing phases it can follow")?,
+                id: p.id,
+                name: p.name,
+            })
+        })
+        .collect::<color_eyre::Result<_>>()?;
+
+    // unknown settings would have come from a newer version, so skip them
+    let settings = ReferenceSetting::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|s| {
+            Some(types::ReferenceSetting {
+                id: types::ReferenceSettingId::from_str(&s.id).ok()?,
+                name: s.name,
+            })
+        })
+        .collect();
+
+    let values = NominalValue::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|v| {
+            Some(CatalogValue {
+                setting: types::ReferenceSettingId::from_str(&v.setting_id).ok()?,
+                value: types::ReferenceValue {
+                    id: v.id,
+                    name: v.name,
+                },
+                position: v.position,
+            })
+        })
+        .collect();
+
+    let mut catalog = Catalog {
+        capabilities,
+        phases,
+        settings,
+        values,
+    };
+    catalog.sort_values();
+
+    Ok(catalog)
+}
+
+/// Fills in any part of the catalog that's still empty from the built in one,
+/// so a new database has something to pick from. Parts that have been edited
+/// are left alone.
+pub async fn seed_catalog(db: &DatabaseConnection) -> color_eyre::Result<()> {
+    let builtin = Catalog::builtin();
+
+    if Capability::find().count(db).await? == 0 {
+        insert_capabilities(db, &builtin.capabilities).await?;
+    }
+    if CapabilityPhase::find().count(db).await? == 0 {
+        insert_phases(db, &builtin.phases).await?;
+    }
+    if ReferenceSetting::find().count(db).await? == 0 {
+        ReferenceSetting::insert_many(builtin.settings.iter().map(|s| {
+            reference_setting::ActiveModel {
+                id: Set(s.id.to_string()),
+                name: Set(s.name.clone()),
+            }
+        }))
+        .on_conflict_do_nothing()
+        .exec(db)
+        .await?;
+    }
+    if NominalValue::find().count(db).await? == 0 {
+        insert_values(db, &builtin.values).await?;
+    }
+
+    // values seeded before they were put in order take the built in order
+    for value in &builtin.values {
+        NominalValue::update_many()
+            .col_expr(nominal_value::Column::Position, Expr::value(value.position))
+            .filter(nominal_value::Column::SettingId.eq(value.setting.to_string()))
+            .filter(nominal_value::Column::Id.eq(&value.value.id))
+            .filter(nominal_value::Column::Position.is_null())
+            .exec(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Adds any of `capabilities` we don't know of yet.
+pub(crate) async fn insert_capabilities(
+    db: &DatabaseConnection,
+    capabilities: &[types::ReferenceCapability],
+) -> color_eyre::Result<()> {
+    if capabilities.is_empty() {
+        return Ok(());
+    }
+
+    Capability::insert_many(capabilities.iter().map(|c| capability::ActiveModel {
+        id: Set(c.id.clone()),
+        name: Set(c.name.clone()),
+    }))
+    .on_conflict_do_nothing()
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Adds any of `phases` we don't know of yet.
+pub(crate) async fn insert_phases(
+    db: &DatabaseConnection,
+    phases: &[types::CapabilityPhase],
+) -> color_eyre::Result<()> {
+    let models = phases
+        .iter()
+        .map(|p| -> color_eyre::Result<_> {
+            Ok(capability_phase::ActiveModel {
+                id: Set(p.id.clone()),
+                name: Set(p.name.clone()),
+                can_follow_phases: Set(serde_json::to_value(&p.can_follow_phases)?),
+            })
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    if models.is_empty() {
+        return Ok(());
+    }
+
+    CapabilityPhase::insert_many(models)
+        .on_conflict_do_nothing()
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Adds any of `values` we don't know of yet.
+pub(crate) async fn insert_values(
+    db: &DatabaseConnection,
+    values: &[CatalogValue],
+) -> color_eyre::Result<()> {
+    if values.is_empty() {
+        return Ok(());
+    }
+
+    NominalValue::insert_many(values.iter().map(|v| nominal_value::ActiveModel {
+        setting_id: Set(v.setting.to_string()),
+        id: Set(v.value.id.clone()),
+        name: Set(v.value.name.clone()),
+        position: Set(v.position),
+    }))
+    .on_conflict_do_nothing()
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+fn check_entry(id: &str, name: &str) -> color_eyre::Result<()> {
+    if id.trim().is_empty() {
+        bail!("An id is needed");
+    }
+    if name.trim().is_empty() {
+        bail!("A name is needed");
+    }
+
+    Ok(())
+}
+
+/// Adds a capability, or renames it if it's already there.
+pub async fn save_capability(
+    db: &DatabaseConnection,
+    capability: types::ReferenceCapability,
+) -> color_eyre::Result<()> {
+    check_entry(&capability.id, &capability.name)?;
+
+    Capability::insert(capability::ActiveModel {
+        id: Set(capability.id.trim().to_owned()),
+        name: Set(capability.name.trim().to_owned()),
+    })
+    .on_conflict(
+        OnConflict::column(capability::Column::Id)
+            .update_column(capability::Column::Name)
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Takes a capability out of the catalog. Recipes keep their own copy of the
+/// capabilities they use, so they aren't affected.
+pub async fn delete_capability(db: &DatabaseConnection, id: &str) -> color_eyre::Result<()> {
+    Capability::delete_by_id(id).exec(db).await?;
+
+    Ok(())
+}
+
+/// Adds a phase, or updates it if it's already there.
+pub async fn save_phase(
+    db: &DatabaseConnection,
+    phase: types::CapabilityPhase,
+) -> color_eyre::Result<()> {
+    check_entry(&phase.id, &phase.name)?;
+
+    CapabilityPhase::insert(capability_phase::ActiveModel {
+        id: Set(phase.id.trim().to_owned()),
+        name: Set(phase.name.trim().to_owned()),
+        can_follow_phases: Set(serde_json::to_value(&phase.can_follow_phases)?),
+    })
+    .on_conflict(
+        OnConflict::column(capability_phase::Column::Id)
+            .update_columns([
+                capability_phase::Column::Name,
+                capability_phase::Column::CanFollowPhases,
+            ])
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_phase(db: &DatabaseConnection, id: &str) -> color_eyre::Result<()> {
+    CapabilityPhase::delete_by_id(id).exec(db).await?;
+
+    Ok(())
+}
+
+/// Changes what a setting is called in the editor.
+pub async fn rename_setting(
+    db: &DatabaseConnection,
+    id: types::ReferenceSettingId,
+    name: &str,
+) -> color_eyre::Result<()> {
+    check_entry(&id.to_string(), name)?;
+
+    ReferenceSetting::insert(reference_setting::ActiveModel {
+        id: Set(id.to_string()),
+        name: Set(name.trim().to_owned()),
+    })
+    .on_conflict(
+        OnConflict::column(reference_setting::Column::Id)
+            .update_column(reference_setting::Column::Name)
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Adds a value a setting can take, or renames and moves it if it's already
+/// there.
+pub async fn save_value(db: &DatabaseConnection, value: CatalogValue) -> color_eyre::Result<()> {
+    check_entry(&value.value.id, &value.value.name)?;
+
+    NominalValue::insert(nominal_value::ActiveModel {
+        setting_id: Set(value.setting.to_string()),
+        id: Set(value.value.id.trim().to_owned()),
+        name: Set(value.value.name.trim().to_owned()),
+        position: Set(value.position),
+    })
+    .on_conflict(
+        OnConflict::columns([nominal_value::Column::SettingId, nominal_value::Column::Id])
+            .update_columns([nominal_value::Column::Name, nominal_value::Column::Position])
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_value(
+    db: &DatabaseConnection,
+    setting: types::ReferenceSettingId,
+    id: &str,
+) -> color_eyre::Result<()> {
+    NominalValue::delete_many()
+        .filter(nominal_value::Column::SettingId.eq(setting.to_string()))
+        .filter(nominal_value::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
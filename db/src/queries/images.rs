@@ -1,13 +1,57 @@
+use std::{collections::HashSet, env, io::Cursor, sync::LazyLock};
+
+use ::image::{DynamicImage, GenericImageView as _, ImageDecoder as _, ImageFormat};
 use color_eyre::eyre::OptionExt as _;
-use migration::OnConflict;
-use sea_orm::EntityLoaderTrait as _;
-use sea_orm::EntityTrait as _;
-use sea_orm::{ActiveValue::Set, DatabaseConnection};
-use sea_orm::{
-    ColumnTrait as _, Condition, DerivePartialModel, EntityLoaderTrait, QueryFilter, QuerySelect,
+use color_eyre::eyre::WrapErr as _;
+use color_eyre::eyre::bail;
+use migration::Expr;
+use rand::distr::SampleString as _;
+use sea_orm::prelude::{DateTimeUtc, Json};
+use sea_orm::sea_query::ExprTrait as _;
+use sea_orm::{ActiveValue::Set, ConnectionTrait, DatabaseConnection};
+use sea_orm::{ColumnTrait as _, EntityTrait as _, FromQueryResult, QueryOrder as _};
+use sea_orm::{DerivePartialModel, QueryFilter, QuerySelect};
+use tracing::debug;
+
+use crate::entities::sea_orm_active_enums::ImageKind;
+use crate::entities::{
+    image, image_variant, placeholder_image, prelude::*, recipe, recipe_revision,
 };
 
-use crate::entities::{image, prelude::*, recipe};
+/// Largest upload we'll accept, in bytes.
+pub const MAX_UPLOAD_BYTES: usize = 32 * 1024 * 1024;
+
+/// Largest width or height we'll accept for an upload, in pixels.
+pub const MAX_DIMENSION: u32 = 8192;
+
+/// Formats we accept uploads in and can serve, in the order we prefer to
+/// serve them when the client has no preference.
+const SERVABLE_FORMATS: [ImageFormat; 3] = [ImageFormat::WebP, ImageFormat::Jpeg, ImageFormat::Png];
+
+/// How many of the most requested sizes to render up front when an image is
+/// uploaded.
+const PREGENERATED_SIZES: u64 = 8;
+
+/// The sizes the longer side of resized images and placeholders is rendered
+/// at. Requests are scaled up for it to land on the next of these, so clients
+/// can't fill the cache with every size in between.
+const RENDER_SIZES: [u32; 25] = [
+    16, 32, 48, 64, 96, 128, 160, 192, 256, 320, 384, 480, 512, 640, 768, 800, 960, 1024, 1280,
+    1440, 1600, 1920, 2048, 2560, 3840,
+];
+
+/// How stale a cached render's last use may get before a hit records it again,
+/// so hot renders don't cost a write on every request.
+pub(super) const LAST_USED_RESOLUTION: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+/// Upper bound on the total size of the resized image and placeholder caches
+/// together, in bytes.
+static RENDER_CACHE_MAX_BYTES: LazyLock<i64> = LazyLock::new(|| {
+    env::var("IMAGE_CACHE_MAX_BYTES")
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(128 * 1024 * 1024)
+});
 
 #[derive(DerivePartialModel)]
 #[sea_orm(entity = "recipe::Entity")]
@@ -15,45 +59,794 @@ struct RecipeIdOnly {
     id: String,
 }
 
-pub async fn get_image(db: &DatabaseConnection, image_id: &str) -> color_eyre::Result<Vec<u8>> {
+pub struct StoredImage {
+    pub id: String,
+    pub recipe_id: String,
+    /// The bytes exactly as they were uploaded
+    pub data: Vec<u8>,
+    pub content_type: String,
+    /// Dimensions after applying EXIF orientation, missing for images
+    /// uploaded before we recorded them
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub file_name: Option<String>,
+    pub focal_x: f64,
+    pub focal_y: f64,
+    pub etag: String,
+    pub modified_at: Option<DateTimeUtc>,
+}
+
+impl StoredImage {
+    pub fn format(&self) -> Option<ImageFormat> {
+        ImageFormat::from_mime_type(&self.content_type)
+    }
+}
+
+impl From<image::Model> for StoredImage {
+    fn from(image: image::Model) -> Self {
+        // images stored before we tracked etags won't have one
+        let etag = if image.e_tag.is_empty() {
+            image_etag(&image.data, image.focal_x, image.focal_y)
+        } else {
+            image.e_tag
+        };
+
+        StoredImage {
+            id: image.id,
+            recipe_id: image.recipe_id,
+            data: image.data,
+            content_type: image.content_type,
+            width: image.width.map(|x| x as u32),
+            height: image.height.map(|x| x as u32),
+            file_name: image.file_name,
+            focal_x: image.focal_x,
+            focal_y: image.focal_y,
+            etag,
+            modified_at: image.modified_at,
+        }
+    }
+}
+
+/// The focal point changes how the image gets cropped, so it's part of the
+/// etag along with the image itself.
+fn image_etag(data: &[u8], focal_x: f64, focal_y: f64) -> String {
+    crate::etag::from_content((data, focal_x.to_bits(), focal_y.to_bits()))
+}
+
+/// Decodes an image, turning it the right way up according to its EXIF
+/// orientation.
+pub(super) fn decode_oriented(data: &[u8]) -> color_eyre::Result<(ImageFormat, DynamicImage)> {
+    let reader = ::image::ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .context("Guessing image format")?;
+
+    let format = reader
+        .format()
+        .ok_or_eyre("Unrecognised image format, expected a JPEG, PNG or WebP")?;
+
+    if !SERVABLE_FORMATS.contains(&format) {
+        bail!("Unsupported image format {format:?}, expected a JPEG, PNG or WebP");
+    }
+
+    let mut decoder = reader
+        .into_decoder()
+        .wrap_err_with(|| format!("Couldn't read {format:?} image"))?;
+
+    let (width, height) = decoder.dimensions();
+    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        bail!("Image is {width}x{height}, the largest allowed is {MAX_DIMENSION}x{MAX_DIMENSION}");
+    }
+
+    let orientation = decoder.orientation().context("Reading image orientation")?;
+    let mut image = DynamicImage::from_decoder(decoder)
+        .wrap_err_with(|| format!("Couldn't decode {format:?} image"))?;
+    image.apply_orientation(orientation);
+
+    Ok((format, image))
+}
+
+/// Picks which format to serve an image in from an `Accept` header.
+///
+/// Ties go to `preferred` and then to the order of [`SERVABLE_FORMATS`], and
+/// we fall back to webp rather than refusing if nothing is acceptable.
+pub fn negotiate_format(accept: Option<&str>, preferred: Option<ImageFormat>) -> ImageFormat {
+    let ranges = accept
+        .unwrap_or("*/*")
+        .split(',')
+        .filter_map(|range| {
+            let mut params = range.split(';').map(str::trim);
+            let mime = params.next()?.to_ascii_lowercase();
+            let q = params
+                .find_map(|p| p.strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            Some((mime, q))
+        })
+        .collect::<Vec<_>>();
+
+    // the most specific matching range decides the quality for a format
+    let quality = |format: ImageFormat| {
+        let mime = format.to_mime_type();
+
+        [mime, "image/*", "*/*"]
+            .iter()
+            .find_map(|candidate| {
+                ranges
+                    .iter()
+                    .find(|(range, _)| range == candidate)
+                    .map(|(_, q)| *q)
+            })
+            .unwrap_or(0.0)
+    };
+
+    preferred
+        .into_iter()
+        .chain(SERVABLE_FORMATS)
+        .filter(|format| SERVABLE_FORMATS.contains(format))
+        .map(|format| (format, quality(format)))
+        .filter(|(_, q)| *q > 0.0)
+        .reduce(|best, next| if next.1 > best.1 { next } else { best })
+        .map(|(format, _)| format)
+        .unwrap_or(ImageFormat::WebP)
+}
+
+/// Gets the image shown as the hero of a recipe, which is the first one in the
+/// gallery if none has been picked.
+pub async fn get_hero_image(
+    db: &DatabaseConnection,
+    recipe_id: &str,
+) -> color_eyre::Result<StoredImage> {
     let recipe: RecipeIdOnly = recipe::Entity::find()
-        .filter(
-            Condition::any()
-                .add(recipe::Column::Id.eq(image_id))
-                .add(recipe::Column::ExposedId.eq(image_id)),
-        )
+        .filter(super::recipes::any_id_matches(recipe_id))
+        .filter(recipe::Column::DeletedAt.is_null())
         .into_partial_model()
         .one(db)
         .await?
         .ok_or_eyre("Image not found")?;
 
-    let recipe_id = recipe.id;
+    let image = Image::find()
+        .filter(image::Column::RecipeId.eq(recipe.id))
+        .filter(image::Column::Kind.eq(ImageKind::Gallery))
+        .order_by_desc(image::Column::IsHero)
+        .order_by_asc(image::Column::Position)
+        .one(db)
+        .await?
+        .ok_or_eyre("Image not found")?;
 
-    let image = Image::load()
-        .filter_by_id(recipe_id)
+    Ok(image.into())
+}
+
+pub async fn get_image(db: &DatabaseConnection, image_id: &str) -> color_eyre::Result<StoredImage> {
+    let image = Image::find_by_id(image_id)
         .one(db)
         .await?
         .ok_or_eyre("Image not found")?;
 
-    Ok(image.data)
+    Ok(image.into())
 }
 
-pub async fn set_image(
+/// Gets an image attached to a step or ingredient, making sure it belongs to
+/// the recipe and is the kind of image the route it was asked for through
+/// serves.
+pub async fn get_attached_image(
     db: &DatabaseConnection,
+    recipe_id: &str,
     image_id: &str,
+    kind: ImageKind,
+) -> color_eyre::Result<Option<StoredImage>> {
+    let image = Image::find_by_id(image_id)
+        .filter(image::Column::RecipeId.eq(recipe_id))
+        .filter(image::Column::Kind.eq(kind))
+        .one(db)
+        .await?;
+
+    Ok(image.map(Into::into))
+}
+
+pub async fn list_images(
+    db: &DatabaseConnection,
+    recipe_id: &str,
+) -> color_eyre::Result<Vec<types::RecipeImage>> {
+    let images = Image::find()
+        .select_only()
+        .columns([
+            image::Column::Id,
+            image::Column::IsHero,
+            image::Column::FocalX,
+            image::Column::FocalY,
+            image::Column::Width,
+            image::Column::Height,
+            image::Column::FileName,
+        ])
+        .filter(image::Column::RecipeId.eq(recipe_id))
+        .filter(image::Column::Kind.eq(ImageKind::Gallery))
+        .order_by_asc(image::Column::Position)
+        .into_model::<GalleryImage>()
+        .all(db)
+        .await?;
+
+    Ok(images
+        .into_iter()
+        .map(|i| types::RecipeImage {
+            id: i.id,
+            is_hero: i.is_hero,
+            focal_x: i.focal_x,
+            focal_y: i.focal_y,
+            width: i.width.map(|x| x as u32),
+            height: i.height.map(|x| x as u32),
+            file_name: i.file_name,
+        })
+        .collect())
+}
+
+#[derive(FromQueryResult)]
+struct GalleryImage {
+    id: String,
+    is_hero: bool,
+    focal_x: f64,
+    focal_y: f64,
+    width: Option<i64>,
+    height: Option<i64>,
+    file_name: Option<String>,
+}
+
+/// Adds an uploaded image to a recipe as is, after checking that we can decode
+/// it and that it's within our limits. Gallery images go on the end of the
+/// gallery, and the first one a recipe gets becomes its hero.
+pub async fn add_image(
+    db: &DatabaseConnection,
+    recipe_id: &str,
+    kind: ImageKind,
     data: Vec<u8>,
-) -> color_eyre::Result<()> {
+    file_name: Option<String>,
+) -> color_eyre::Result<String> {
+    if data.len() > MAX_UPLOAD_BYTES {
+        bail!(
+            "Image is {} MiB, the largest allowed is {} MiB",
+            data.len() / (1024 * 1024),
+            MAX_UPLOAD_BYTES / (1024 * 1024)
+        );
+    }
+
+    let (format, decoded) = decode_oriented(&data)?;
+
+    let last = Image::find()
+        .filter(image::Column::RecipeId.eq(recipe_id))
+        .filter(image::Column::Kind.eq(kind))
+        .order_by_desc(image::Column::Position)
+        .one(db)
+        .await?;
+
+    let id = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 10);
+
     Image::insert(image::ActiveModel {
-        id: Set(image_id.to_owned()),
+        id: Set(id.clone()),
+        recipe_id: Set(recipe_id.to_owned()),
+        position: Set(last.as_ref().map_or(0, |i| i.position + 1)),
+        is_hero: Set(kind == ImageKind::Gallery && last.is_none()),
+        kind: Set(kind),
+        focal_x: Set(0.5),
+        focal_y: Set(0.5),
+        e_tag: Set(image_etag(&data, 0.5, 0.5)),
+        modified_at: Set(Some(chrono::Utc::now())),
+        content_type: Set(format.to_mime_type().to_owned()),
+        width: Set(Some(decoded.width() as i64)),
+        height: Set(Some(decoded.height() as i64)),
+        file_name: Set(file_name),
         data: Set(data),
     })
-    .on_conflict(
-        OnConflict::column(image::Column::Id)
-            .update_column(image::Column::Data)
-            .to_owned(),
-    )
     .exec(db)
     .await?;
 
+    Ok(id)
+}
+
+pub async fn set_hero_image(
+    db: &DatabaseConnection,
+    recipe_id: &str,
+    image_id: &str,
+) -> color_eyre::Result<()> {
+    Image::update_many()
+        .col_expr(
+            image::Column::IsHero,
+            Expr::col(image::Column::Id).eq(image_id),
+        )
+        .filter(image::Column::RecipeId.eq(recipe_id))
+        .filter(image::Column::Kind.eq(ImageKind::Gallery))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Moves where crops of an image are centred, given as fractions of its width
+/// and height.
+pub async fn set_focal_point(
+    db: &DatabaseConnection,
+    image_id: &str,
+    focal_x: f64,
+    focal_y: f64,
+) -> color_eyre::Result<()> {
+    let focal_x = focal_x.clamp(0.0, 1.0);
+    let focal_y = focal_y.clamp(0.0, 1.0);
+
+    let image = Image::find_by_id(image_id)
+        .one(db)
+        .await?
+        .ok_or_eyre("Image not found")?;
+
+    Image::update(image::ActiveModel {
+        id: Set(image.id),
+        focal_x: Set(focal_x),
+        focal_y: Set(focal_y),
+        e_tag: Set(image_etag(&image.data, focal_x, focal_y)),
+        modified_at: Set(Some(chrono::Utc::now())),
+        ..Default::default()
+    })
+    .exec(db)
+    .await?;
+
+    // everything we rendered was cropped around the old point
+    ImageVariant::delete_many()
+        .filter(image_variant::Column::ImageId.eq(image_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Puts a recipe's gallery in the order of `image_ids`.
+pub async fn reorder_images(
+    db: &DatabaseConnection,
+    recipe_id: &str,
+    image_ids: &[String],
+) -> color_eyre::Result<()> {
+    for (position, image_id) in image_ids.iter().enumerate() {
+        Image::update_many()
+            .col_expr(image::Column::Position, Expr::value(position as i64))
+            .filter(image::Column::RecipeId.eq(recipe_id))
+            .filter(image::Column::Id.eq(image_id))
+            .exec(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Removes an image from its gallery, handing the hero spot to the first
+/// remaining image if it had it.
+pub async fn delete_image(db: &DatabaseConnection, image_id: &str) -> color_eyre::Result<()> {
+    let Some(image) = Image::find_by_id(image_id).one(db).await? else {
+        return Ok(());
+    };
+
+    ImageVariant::delete_many()
+        .filter(image_variant::Column::ImageId.eq(image_id))
+        .exec(db)
+        .await?;
+
+    Image::delete_by_id(image_id).exec(db).await?;
+
+    if image.is_hero {
+        let next = Image::find()
+            .filter(image::Column::RecipeId.eq(&image.recipe_id))
+            .filter(image::Column::Kind.eq(ImageKind::Gallery))
+            .order_by_asc(image::Column::Position)
+            .one(db)
+            .await?;
+
+        if let Some(next) = next {
+            set_hero_image(db, &image.recipe_id, &next.id).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Deletes the step and ingredient images of a recipe that nothing uses any
+/// more, along with everything rendered from them. Images its revisions still
+/// show are kept, so restoring one doesn't bring back broken images.
+pub(crate) async fn prune_attached_images(
+    db: &impl ConnectionTrait,
+    recipe: &types::Recipe,
+) -> color_eyre::Result<()> {
+    let attached: Vec<String> = Image::find()
+        .select_only()
+        .column(image::Column::Id)
+        .filter(image::Column::RecipeId.eq(&recipe.id))
+        .filter(image::Column::Kind.is_in([ImageKind::Step, ImageKind::Ingredient]))
+        .into_tuple()
+        .all(db)
+        .await?;
+    if attached.is_empty() {
+        return Ok(());
+    }
+
+    let snapshots: Vec<Json> = RecipeRevision::find()
+        .select_only()
+        .column(recipe_revision::Column::Snapshot)
+        .filter(recipe_revision::Column::RecipeId.eq(&recipe.id))
+        .into_tuple()
+        .all(db)
+        .await?;
+    let revisions = snapshots
+        .into_iter()
+        .map(|s| serde_path_to_error::deserialize(s).context("Deserializing revision"))
+        .collect::<color_eyre::Result<Vec<types::Recipe>>>()?;
+
+    let used = std::iter::once(recipe)
+        .chain(&revisions)
+        .flat_map(|r| {
+            let steps = r.steps.iter().flat_map(|s| &s.media);
+            steps.chain(r.ingredients.iter().flat_map(|i| &i.media))
+        })
+        .map(|m| m.id.as_str())
+        .collect::<HashSet<_>>();
+    let orphaned = attached
+        .into_iter()
+        .filter(|id| !used.contains(id.as_str()))
+        .collect::<Vec<_>>();
+    if orphaned.is_empty() {
+        return Ok(());
+    }
+
+    debug!(recipe_id = recipe.id, images = ?orphaned, "Deleting unused images");
+
+    ImageVariant::delete_many()
+        .filter(image_variant::Column::ImageId.is_in(orphaned.clone()))
+        .exec(db)
+        .await?;
+    Image::delete_many()
+        .filter(image::Column::Id.is_in(orphaned))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Like `resize_to_fill`, but crops around the focal point rather than the
+/// centre.
+fn resize_to_fill_at(
+    image: &DynamicImage,
+    width: u32,
+    height: u32,
+    focal_x: f64,
+    focal_y: f64,
+) -> DynamicImage {
+    let (source_width, source_height) = image.dimensions();
+
+    let scale = f64::max(
+        width as f64 / source_width as f64,
+        height as f64 / source_height as f64,
+    );
+    let crop_width = ((width as f64 / scale).round() as u32).clamp(1, source_width);
+    let crop_height = ((height as f64 / scale).round() as u32).clamp(1, source_height);
+
+    let crop_x = (focal_x * source_width as f64 - crop_width as f64 / 2.0)
+        .clamp(0.0, (source_width - crop_width) as f64) as u32;
+    let crop_y = (focal_y * source_height as f64 - crop_height as f64 / 2.0)
+        .clamp(0.0, (source_height - crop_height) as f64) as u32;
+
+    image
+        .crop_imm(crop_x, crop_y, crop_width, crop_height)
+        .resize_exact(width, height, ::image::imageops::FilterType::Triangle)
+}
+
+/// Decodes `image` and resizes it to fill `width`x`height`.
+fn render_variant(
+    image: &StoredImage,
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+) -> color_eyre::Result<Vec<u8>> {
+    if width == 0 || height == 0 {
+        bail!("Can't resize an image to {width}x{height}");
+    }
+
+    let (_, decoded) = decode_oriented(&image.data)?;
+
+    let mut resized = resize_to_fill_at(&decoded, width, height, image.focal_x, image.focal_y);
+
+    // jpeg has no alpha channel
+    if format == ImageFormat::Jpeg {
+        resized = DynamicImage::ImageRgb8(resized.to_rgb8());
+    }
+
+    let mut data = Vec::new();
+    resized
+        .write_to(Cursor::new(&mut data), format)
+        .context("Converting image")?;
+
+    Ok(data)
+}
+
+/// The size a `width`x`height` request is rendered at: scaled for the longer
+/// side to land on the next of [`RENDER_SIZES`], keeping the aspect ratio.
+pub fn render_size(width: u32, height: u32) -> (u32, u32) {
+    if width == 0 || height == 0 {
+        return (width, height);
+    }
+
+    let longer = std::cmp::max(width, height);
+    let snapped = RENDER_SIZES
+        .into_iter()
+        .find(|s| *s >= longer)
+        .unwrap_or(RENDER_SIZES[RENDER_SIZES.len() - 1]);
+    let scale = |side: u32| {
+        std::cmp::max(
+            (side as u64 * snapped as u64 + longer as u64 / 2) / longer as u64,
+            1,
+        ) as u32
+    };
+
+    (scale(width), scale(height))
+}
+
+/// Gets `image` resized to fill about `width`x`height`, scaled up to one of
+/// the sizes we render at, rendering and caching it if that size hasn't been
+/// asked for since the image last changed.
+pub async fn get_resized(
+    db: &DatabaseConnection,
+    image: &StoredImage,
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+) -> color_eyre::Result<Vec<u8>> {
+    let (width, height) = render_size(width, height);
+
+    get_variant(db, image, width, height, format).await
+}
+
+/// Gets `image` resized to fill exactly `width`x`height`, from the cache or
+/// freshly rendered.
+async fn get_variant(
+    db: &DatabaseConnection,
+    image: &StoredImage,
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+) -> color_eyre::Result<Vec<u8>> {
+    let key = (
+        image.id.clone(),
+        width as i64,
+        height as i64,
+        format.to_mime_type().to_owned(),
+    );
+
+    if let Some(variant) = ImageVariant::find_by_id(key.clone()).one(db).await? {
+        debug!(
+            image_id = image.id,
+            width, height, "Resized image cache hit"
+        );
+
+        let now = chrono::Utc::now();
+        if now - variant.last_used_at > LAST_USED_RESOLUTION {
+            ImageVariant::update_many()
+                .col_expr(image_variant::Column::LastUsedAt, Expr::value(now))
+                .filter(image_variant::Column::ImageId.eq(&image.id))
+                .filter(image_variant::Column::Width.eq(key.1))
+                .filter(image_variant::Column::Height.eq(key.2))
+                .filter(image_variant::Column::Format.eq(&key.3))
+                .exec(db)
+                .await?;
+        }
+
+        return Ok(variant.data);
+    }
+
+    debug!(
+        image_id = image.id,
+        width, height, "Resized image cache miss"
+    );
+
+    let data = render_variant(image, width, height, format)?;
+
+    ImageVariant::insert(image_variant::ActiveModel {
+        image_id: Set(key.0),
+        width: Set(key.1),
+        height: Set(key.2),
+        format: Set(key.3),
+        data: Set(data.clone()),
+        last_used_at: Set(chrono::Utc::now()),
+    })
+    .on_conflict_do_nothing()
+    .exec(db)
+    .await?;
+
+    evict_renders(db).await?;
+
+    Ok(data)
+}
+
+/// Gets `image` at its original size in `format`, only re-encoding it if it
+/// wasn't uploaded in that format.
+pub async fn get_converted(
+    db: &DatabaseConnection,
+    image: &StoredImage,
+    format: ImageFormat,
+) -> color_eyre::Result<Vec<u8>> {
+    if image.format() == Some(format) {
+        return Ok(image.data.clone());
+    }
+
+    let (width, height) = match (image.width, image.height) {
+        (Some(width), Some(height)) => (width, height),
+        _ => decode_oriented(&image.data)?.1.dimensions(),
+    };
+
+    // only ever the one size, so needn't be rounded
+    get_variant(db, image, width, height, format).await
+}
+
+#[derive(FromQueryResult)]
+struct VariantSize {
+    image_id: String,
+    width: i64,
+    height: i64,
+    format: String,
+    size: i64,
+    last_used_at: DateTimeUtc,
+}
+
+#[derive(FromQueryResult)]
+struct PlaceholderSize {
+    recipe_id: String,
+    width: i64,
+    height: i64,
+    format: String,
+    size: i64,
+    last_used_at: DateTimeUtc,
+}
+
+/// Something rendered and cached, to be evicted by.
+enum Render {
+    Variant(VariantSize),
+    Placeholder(PlaceholderSize),
+}
+
+impl Render {
+    fn size(&self) -> i64 {
+        match self {
+            Render::Variant(v) => v.size,
+            Render::Placeholder(p) => p.size,
+        }
+    }
+
+    fn last_used_at(&self) -> DateTimeUtc {
+        match self {
+            Render::Variant(v) => v.last_used_at,
+            Render::Placeholder(p) => p.last_used_at,
+        }
+    }
+}
+
+/// Drops the least recently used resized images and placeholders until
+/// between them they fit in `IMAGE_CACHE_MAX_BYTES`.
+pub(super) async fn evict_renders(db: &DatabaseConnection) -> color_eyre::Result<()> {
+    let variants = ImageVariant::find()
+        .select_only()
+        .columns([
+            image_variant::Column::ImageId,
+            image_variant::Column::Width,
+            image_variant::Column::Height,
+            image_variant::Column::Format,
+            image_variant::Column::LastUsedAt,
+        ])
+        .column_as(Expr::cust("length(data)"), "size")
+        .into_model::<VariantSize>()
+        .all(db)
+        .await?;
+    let placeholders = PlaceholderImage::find()
+        .select_only()
+        .columns([
+            placeholder_image::Column::RecipeId,
+            placeholder_image::Column::Width,
+            placeholder_image::Column::Height,
+            placeholder_image::Column::Format,
+            placeholder_image::Column::LastUsedAt,
+        ])
+        .column_as(Expr::cust("length(data)"), "size")
+        .into_model::<PlaceholderSize>()
+        .all(db)
+        .await?;
+
+    let mut renders = variants
+        .into_iter()
+        .map(Render::Variant)
+        .chain(placeholders.into_iter().map(Render::Placeholder))
+        .collect::<Vec<_>>();
+    renders.sort_by_key(|r| std::cmp::Reverse(r.last_used_at()));
+
+    let mut total = 0;
+
+    for render in renders {
+        total += render.size();
+
+        if total <= *RENDER_CACHE_MAX_BYTES {
+            continue;
+        }
+
+        match render {
+            Render::Variant(variant) => {
+                debug!(
+                    image_id = variant.image_id,
+                    width = variant.width,
+                    height = variant.height,
+                    "Evicting resized image"
+                );
+
+                ImageVariant::delete_by_id((
+                    variant.image_id,
+                    variant.width,
+                    variant.height,
+                    variant.format,
+                ))
+                .exec(db)
+                .await?;
+            }
+            Render::Placeholder(placeholder) => {
+                debug!(
+                    recipe_id = placeholder.recipe_id,
+                    width = placeholder.width,
+                    height = placeholder.height,
+                    "Evicting placeholder"
+                );
+
+                PlaceholderImage::delete_by_id((
+                    placeholder.recipe_id,
+                    placeholder.width,
+                    placeholder.height,
+                    placeholder.format,
+                ))
+                .exec(db)
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(FromQueryResult)]
+struct VariantDimensions {
+    width: i64,
+    height: i64,
+    format: String,
+}
+
+/// Renders the sizes the device asks for most often, so the first request for
+/// a freshly uploaded image doesn't have to wait on a resize.
+pub async fn pregenerate_variants(
+    db: &DatabaseConnection,
+    image_id: &str,
+) -> color_eyre::Result<()> {
+    let common = ImageVariant::find()
+        .select_only()
+        .columns([
+            image_variant::Column::Width,
+            image_variant::Column::Height,
+            image_variant::Column::Format,
+        ])
+        .filter(image_variant::Column::ImageId.ne(image_id))
+        .group_by(image_variant::Column::Width)
+        .group_by(image_variant::Column::Height)
+        .group_by(image_variant::Column::Format)
+        .order_by_desc(Expr::cust("count(*)"))
+        .limit(PREGENERATED_SIZES)
+        .into_model::<VariantDimensions>()
+        .all(db)
+        .await?;
+
+    let image = get_image(db, image_id).await?;
+
+    for size in common {
+        let Some(format) = ImageFormat::from_mime_type(&size.format) else {
+            continue;
+        };
+
+        get_variant(db, &image, size.width as u32, size.height as u32, format).await?;
+    }
+
     Ok(())
 }
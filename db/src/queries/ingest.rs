@@ -46,6 +46,7 @@ pub async fn insert_ingredients(
         entities::ingredient::ActiveModel {
             id: Set(i.id.clone()),
             name: Set(i.name.clone()),
+            density: NotSet,
         }
     }))
     .on_conflict_do_nothing()
@@ -120,6 +121,9 @@ pub async fn insert_recipes(db: &DatabaseConnection, recipes: &[types::Recipe])
         name: Set(a.name.clone()),
         image: Set(a.image.clone()),
         url: Set(a.image.clone()),
+        avatar: NotSet,
+        avatar_e_tag: NotSet,
+        is_default: NotSet,
     }))
     .exec(db)
     .await?;
@@ -148,11 +152,19 @@ pub async fn insert_recipes(db: &DatabaseConnection, recipes: &[types::Recipe])
             locale: Set(r.locale.clone()),
             created_at: Set(r.created_at),
             modified_at: Set(r.modified_at),
-            published_at: Set(r.published_at.expect("Imported recipes should be published")),
+            published_at: Set(r
+                .published_at
+                .expect("Imported recipes should be published")),
             created_by_id: Set(r.created_by_id.clone()),
             steps: Set(serde_json::to_value(&r.steps).unwrap()),
             ingredients: Set(serde_json::to_value(&r.ingredients).unwrap()),
             is_custom: Set(false),
+            deleted_at: NotSet,
+            state: Set(r.state.clone()),
+            visibility: Set(r.visibility.clone()),
+            fork_of: NotSet,
+            appliance: Set(r.appliance.clone()),
+            timing: Set(None),
         })
         .chunks(1000)
     {
@@ -164,3 +176,91 @@ pub async fn insert_recipes(db: &DatabaseConnection, recipes: &[types::Recipe])
 
     Ok(())
 }
+
+/// Keeps the tags of official recipes, which also fills the tag catalogue the
+/// editor picks from.
+pub async fn insert_recipe_tags(db: &DatabaseConnection, recipes: &[types::Recipe]) -> Result<()> {
+    super::tags::insert_tags(
+        db,
+        recipes
+            .iter()
+            .flat_map(|r| &r.reference_tags)
+            .unique_by(|t| &t.id),
+    )
+    .await?;
+
+    for chunk in &recipes
+        .iter()
+        .flat_map(|r| {
+            r.reference_tags
+                .iter()
+                .map(|t| entities::recipe_tag::ActiveModel {
+                    recipe_id: Set(r.id.clone()),
+                    tag_id: Set(t.id.clone()),
+                })
+        })
+        .chunks(1000)
+    {
+        entities::prelude::RecipeTag::insert_many(chunk)
+            .on_conflict_do_nothing()
+            .exec(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Adds the capabilities, phases and nominal values official recipes use to
+/// the catalog the editor picks from.
+pub async fn insert_reference_catalog(
+    db: &DatabaseConnection,
+    recipes: &[types::Recipe],
+) -> Result<()> {
+    let capabilities = recipes
+        .iter()
+        .flat_map(|r| &r.steps)
+        .filter_map(|s| s.capability.as_ref())
+        .collect::<Vec<_>>();
+
+    super::catalog::insert_capabilities(
+        db,
+        &capabilities
+            .iter()
+            .map(|c| c.reference_capability.clone())
+            .unique_by(|c| c.id.clone())
+            .collect::<Vec<_>>(),
+    )
+    .await?;
+
+    super::catalog::insert_phases(
+        db,
+        &capabilities
+            .iter()
+            .map(|c| c.phase.clone())
+            .unique_by(|p| p.id.clone())
+            .collect::<Vec<_>>(),
+    )
+    .await?;
+
+    let values = capabilities
+        .iter()
+        .flat_map(|c| &c.settings)
+        .filter_map(|s| match &s.value {
+            types::SettingValue::Nominal {
+                reference_value, ..
+            } => Some(types::catalog::NominalValue {
+                setting: s.reference_setting.id,
+                value: reference_value.clone(),
+                position: None,
+            }),
+            _ => None,
+        })
+        .unique_by(|v| (v.setting, v.value.id.clone()))
+        .collect::<Vec<_>>();
+
+    for chunk in values.chunks(1000) {
+        super::catalog::insert_values(db, chunk).await?;
+    }
+
+    Ok(())
+}
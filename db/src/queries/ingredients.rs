@@ -1,4 +1,8 @@
+use std::collections::HashMap;
+
 use color_eyre::eyre::OptionExt as _;
+use migration::Expr;
+use sea_orm::ColumnTrait as _;
 use sea_orm::DatabaseConnection;
 use sea_orm::EntityLoaderTrait;
 use sea_orm::EntityLoaderTrait as _;
@@ -6,7 +10,7 @@ use sea_orm::EntityTrait as _;
 use sea_orm::ModelTrait;
 use sea_orm::QueryFilter;
 
-use crate::entities::prelude::*;
+use crate::entities::{ingredient, prelude::*, unit};
 
 pub async fn list_ingredients(
     db: &DatabaseConnection,
@@ -38,6 +42,16 @@ pub async fn list_ingredients(
     Ok(ingredients)
 }
 
+fn to_allowed_unit(u: unit::Model) -> types::IngredientAllowedUnit {
+    types::IngredientAllowedUnit {
+        id: u.id,
+        name: u.name,
+        abbreviation: u.abbreviation,
+        dimension: u.dimension_id,
+        measurement_system: u.measurement_system_id,
+    }
+}
+
 pub async fn list_ingredient_allowed(
     db: &DatabaseConnection,
     id: &str,
@@ -48,17 +62,85 @@ pub async fn list_ingredient_allowed(
         .ok_or_eyre("Couldn't find ingredient")?;
     let allowed_model = ingredient.find_related(Unit).all(db).await?;
 
-    let allowed = allowed_model
+    let allowed = allowed_model.into_iter().map(to_allowed_unit).collect();
+
+    Ok(allowed)
+}
+
+/// The allowed units and densities of some ingredients, to convert their
+/// quantities with.
+pub async fn conversions(
+    db: &DatabaseConnection,
+    ids: Vec<String>,
+) -> color_eyre::Result<types::convert::Conversions> {
+    let ingredients = Ingredient::find()
+        .find_with_related(Unit)
+        .filter(ingredient::Column::Id.is_in(ids))
+        .all(db)
+        .await?;
+
+    let mut allowed_units = HashMap::new();
+    let mut densities = HashMap::new();
+    for (ingredient, units) in ingredients {
+        allowed_units.insert(
+            ingredient.id.clone(),
+            units.into_iter().map(to_allowed_unit).collect(),
+        );
+        if let Some(density) = ingredient.density {
+            densities.insert(ingredient.id, density);
+        }
+    }
+
+    Ok(types::convert::Conversions {
+        allowed_units,
+        densities,
+    })
+}
+
+/// The ingredients whose density is known.
+pub async fn list_densities(
+    db: &DatabaseConnection,
+) -> color_eyre::Result<Vec<types::convert::IngredientDensity>> {
+    let ingredients = Ingredient::find()
+        .filter(ingredient::Column::Density.is_not_null())
+        .order_by_id_asc()
+        .all(db)
+        .await?;
+
+    Ok(ingredients
         .into_iter()
-        .map(|u| {
-            Ok(types::IngredientAllowedUnit {
-                id: u.id,
-                name: u.name,
-                abbreviation: u.abbreviation,
-                dimension: u.dimension_id,
+        .filter_map(|i| {
+            Some(types::convert::IngredientDensity {
+                grams_per_ml: i.density?,
+                ingredient: types::Ingredient {
+                    id: i.id,
+                    name: i.name,
+                },
             })
         })
-        .collect::<color_eyre::Result<Vec<_>>>()?;
+        .collect())
+}
 
-    Ok(allowed)
+/// Sets how many grams a millilitre of an ingredient weighs, or forgets it.
+pub async fn set_density(
+    db: &DatabaseConnection,
+    id: &str,
+    grams_per_ml: Option<f64>,
+) -> color_eyre::Result<()> {
+    if grams_per_ml.is_some_and(|d| !d.is_finite() || d <= 0.0) {
+        color_eyre::eyre::bail!("Density has to be more than zero");
+    }
+
+    Ingredient::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_eyre("Couldn't find ingredient")?;
+
+    Ingredient::update_many()
+        .col_expr(ingredient::Column::Density, Expr::value(grams_per_ml))
+        .filter(ingredient::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+
+    Ok(())
 }
@@ -1,5 +1,12 @@
+pub mod appliances;
+pub mod authors;
+pub mod capabilities;
+pub mod catalog;
 pub mod images;
 pub mod ingest;
 pub mod ingredients;
+pub mod placeholders;
 pub mod preparations;
 pub mod recipes;
+pub mod revisions;
+pub mod tags;
@@ -0,0 +1,456 @@
+use std::{io::Cursor, sync::LazyLock};
+
+use ::image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use ab_glyph::{FontRef, PxScale};
+use color_eyre::eyre::WrapErr as _;
+use color_eyre::eyre::bail;
+use imageproc::drawing::{
+    draw_filled_circle_mut, draw_filled_ellipse_mut, draw_filled_rect_mut, draw_polygon_mut,
+    draw_text_mut, text_size,
+};
+use imageproc::{point::Point, rect::Rect};
+use migration::{Expr, OnConflict};
+use sea_orm::{ActiveValue::Set, DatabaseConnection, prelude::Json};
+use sea_orm::{ColumnTrait as _, DerivePartialModel, EntityTrait as _, QueryFilter};
+use tracing::debug;
+
+use super::images::MAX_DIMENSION;
+use crate::entities::{placeholder_image, prelude::*, recipe};
+
+/// Bump when changing how placeholders are drawn, so cached ones get redrawn.
+const RENDER_VERSION: u32 = 2;
+
+static FONT: LazyLock<FontRef<'static>> = LazyLock::new(|| {
+    FontRef::try_from_slice(include_bytes!("../../assets/DejaVuSans-Bold.ttf"))
+        .expect("Bundled font should parse")
+});
+
+const WHITE: Rgb<u8> = Rgb([255, 255, 255]);
+
+#[derive(DerivePartialModel)]
+#[sea_orm(entity = "recipe::Entity")]
+struct PlaceholderRecipe {
+    id: String,
+    name: String,
+    steps: Json,
+}
+
+/// What the badge on a placeholder shows, going by the kind of thing the
+/// recipe's main capability does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CapabilityIcon {
+    /// Stirring, beating, kneading and the like
+    Whisk,
+    /// Cooking on the heat
+    Pot,
+    Steam,
+    /// Baking and proving
+    Oven,
+}
+
+impl CapabilityIcon {
+    /// The icon for a capability, from the last part of its ID, e.g. `Knead`
+    /// for `cckg:Knead` and `AssistedStir` for `kitchenos:Kenwood:AssistedStir`.
+    fn for_capability(capability_id: &str) -> Option<Self> {
+        let kind = capability_id
+            .rsplit(':')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let matches = |words: &[&str]| words.iter().any(|w| kind.contains(w));
+
+        if matches(&["steam"]) {
+            Some(Self::Steam)
+        } else if matches(&["bake", "prove", "proof", "roast"]) {
+            Some(Self::Oven)
+        } else if matches(&[
+            "whisk", "beat", "stir", "mix", "knead", "fold", "blend", "cream",
+        ]) {
+            Some(Self::Whisk)
+        } else if matches(&[
+            "cook", "boil", "simmer", "fry", "saute", "sauté", "sear", "bechamel", "melt", "heat",
+        ]) {
+            Some(Self::Pot)
+        } else {
+            None
+        }
+    }
+}
+
+/// What a recipe's placeholder hero is drawn from.
+pub struct Placeholder {
+    pub recipe_id: String,
+    pub name: String,
+    /// Name of the capability most of the recipe's steps use
+    pub capability: Option<String>,
+    /// Its icon, if we have one for it. Otherwise the badge shows its initial.
+    pub icon: Option<CapabilityIcon>,
+    pub etag: String,
+}
+
+/// Finds what we need to draw a placeholder for a custom recipe, or `None` if
+/// we don't have the recipe.
+pub async fn find_placeholder(
+    db: &DatabaseConnection,
+    recipe_id: &str,
+) -> color_eyre::Result<Option<Placeholder>> {
+    let Some(recipe) = Recipe::find()
+        .filter(super::recipes::any_id_matches(recipe_id))
+        .filter(recipe::Column::IsCustom.eq(true))
+        .filter(recipe::Column::DeletedAt.is_null())
+        .into_partial_model::<PlaceholderRecipe>()
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let steps: Vec<types::RecipeStep> =
+        serde_path_to_error::deserialize(recipe.steps).context("Deserializing steps")?;
+
+    let capabilities = steps
+        .iter()
+        .filter_map(|s| s.capability.as_ref())
+        .map(|c| &c.reference_capability)
+        .collect::<Vec<_>>();
+    let main = capabilities
+        .iter()
+        .max_by_key(|c| capabilities.iter().filter(|o| o.id == c.id).count());
+    let capability = main.map(|c| c.name.clone());
+    let icon = main.and_then(|c| CapabilityIcon::for_capability(&c.id));
+
+    let etag =
+        crate::etag::from_content((RENDER_VERSION, &recipe.id, &recipe.name, &capability, icon));
+
+    Ok(Some(Placeholder {
+        recipe_id: recipe.id,
+        name: recipe.name,
+        capability,
+        icon,
+        etag,
+    }))
+}
+
+/// Picks a colour from the recipe ID with FNV-1a, which unlike `DefaultHasher`
+/// won't change between builds.
+fn background_colour(recipe_id: &str) -> Rgb<u8> {
+    let hash = recipe_id.bytes().fold(0x811c9dc5u32, |h, b| {
+        (h ^ b as u32).wrapping_mul(0x01000193)
+    });
+
+    hsl_to_rgb((hash % 360) as f32, 0.45, 0.45)
+}
+
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> Rgb<u8> {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    Rgb([r, g, b].map(|v| ((v + m) * 255.0).round() as u8))
+}
+
+/// Breaks `text` into lines no wider than `max_width`, cutting it short with
+/// an ellipsis past `max_lines`.
+fn wrap_text(text: &str, scale: PxScale, max_width: u32, max_lines: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_owned()
+        } else {
+            format!("{current} {word}")
+        };
+
+        if current.is_empty() || text_size(scale, &*FONT, &candidate).0 <= max_width {
+            current = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_owned()));
+        }
+    }
+
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            last.push('…');
+        }
+    }
+
+    lines
+}
+
+/// A filled rectangle given relative to a badge's centre, in units of its
+/// radius.
+fn badge_rect(
+    canvas: &mut RgbImage,
+    (cx, cy): (f32, f32),
+    radius: f32,
+    (x, y, w, h): (f32, f32, f32, f32),
+    colour: Rgb<u8>,
+) {
+    let rect = Rect::at((cx + x * radius) as i32, (cy + y * radius) as i32)
+        .of_size((w * radius).max(1.0) as u32, (h * radius).max(1.0) as u32);
+    draw_filled_rect_mut(canvas, rect, colour);
+}
+
+/// Draws `icon` in `colour` inside a badge of `radius` around `centre`.
+fn draw_icon(
+    canvas: &mut RgbImage,
+    icon: CapabilityIcon,
+    centre: (f32, f32),
+    radius: f32,
+    colour: Rgb<u8>,
+) {
+    let (cx, cy) = centre;
+    let at = |x: f32, y: f32| Point::new((cx + x * radius) as i32, (cy + y * radius) as i32);
+    let r = |v: f32| ((v * radius) as i32).max(1);
+
+    match icon {
+        CapabilityIcon::Whisk => {
+            // balloon wires as rings, each cut out of the one before, over
+            // a handle
+            let wires = (cx as i32, (cy - 0.2 * radius) as i32);
+            for (width, height) in [(0.42, 0.5), (0.28, 0.46), (0.14, 0.42)] {
+                draw_filled_ellipse_mut(canvas, wires, r(width), r(height), colour);
+                draw_filled_ellipse_mut(canvas, wires, r(width - 0.07), r(height - 0.07), WHITE);
+            }
+            badge_rect(canvas, centre, radius, (-0.08, 0.25, 0.16, 0.5), colour);
+        }
+        CapabilityIcon::Pot | CapabilityIcon::Steam => {
+            // the steaming pot sits lower to leave room for the steam
+            let drop = if icon == CapabilityIcon::Steam {
+                0.2
+            } else {
+                0.0
+            };
+            badge_rect(
+                canvas,
+                centre,
+                radius,
+                (-0.5, -0.05 + drop, 1.0, 0.5),
+                colour,
+            );
+            badge_rect(
+                canvas,
+                centre,
+                radius,
+                (-0.62, -0.18 + drop, 1.24, 0.1),
+                colour,
+            );
+            badge_rect(
+                canvas,
+                centre,
+                radius,
+                (-0.75, 0.02 + drop, 0.2, 0.08),
+                colour,
+            );
+            badge_rect(
+                canvas,
+                centre,
+                radius,
+                (0.55, 0.02 + drop, 0.2, 0.08),
+                colour,
+            );
+
+            if icon == CapabilityIcon::Steam {
+                for x in [-0.3, 0.0, 0.3] {
+                    draw_polygon_mut(
+                        canvas,
+                        &[
+                            at(x - 0.05, -0.05),
+                            at(x - 0.12, -0.3),
+                            at(x - 0.02, -0.55),
+                            at(x + 0.06, -0.55),
+                            at(x - 0.04, -0.3),
+                            at(x + 0.03, -0.05),
+                        ],
+                        colour,
+                    );
+                }
+            } else {
+                badge_rect(canvas, centre, radius, (-0.1, -0.3, 0.2, 0.1), colour);
+            }
+        }
+        CapabilityIcon::Oven => {
+            badge_rect(canvas, centre, radius, (-0.6, -0.55, 1.2, 1.1), colour);
+            // the door's window, and a row of knobs above it
+            badge_rect(canvas, centre, radius, (-0.42, -0.15, 0.84, 0.52), WHITE);
+            for x in [-0.3, 0.0, 0.3] {
+                let knob = ((cx + x * radius) as i32, (cy - 0.35 * radius) as i32);
+                draw_filled_circle_mut(canvas, knob, r(0.07), WHITE);
+            }
+        }
+    }
+}
+
+fn render_placeholder(
+    placeholder: &Placeholder,
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+) -> color_eyre::Result<Vec<u8>> {
+    if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+        bail!("Can't draw a placeholder at {width}x{height}");
+    }
+
+    let background = background_colour(&placeholder.recipe_id);
+    let mut canvas = RgbImage::from_pixel(width, height, background);
+    let unit = width.min(height) as f32;
+
+    // the capability goes in a badge above the name
+    let mut text_top = height as f32 / 2.0;
+    if let Some(capability) = &placeholder.capability {
+        let radius = unit / 6.0;
+        let centre = (width as f32 / 2.0, height as f32 * 0.3);
+        draw_filled_circle_mut(
+            &mut canvas,
+            (centre.0 as i32, centre.1 as i32),
+            radius as i32,
+            WHITE,
+        );
+
+        match placeholder.icon {
+            Some(icon) => draw_icon(&mut canvas, icon, centre, radius, background),
+            None => {
+                let initial = capability
+                    .chars()
+                    .next()
+                    .map(|c| c.to_uppercase().to_string())
+                    .unwrap_or_default();
+                let scale = PxScale::from(radius * 1.2);
+                let (initial_width, initial_height) = text_size(scale, &*FONT, &initial);
+                draw_text_mut(
+                    &mut canvas,
+                    background,
+                    (centre.0 - initial_width as f32 / 2.0) as i32,
+                    (centre.1 - initial_height as f32 / 2.0) as i32,
+                    scale,
+                    &*FONT,
+                    &initial,
+                );
+            }
+        }
+
+        text_top = centre.1 + radius * 1.5;
+    }
+
+    let scale = PxScale::from((unit / 10.0).max(8.0));
+    let line_height = scale.y * 1.2;
+    let lines = wrap_text(&placeholder.name, scale, (width as f32 * 0.85) as u32, 3);
+    if placeholder.capability.is_none() {
+        text_top -= line_height * lines.len() as f32 / 2.0;
+    }
+
+    for (idx, line) in lines.iter().enumerate() {
+        let (line_width, _) = text_size(scale, &*FONT, line);
+        draw_text_mut(
+            &mut canvas,
+            WHITE,
+            ((width as f32 - line_width as f32) / 2.0) as i32,
+            (text_top + line_height * idx as f32) as i32,
+            scale,
+            &*FONT,
+            line,
+        );
+    }
+
+    let mut data = Vec::new();
+    DynamicImage::ImageRgb8(canvas)
+        .write_to(Cursor::new(&mut data), format)
+        .context("Encoding placeholder")?;
+
+    Ok(data)
+}
+
+/// Gets the placeholder drawn at about `width`x`height`, scaled up like
+/// resized images are, drawing and caching it if
+/// that size hasn't been asked for since the recipe last changed in a way that
+/// shows up on it.
+pub async fn get_placeholder(
+    db: &DatabaseConnection,
+    placeholder: &Placeholder,
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+) -> color_eyre::Result<Vec<u8>> {
+    let (width, height) = super::images::render_size(width, height);
+    let key = (
+        placeholder.recipe_id.clone(),
+        width as i64,
+        height as i64,
+        format.to_mime_type().to_owned(),
+    );
+
+    if let Some(cached) = PlaceholderImage::find_by_id(key.clone()).one(db).await?
+        && cached.e_tag == placeholder.etag
+    {
+        debug!(
+            recipe_id = placeholder.recipe_id,
+            width, height, "Placeholder cache hit"
+        );
+
+        let now = chrono::Utc::now();
+        if now - cached.last_used_at > super::images::LAST_USED_RESOLUTION {
+            PlaceholderImage::update_many()
+                .col_expr(placeholder_image::Column::LastUsedAt, Expr::value(now))
+                .filter(placeholder_image::Column::RecipeId.eq(&key.0))
+                .filter(placeholder_image::Column::Width.eq(key.1))
+                .filter(placeholder_image::Column::Height.eq(key.2))
+                .filter(placeholder_image::Column::Format.eq(&key.3))
+                .exec(db)
+                .await?;
+        }
+
+        return Ok(cached.data);
+    }
+
+    debug!(
+        recipe_id = placeholder.recipe_id,
+        width, height, "Placeholder cache miss"
+    );
+
+    let data = render_placeholder(placeholder, width, height, format)?;
+
+    PlaceholderImage::insert(placeholder_image::ActiveModel {
+        recipe_id: Set(key.0),
+        width: Set(key.1),
+        height: Set(key.2),
+        format: Set(key.3),
+        e_tag: Set(placeholder.etag.clone()),
+        data: Set(data.clone()),
+        last_used_at: Set(chrono::Utc::now()),
+    })
+    .on_conflict(
+        OnConflict::columns([
+            placeholder_image::Column::RecipeId,
+            placeholder_image::Column::Width,
+            placeholder_image::Column::Height,
+            placeholder_image::Column::Format,
+        ])
+        .update_columns([
+            placeholder_image::Column::ETag,
+            placeholder_image::Column::Data,
+            placeholder_image::Column::LastUsedAt,
+        ])
+        .to_owned(),
+    )
+    .exec(db)
+    .await?;
+
+    super::images::evict_renders(db).await?;
+
+    Ok(data)
+}
@@ -1,14 +1,38 @@
 use color_eyre::eyre::OptionExt as _;
 use color_eyre::eyre::WrapErr as _;
 use color_eyre::eyre::eyre;
+use migration::Expr;
 use rand::distr::SampleString;
 use sea_orm::QueryFilter;
+use sea_orm::QueryOrder as _;
+use sea_orm::sea_query::{Order, Query};
 use sea_orm::{ActiveModelBehavior as _, EntityTrait as _};
 use sea_orm::{ActiveValue::NotSet, ColumnTrait as _, EntityLoaderTrait};
-use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use sea_orm::{ActiveValue::Set, ConnectionTrait, DatabaseConnection};
 use sea_orm::{Condition, EntityLoaderTrait as _};
+use sea_orm::{QuerySelect as _, TransactionTrait as _};
 
-use crate::entities::{self, author, prelude::*, recipe};
+use crate::entities::{
+    self, author, image, image_variant, placeholder_image, prelude::*, recipe, recipe_alias,
+    recipe_revision, recipe_tag,
+};
+
+/// Matches a recipe by its internal id, its exposed id, or any exposed id it
+/// was previously handed out under.
+pub(crate) fn any_id_matches(id: &str) -> Condition {
+    Condition::any()
+        .add(recipe::Column::Id.eq(id))
+        .add(recipe::Column::ExposedId.eq(id))
+        .add(
+            recipe::Column::Id.in_subquery(
+                Query::select()
+                    .column(recipe_alias::Column::RecipeId)
+                    .from(recipe_alias::Entity)
+                    .and_where(recipe_alias::Column::Alias.eq(id))
+                    .to_owned(),
+            ),
+        )
+}
 
 pub async fn list_recipes(
     db: &DatabaseConnection,
@@ -19,28 +43,32 @@ pub async fn list_recipes(
     let mut recipe_models_q = Recipe::load().order_by_id_asc().with(Author);
 
     if let Some(offset) = offset {
-        recipe_models_q.query().offset(offset);
+        sea_orm::QueryFilter::query(&mut recipe_models_q).offset(offset);
     }
 
     if let Some(limit) = limit {
-        recipe_models_q.query().limit(limit);
+        sea_orm::QueryFilter::query(&mut recipe_models_q).limit(limit);
     }
 
+    recipe_models_q.filter_mut(recipe::Column::DeletedAt.is_null());
+
     if !all {
         recipe_models_q.filter_mut(recipe::Column::IsCustom.eq(true))
     }
 
     let recipe_models = recipe_models_q.all(db).await?;
+    let mut tags = super::tags::tags_of(db, recipe_models.iter().map(|r| r.id.as_str())).await?;
 
     let recipes = recipe_models
         .into_iter()
         .map(|r| {
+            let reference_tags = tags.remove(&r.id).unwrap_or_default();
+
             Ok(types::Recipe {
+                appliance: r.appliance,
                 author: {
-                    let entities::author::ModelEx {
-                        name, image, url, ..
-                    } = r.author.into_option().ok_or_eyre("Author not loaded")?;
-                    types::Author { name, image, url }
+                    let a = r.author.into_option().ok_or_eyre("Author not loaded")?;
+                    types::Author::served(a.id, a.name, a.image, a.url, a.avatar.is_some())
                 },
                 created_at: r.created_at,
                 created_by_id: r.created_by_id,
@@ -53,21 +81,26 @@ pub async fn list_recipes(
                 // steps: serde_json::from_value(r.steps)?,
                 steps: serde_path_to_error::deserialize(r.steps)
                     .with_context(|| format!("Deserializing steps of {}", r.id))?,
+                timing: r
+                    .timing
+                    .map(serde_path_to_error::deserialize)
+                    .transpose()
+                    .with_context(|| format!("Deserializing timing estimates of {}", r.id))?,
                 id: r.id,
                 locale: r.locale,
                 modified_at: r.modified_at,
                 name: r.name,
                 organization_id: "".to_owned(),
                 published_at: Some(r.published_at),
-                reference_tags: Vec::new(),
+                reference_tags,
                 serves: r.serves as u8,
-                state: "published".to_owned(),
+                state: r.state,
                 total_time: r
                     .total_time
                     .parse::<jiff::Span>()
                     .map_err(|e| eyre!("Parsing an iso8601_duration: {e:?}"))?
                     .to_duration(jiff::SpanRelativeTo::days_are_24_hours())?,
-                visibility: "all-users".to_owned(),
+                visibility: r.visibility,
                 cook_time: r.cook_time.and_then(|x| {
                     x.parse::<jiff::Span>()
                         .ok()?
@@ -89,26 +122,41 @@ pub async fn list_recipes(
     Ok(recipes)
 }
 
+/// Lists recipes for the device. With `published_only`, drafts and recipes
+/// scheduled for later are left out.
 pub async fn list_recipe_items(
     db: &DatabaseConnection,
     offset: Option<u64>,
     limit: Option<u64>,
     all: bool,
+    published_only: bool,
+    appliance: Option<&str>,
 ) -> color_eyre::Result<Vec<types::RecipeItem>> {
     let mut recipe_models_q = Recipe::load().order_by_id_asc().with(Author);
 
     if let Some(offset) = offset {
-        recipe_models_q.query().offset(offset);
+        sea_orm::QueryFilter::query(&mut recipe_models_q).offset(offset);
     }
 
     if let Some(limit) = limit {
-        recipe_models_q.query().limit(limit);
+        sea_orm::QueryFilter::query(&mut recipe_models_q).limit(limit);
     }
 
+    recipe_models_q.filter_mut(recipe::Column::DeletedAt.is_null());
+
     if !all {
         recipe_models_q.filter_mut(recipe::Column::IsCustom.eq(true))
     }
 
+    if published_only {
+        recipe_models_q.filter_mut(recipe::Column::State.eq(types::Recipe::PUBLISHED));
+        recipe_models_q.filter_mut(recipe::Column::PublishedAt.lte(chrono::Utc::now()));
+    }
+
+    if let Some(appliance) = appliance {
+        recipe_models_q.filter_mut(recipe::Column::Appliance.eq(appliance));
+    }
+
     let recipe_models = recipe_models_q.all(db).await?;
 
     let recipes = recipe_models
@@ -130,30 +178,61 @@ pub async fn list_recipe_items(
     Ok(recipes)
 }
 
-pub async fn get_recipe(db: &DatabaseConnection, id: &str) -> color_eyre::Result<types::Recipe> {
-    let r = Recipe::load()
+/// The other translations of a recipe, which all point at the one they were
+/// first forked from.
+async fn forks_of(
+    db: &impl ConnectionTrait,
+    id: &str,
+    fork_of: Option<&str>,
+) -> color_eyre::Result<Vec<types::ForkedIntoOtherLocale>> {
+    let root = fork_of.unwrap_or(id);
+
+    let forks = Recipe::find()
         .filter(
             Condition::any()
-                .add(recipe::Column::Id.eq(id))
-                .add(recipe::Column::ExposedId.eq(id)),
+                .add(recipe::Column::Id.eq(root))
+                .add(recipe::Column::ForkOf.eq(root)),
         )
+        .filter(recipe::Column::Id.ne(id))
+        .filter(recipe::Column::DeletedAt.is_null())
+        .order_by_asc(recipe::Column::Locale)
+        .all(db)
+        .await?;
+
+    Ok(forks
+        .into_iter()
+        .map(|f| types::ForkedIntoOtherLocale {
+            id: f.exposed_id.unwrap_or(f.id),
+            locale: f.locale,
+        })
+        .collect())
+}
+
+pub async fn get_recipe(db: &impl ConnectionTrait, id: &str) -> color_eyre::Result<types::Recipe> {
+    let r = Recipe::load()
+        .filter(any_id_matches(id))
+        .filter(recipe::Column::DeletedAt.is_null())
         .with(Author)
         .one(db)
         .await?
         .ok_or_eyre("Recipe not found")?;
+    let reference_tags = super::tags::tags_of(db, [r.id.as_str()])
+        .await?
+        .remove(&r.id)
+        .unwrap_or_default();
+    let forked_into_other_locales = forks_of(db, &r.id, r.fork_of.as_deref()).await?;
 
     Ok(types::Recipe {
+        appliance: r.appliance,
         author: {
-            let entities::author::ModelEx {
-                name, image, url, ..
-            } = r.author.into_option().ok_or_eyre("Author not loaded")?;
-            types::Author { name, image, url }
+            let a = r.author.into_option().ok_or_eyre("Author not loaded")?;
+            types::Author::served(a.id, a.name, a.image, a.url, a.avatar.is_some())
         },
         created_at: r.created_at,
         created_by_id: r.created_by_id,
         description: r.description,
         etag: r.e_tag,
-        forked_into_other_locales: Vec::new(),
+        forked_into_other_locales,
         id: r.id,
         // ingredients: serde_json::from_value(r.ingredients)?,
         ingredients: serde_path_to_error::deserialize(r.ingredients)
@@ -163,17 +242,22 @@ pub async fn get_recipe(db: &DatabaseConnection, id: &str) -> color_eyre::Result
         name: r.name,
         organization_id: "".to_owned(),
         published_at: Some(r.published_at),
-        reference_tags: Vec::new(),
+        reference_tags,
         serves: r.serves as u8,
-        state: "published".to_owned(),
+        state: r.state,
         // steps: serde_json::from_value(r.steps)?,
         steps: serde_path_to_error::deserialize(r.steps).context("Deserializing steps")?,
+        timing: r
+            .timing
+            .map(serde_path_to_error::deserialize)
+            .transpose()
+            .context("Deserializing timing estimates")?,
         total_time: r
             .total_time
             .parse::<jiff::Span>()
             .map_err(|e| eyre!("Parsing an iso8601_duration: {e:?}"))?
             .to_duration(jiff::SpanRelativeTo::days_are_24_hours())?,
-        visibility: "all-users".to_owned(),
+        visibility: r.visibility,
         cook_time: r.cook_time.and_then(|x| {
             x.parse::<jiff::Span>()
                 .ok()?
@@ -191,17 +275,210 @@ pub async fn get_recipe(db: &DatabaseConnection, id: &str) -> color_eyre::Result
     })
 }
 
+/// A recipe scaled to serve `serves` people, without saving it, along with
+/// the steps that may need adjusting by hand. It gets an etag of its own, so
+/// each scaled variant is cached apart from the original.
+pub async fn scale(
+    db: &DatabaseConnection,
+    id: &str,
+    serves: u8,
+) -> color_eyre::Result<(types::Recipe, Vec<types::scale::ScaleWarning>)> {
+    let mut recipe = get_recipe(db, id).await?;
+    let warnings = recipe.scale(serves);
+    recipe.etag = content_etag(&recipe)?;
+
+    Ok((recipe, warnings))
+}
+
+/// Derives a recipe's etag from everything the device gets served, other than
+/// the etag itself and the modification time.
+fn content_etag(r: &types::Recipe) -> color_eyre::Result<String> {
+    let content = types::Recipe {
+        etag: String::new(),
+        modified_at: r.created_at,
+        ..r.clone()
+    };
+
+    Ok(crate::etag::from_content(
+        serde_json::to_vec(&content).context("Serializing recipe")?,
+    ))
+}
+
+/// Recomputes the etags of every translation of `root` from what's stored, for
+/// when the list of other translations they each show has changed.
+async fn refresh_translation_etags(db: &DatabaseConnection, root: &str) -> color_eyre::Result<()> {
+    let ids: Vec<String> = Recipe::find()
+        .select_only()
+        .column(recipe::Column::Id)
+        .filter(
+            Condition::any()
+                .add(recipe::Column::Id.eq(root))
+                .add(recipe::Column::ForkOf.eq(root)),
+        )
+        .filter(recipe::Column::DeletedAt.is_null())
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    for id in ids {
+        let recipe = get_recipe(db, &id).await?;
+        let etag = content_etag(&recipe)?;
+
+        if etag != recipe.etag {
+            Recipe::update_many()
+                .col_expr(recipe::Column::ETag, Expr::value(etag))
+                .filter(recipe::Column::Id.eq(&id))
+                .exec(db)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The translation a recipe and its other translations were all forked from.
+async fn translation_root(db: &impl ConnectionTrait, id: &str) -> color_eyre::Result<String> {
+    let r = Recipe::find()
+        .filter(any_id_matches(id))
+        .one(db)
+        .await?
+        .ok_or_eyre("Recipe not found")?;
+
+    Ok(r.fork_of.unwrap_or(r.id))
+}
+
+/// Returned by [`set_recipe`] when the recipe has a problem that would stop
+/// its appliance accepting it.
+#[derive(Debug)]
+pub struct Invalid {
+    pub issue: types::validate::Issue,
+}
+
+impl std::fmt::Display for Invalid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.issue.path, self.issue.message)
+    }
+}
+
+impl std::error::Error for Invalid {}
+
+/// Checks a recipe against the catalog and the appliance it's written for.
+/// Every way of saving a recipe goes through this, so none of them can store
+/// one the appliance won't take.
+async fn check_recipe(db: &DatabaseConnection, r: &types::Recipe) -> color_eyre::Result<()> {
+    let catalog = super::catalog::get_catalog(db).await?;
+    let appliance = super::appliances::get_appliance(db, &r.appliance).await?;
+
+    let issues = types::validate::validate(
+        r,
+        types::validate::Rules {
+            catalog: &catalog,
+            appliance: &appliance,
+            // the bowl, ingredient usage and usual settings are only ever
+            // warned about
+            conversions: &types::convert::Conversions::default(),
+            schemas: &[],
+        },
+    );
+
+    match issues
+        .into_iter()
+        .find(|i| i.severity == types::validate::Severity::Error)
+    {
+        Some(issue) => Err(Invalid { issue }.into()),
+        None => Ok(()),
+    }
+}
+
+/// Returned by [`set_recipe`] when the recipe was saved by someone else since
+/// the editor loaded it. Get it back out with `downcast_ref`.
+#[derive(Debug)]
+pub struct Conflict {
+    /// The recipe as it's stored now
+    pub current: types::Recipe,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Recipe {} was changed since it was loaded",
+            self.current.id
+        )
+    }
+}
+
+impl std::error::Error for Conflict {}
+
 // bad api, but IDC
 // TODO: Port Rel8 to Rust
+/// Saves a recipe and returns its new etag. When updating, `base_etag` is the
+/// etag the editor loaded; if the stored recipe has moved on since, nothing is
+/// written and a [`Conflict`] is returned. Pass `None` to overwrite anyway.
 pub async fn set_recipe(
     db: &DatabaseConnection,
-    r: types::Recipe,
+    mut r: types::Recipe,
     create: bool,
-) -> color_eyre::Result<()> {
-    let author = Author::find()
-        .filter(author::Column::Name.eq(&r.author.name))
-        .one(db)
-        .await?;
+    base_etag: Option<&str>,
+    editor: Option<String>,
+) -> color_eyre::Result<String> {
+    check_recipe(db, &r).await?;
+
+    // everything from taking the version on goes in together, so a save that
+    // fails halfway leaves the recipe as it was
+    let txn = db.begin().await?;
+
+    let id = r.id.clone();
+
+    // the etag covers the other translations as they're stored, which may
+    // have changed since the editor loaded the recipe
+    if !create {
+        let root = translation_root(&txn, &id).await?;
+        r.forked_into_other_locales = forks_of(&txn, &id, Some(&root)).await?;
+    }
+    let etag = content_etag(&r)?;
+
+    if !create {
+        // recipes saved before we kept history get their current version
+        // recorded first, so this edit can still be undone
+        if !super::revisions::has_revisions(&txn, &id).await? {
+            let current = get_recipe(&txn, &id).await?;
+            super::revisions::record_revision(&txn, &current, None).await?;
+        }
+
+        // checking and taking the version in one statement means two editors
+        // saving over the same version can't both get through
+        if let Some(base_etag) = base_etag {
+            let claimed = Recipe::update_many()
+                .col_expr(recipe::Column::ETag, Expr::value(etag.clone()))
+                .col_expr(recipe::Column::ModifiedAt, Expr::value(chrono::Utc::now()))
+                .filter(recipe::Column::Id.eq(&id))
+                .filter(recipe::Column::ETag.eq(base_etag))
+                .exec(&txn)
+                .await?;
+
+            if claimed.rows_affected == 0 {
+                let current = get_recipe(&txn, &id).await?;
+                return Err(Conflict { current }.into());
+            }
+        }
+    }
+
+    // recipes written by one of our author profiles point straight at it,
+    // anything else is matched up by name
+    let author = match r.author.id {
+        Some(id) => Author::find_by_id(id).one(&txn).await?,
+        None => None,
+    };
+    let author = match author {
+        Some(author) => Some(author),
+        None => {
+            Author::find()
+                .filter(author::Column::Name.eq(&r.author.name))
+                .one(&txn)
+                .await?
+        }
+    };
 
     let author_id = match author {
         Some(author) => author.id,
@@ -211,43 +488,285 @@ pub async fn set_recipe(
                 name: Set(r.author.name),
                 image: Set(r.author.image),
                 url: Set(r.author.url),
+                avatar: NotSet,
+                avatar_e_tag: NotSet,
+                is_default: NotSet,
                 recipes: sea_orm::HasManyModel::NotSet,
             }
-            .insert(db)
+            .insert(&txn)
             .await?
             .id
         }
     };
 
-    let exposed_id = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 10);
+    // the device remembers recipes by their exposed id, so only hand out a new
+    // one when the recipe doesn't already have one
+    let existing_exposed_id = if create {
+        None
+    } else {
+        Recipe::find_by_id(&r.id)
+            .one(&txn)
+            .await?
+            .ok_or_eyre("Recipe not found")?
+            .exposed_id
+    };
+    let new_exposed_id = existing_exposed_id
+        .is_none()
+        .then(|| rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 10));
+    let exposed_id = existing_exposed_id.or_else(|| new_exposed_id.clone());
 
     let model = entities::recipe::ActiveModelEx {
         author: sea_orm::HasOneModel::NotSet,
+        placeholder_images: sea_orm::HasManyModel::NotSet,
+        recipe_aliases: sea_orm::HasManyModel::NotSet,
+        recipe_revisions: sea_orm::HasManyModel::NotSet,
+        tags: sea_orm::HasManyModel::NotSet,
         author_id: Set(author_id),
         id: Set(r.id.clone()),
-        exposed_id: Set(Some(exposed_id)),
+        exposed_id: Set(exposed_id),
         name: Set(r.name.clone()),
         description: Set(r.description.clone()),
         prep_time: Set(r.prep_time.map(|x| x.to_string())),
         cook_time: Set(r.cook_time.map(|x| x.to_string())),
         total_time: Set(r.total_time.to_string()),
         serves: Set(r.serves as i64),
-        e_tag: Set(r.etag.clone()),
+        e_tag: Set(etag),
         organisation_id: Set(r.organization_id.clone()),
         locale: Set(r.locale.clone()),
         created_at: Set(r.created_at),
-        modified_at: Set(r.modified_at),
-        published_at: Set(r.published_at.expect("This should be given")),
+        modified_at: Set(chrono::Utc::now()),
+        published_at: Set(r.published_at.unwrap_or_else(chrono::Utc::now)),
         created_by_id: Set(r.created_by_id.clone()),
         steps: Set(serde_json::to_value(&r.steps).unwrap()),
         ingredients: Set(serde_json::to_value(&r.ingredients).unwrap()),
         is_custom: Set(true),
+        deleted_at: NotSet,
+        state: Set(r.state.clone()),
+        visibility: Set(r.visibility.clone()),
+        fork_of: NotSet,
+        appliance: Set(r.appliance.clone()),
+        timing: Set(r.timing.as_ref().map(serde_json::to_value).transpose()?),
     };
 
     if create {
-        model.insert(db).await?;
+        model.insert(&txn).await?;
     } else {
-        model.update(db).await?;
+        model.update(&txn).await?;
+    }
+
+    if let Some(alias) = new_exposed_id {
+        RecipeAlias::insert(recipe_alias::ActiveModel {
+            alias: Set(alias),
+            recipe_id: Set(r.id),
+        })
+        .on_conflict_do_nothing()
+        .exec(&txn)
+        .await?;
+    }
+
+    super::tags::set_recipe_tags(&txn, &id, &r.reference_tags).await?;
+
+    let saved = get_recipe(&txn, &id).await?;
+    super::revisions::record_revision(&txn, &saved, editor).await?;
+    super::images::prune_attached_images(&txn, &saved).await?;
+
+    txn.commit().await?;
+
+    Ok(saved.etag)
+}
+
+/// Forks a recipe into another locale and returns the new recipe's id. The
+/// fork keeps the ingredients, capabilities and settings, with the text copied
+/// over for translating, and starts out as a draft.
+pub async fn create_translation(
+    db: &DatabaseConnection,
+    id: &str,
+    locale: &str,
+    editor: Option<String>,
+) -> color_eyre::Result<String> {
+    let original = get_recipe(db, id).await?;
+
+    if original.locale == locale
+        || original
+            .forked_into_other_locales
+            .iter()
+            .any(|f| f.locale == locale)
+    {
+        return Err(eyre!("There's already a {locale} version of this recipe"));
+    }
+
+    let root = Recipe::find_by_id(&original.id)
+        .one(db)
+        .await?
+        .ok_or_eyre("Recipe not found")?
+        .fork_of
+        .unwrap_or_else(|| original.id.clone());
+
+    let fork_id = types::UID::new().0;
+    let now = chrono::Utc::now();
+
+    let fork = types::Recipe {
+        id: fork_id.clone(),
+        locale: locale.to_owned(),
+        state: types::Recipe::DRAFT.to_owned(),
+        created_at: now,
+        modified_at: now,
+        published_at: Some(now),
+        forked_into_other_locales: Vec::new(),
+        ..original
+    };
+
+    set_recipe(db, fork, true, None, editor).await?;
+
+    Recipe::update_many()
+        .col_expr(recipe::Column::ForkOf, Expr::value(root.clone()))
+        .filter(recipe::Column::Id.eq(&fork_id))
+        .exec(db)
+        .await?;
+
+    // the others now list the new translation, so devices need to refetch them
+    refresh_translation_etags(db, &root).await?;
+
+    Ok(fork_id)
+}
+
+/// Moves a recipe to the trash, which hides it from everything but the trash
+/// page until it's restored or purged.
+pub async fn delete_recipe(db: &DatabaseConnection, id: &str) -> color_eyre::Result<()> {
+    Recipe::update_many()
+        .col_expr(recipe::Column::DeletedAt, Expr::value(chrono::Utc::now()))
+        .filter(any_id_matches(id))
+        .exec(db)
+        .await?;
+
+    let root = translation_root(db, id).await?;
+    refresh_translation_etags(db, &root).await?;
+
+    Ok(())
+}
+
+pub async fn restore_recipe(db: &DatabaseConnection, id: &str) -> color_eyre::Result<()> {
+    Recipe::update_many()
+        .col_expr(
+            recipe::Column::DeletedAt,
+            Expr::value(Option::<chrono::DateTime<chrono::Utc>>::None),
+        )
+        .filter(any_id_matches(id))
+        .exec(db)
+        .await?;
+
+    let root = translation_root(db, id).await?;
+    refresh_translation_etags(db, &root).await?;
+
+    Ok(())
+}
+
+pub async fn list_trashed_recipes(
+    db: &DatabaseConnection,
+) -> color_eyre::Result<Vec<types::TrashedRecipe>> {
+    let mut recipe_models_q = Recipe::load()
+        .filter(recipe::Column::DeletedAt.is_not_null())
+        .with(Author);
+
+    sea_orm::QueryFilter::query(&mut recipe_models_q)
+        .order_by(recipe::Column::DeletedAt, Order::Desc);
+
+    let recipe_models = recipe_models_q.all(db).await?;
+
+    recipe_models
+        .into_iter()
+        .map(|r| {
+            Ok(types::TrashedRecipe {
+                id: r.id,
+                name: r.name,
+                author_name: r.author.into_option().ok_or_eyre("Author not loaded")?.name,
+                deleted_at: r.deleted_at.ok_or_eyre("Recipe isn't deleted")?,
+            })
+        })
+        .collect()
+}
+
+/// Permanently removes a trashed recipe along with its images and everything
+/// cached for it. Translations forked from it are handed over to the oldest of
+/// them, so they stay linked to each other.
+pub async fn purge_recipe(db: &DatabaseConnection, id: &str) -> color_eyre::Result<()> {
+    let txn = db.begin().await?;
+
+    let r = Recipe::find()
+        .filter(any_id_matches(id))
+        .filter(recipe::Column::DeletedAt.is_not_null())
+        .one(&txn)
+        .await?
+        .ok_or_eyre("Recipe isn't in the trash")?;
+
+    let forks: Vec<String> = Recipe::find()
+        .select_only()
+        .column(recipe::Column::Id)
+        .filter(recipe::Column::ForkOf.eq(&r.id))
+        .order_by_asc(recipe::Column::CreatedAt)
+        .into_tuple()
+        .all(&txn)
+        .await?;
+
+    if let Some(new_root) = forks.first() {
+        Recipe::update_many()
+            .col_expr(recipe::Column::ForkOf, Expr::value(new_root.clone()))
+            .filter(recipe::Column::ForkOf.eq(&r.id))
+            .exec(&txn)
+            .await?;
+
+        Recipe::update_many()
+            .col_expr(recipe::Column::ForkOf, Expr::value(Option::<String>::None))
+            .filter(recipe::Column::Id.eq(new_root))
+            .exec(&txn)
+            .await?;
+    }
+
+    ImageVariant::delete_many()
+        .filter(
+            image_variant::Column::ImageId.in_subquery(
+                Query::select()
+                    .column(image::Column::Id)
+                    .from(image::Entity)
+                    .and_where(image::Column::RecipeId.eq(&r.id))
+                    .to_owned(),
+            ),
+        )
+        .exec(&txn)
+        .await?;
+
+    Image::delete_many()
+        .filter(image::Column::RecipeId.eq(&r.id))
+        .exec(&txn)
+        .await?;
+
+    PlaceholderImage::delete_many()
+        .filter(placeholder_image::Column::RecipeId.eq(&r.id))
+        .exec(&txn)
+        .await?;
+
+    RecipeAlias::delete_many()
+        .filter(recipe_alias::Column::RecipeId.eq(&r.id))
+        .exec(&txn)
+        .await?;
+
+    RecipeRevision::delete_many()
+        .filter(recipe_revision::Column::RecipeId.eq(&r.id))
+        .exec(&txn)
+        .await?;
+
+    RecipeTag::delete_many()
+        .filter(recipe_tag::Column::RecipeId.eq(&r.id))
+        .exec(&txn)
+        .await?;
+
+    Recipe::delete_by_id(&r.id).exec(&txn).await?;
+
+    txn.commit().await?;
+
+    let root = forks.first().cloned().or(r.fork_of);
+    if let Some(root) = root {
+        refresh_translation_etags(db, &root).await?;
     }
 
     Ok(())
@@ -0,0 +1,96 @@
+use color_eyre::eyre::OptionExt as _;
+use color_eyre::eyre::WrapErr as _;
+use sea_orm::{ActiveValue::NotSet, ActiveValue::Set, ConnectionTrait, DatabaseConnection};
+use sea_orm::{ColumnTrait as _, EntityTrait as _, PaginatorTrait as _};
+use sea_orm::{QueryFilter, QueryOrder as _, QuerySelect};
+
+use crate::entities::{prelude::*, recipe_revision};
+
+/// Keeps an immutable copy of `recipe` as it's served right now.
+pub(crate) async fn record_revision(
+    db: &impl ConnectionTrait,
+    recipe: &types::Recipe,
+    editor: Option<String>,
+) -> color_eyre::Result<()> {
+    RecipeRevision::insert(recipe_revision::ActiveModel {
+        id: NotSet,
+        recipe_id: Set(recipe.id.clone()),
+        created_at: Set(recipe.modified_at),
+        editor: Set(editor),
+        e_tag: Set(recipe.etag.clone()),
+        snapshot: Set(serde_json::to_value(recipe).context("Serializing recipe")?),
+    })
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+pub(crate) async fn has_revisions(
+    db: &impl ConnectionTrait,
+    recipe_id: &str,
+) -> color_eyre::Result<bool> {
+    let count = RecipeRevision::find()
+        .filter(recipe_revision::Column::RecipeId.eq(recipe_id))
+        .count(db)
+        .await?;
+
+    Ok(count > 0)
+}
+
+/// Lists a recipe's revisions, newest first.
+pub async fn list_revisions(
+    db: &DatabaseConnection,
+    recipe_id: &str,
+) -> color_eyre::Result<Vec<types::RecipeRevision>> {
+    let revisions = RecipeRevision::find()
+        .select_only()
+        .columns([
+            recipe_revision::Column::Id,
+            recipe_revision::Column::CreatedAt,
+            recipe_revision::Column::Editor,
+            recipe_revision::Column::ETag,
+        ])
+        .filter(recipe_revision::Column::RecipeId.eq(recipe_id))
+        .order_by_desc(recipe_revision::Column::Id)
+        .into_tuple::<(i64, chrono::DateTime<chrono::Utc>, Option<String>, String)>()
+        .all(db)
+        .await?;
+
+    Ok(revisions
+        .into_iter()
+        .map(|(id, created_at, editor, etag)| types::RecipeRevision {
+            id,
+            created_at,
+            editor,
+            etag,
+        })
+        .collect())
+}
+
+pub async fn get_revision(
+    db: &DatabaseConnection,
+    revision_id: i64,
+) -> color_eyre::Result<types::Recipe> {
+    let revision = RecipeRevision::find_by_id(revision_id)
+        .one(db)
+        .await?
+        .ok_or_eyre("Revision not found")?;
+
+    serde_path_to_error::deserialize(revision.snapshot).context("Deserializing revision")
+}
+
+/// Puts a recipe back how it was at a revision. This is saved as a new
+/// revision, so it can be undone too, and overwrites whatever is there now.
+pub async fn restore_revision(
+    db: &DatabaseConnection,
+    revision_id: i64,
+    editor: Option<String>,
+) -> color_eyre::Result<types::Recipe> {
+    let recipe = get_revision(db, revision_id).await?;
+    let id = recipe.id.clone();
+
+    super::recipes::set_recipe(db, recipe, false, None, editor).await?;
+
+    super::recipes::get_recipe(db, &id).await
+}
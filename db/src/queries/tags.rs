@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ColumnTrait as _, ConnectionTrait, DatabaseConnection, EntityTrait as _, QueryFilter,
+    QueryOrder as _,
+};
+
+use crate::entities::{prelude::*, recipe_tag, tag};
+
+/// Lists every tag we know of, grouped by category.
+pub async fn list_tags(db: &DatabaseConnection) -> color_eyre::Result<Vec<types::ReferenceTag>> {
+    let tags = Tag::find()
+        .order_by_asc(tag::Column::Category)
+        .order_by_asc(tag::Column::Name)
+        .all(db)
+        .await?;
+
+    Ok(tags
+        .into_iter()
+        .map(|t| types::ReferenceTag {
+            category: t.category,
+            id: t.id,
+            name: t.name,
+        })
+        .collect())
+}
+
+/// Looks up the tags of each of `recipe_ids`, keyed by recipe id.
+pub(crate) async fn tags_of(
+    db: &impl ConnectionTrait,
+    recipe_ids: impl IntoIterator<Item = &str>,
+) -> color_eyre::Result<HashMap<String, Vec<types::ReferenceTag>>> {
+    let rows = RecipeTag::find()
+        .filter(recipe_tag::Column::RecipeId.is_in(recipe_ids))
+        .order_by_asc(recipe_tag::Column::TagId)
+        .find_also_related(Tag)
+        .all(db)
+        .await?;
+
+    let mut tags = HashMap::<String, Vec<types::ReferenceTag>>::new();
+
+    for (recipe_tag, tag) in rows {
+        let Some(tag) = tag else { continue };
+
+        tags.entry(recipe_tag.recipe_id)
+            .or_default()
+            .push(types::ReferenceTag {
+                category: tag.category,
+                id: tag.id,
+                name: tag.name,
+            });
+    }
+
+    Ok(tags)
+}
+
+/// Adds any of `tags` we don't know of yet.
+pub(crate) async fn insert_tags<'a>(
+    db: &impl ConnectionTrait,
+    tags: impl IntoIterator<Item = &'a types::ReferenceTag>,
+) -> color_eyre::Result<()> {
+    let models = tags
+        .into_iter()
+        .map(|t| tag::ActiveModel {
+            id: Set(t.id.clone()),
+            category: Set(t.category.clone()),
+            name: Set(t.name.clone()),
+        })
+        .collect::<Vec<_>>();
+
+    if models.is_empty() {
+        return Ok(());
+    }
+
+    Tag::insert_many(models)
+        .on_conflict_do_nothing()
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Replaces the tags of a recipe.
+pub(crate) async fn set_recipe_tags(
+    db: &impl ConnectionTrait,
+    recipe_id: &str,
+    tags: &[types::ReferenceTag],
+) -> color_eyre::Result<()> {
+    insert_tags(db, tags).await?;
+
+    RecipeTag::delete_many()
+        .filter(recipe_tag::Column::RecipeId.eq(recipe_id))
+        .exec(db)
+        .await?;
+
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    RecipeTag::insert_many(tags.iter().map(|t| recipe_tag::ActiveModel {
+        recipe_id: Set(recipe_id.to_owned()),
+        tag_id: Set(t.id.clone()),
+    }))
+    .on_conflict_do_nothing()
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
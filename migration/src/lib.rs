@@ -3,6 +3,27 @@ pub use sea_orm_migration::prelude::*;
 mod m20220101_000001_create_table;
 mod m20251221_133916_add_images;
 mod m20260104_181130_add_exposed_id;
+mod m20261019_093112_add_recipe_alias;
+mod m20261019_141507_add_image_etag;
+mod m20261019_170244_add_image_variants;
+mod m20261020_101938_add_image_metadata;
+mod m20261020_154410_add_image_gallery;
+mod m20261021_094205_add_placeholder_image;
+mod m20261021_131522_add_image_kind;
+mod m20261021_160833_add_recipe_deleted_at;
+mod m20261022_092716_add_recipe_revision;
+mod m20261023_101524_add_recipe_state;
+mod m20261023_143851_add_tags;
+mod m20261024_091207_add_recipe_fork_of;
+mod m20261024_140533_add_author_profile;
+mod m20261025_103348_add_capability_schema;
+mod m20261025_151204_add_reference_catalog;
+mod m20261026_094416_add_appliance_profiles;
+mod m20261026_142205_add_recipe_timing;
+mod m20261027_091530_add_ingredient_density;
+mod m20261027_160412_add_appliance_min_heating;
+mod m20261028_093512_add_nominal_value_position;
+mod m20261029_104215_add_appliance_capability_loads;
 
 pub struct Migrator;
 
@@ -13,6 +34,27 @@ impl MigratorTrait for Migrator {
             Box::new(m20220101_000001_create_table::Migration),
             Box::new(m20251221_133916_add_images::Migration),
             Box::new(m20260104_181130_add_exposed_id::Migration),
+            Box::new(m20261019_093112_add_recipe_alias::Migration),
+            Box::new(m20261019_141507_add_image_etag::Migration),
+            Box::new(m20261019_170244_add_image_variants::Migration),
+            Box::new(m20261020_101938_add_image_metadata::Migration),
+            Box::new(m20261020_154410_add_image_gallery::Migration),
+            Box::new(m20261021_094205_add_placeholder_image::Migration),
+            Box::new(m20261021_131522_add_image_kind::Migration),
+            Box::new(m20261021_160833_add_recipe_deleted_at::Migration),
+            Box::new(m20261022_092716_add_recipe_revision::Migration),
+            Box::new(m20261023_101524_add_recipe_state::Migration),
+            Box::new(m20261023_143851_add_tags::Migration),
+            Box::new(m20261024_091207_add_recipe_fork_of::Migration),
+            Box::new(m20261024_140533_add_author_profile::Migration),
+            Box::new(m20261025_103348_add_capability_schema::Migration),
+            Box::new(m20261025_151204_add_reference_catalog::Migration),
+            Box::new(m20261026_094416_add_appliance_profiles::Migration),
+            Box::new(m20261026_142205_add_recipe_timing::Migration),
+            Box::new(m20261027_091530_add_ingredient_density::Migration),
+            Box::new(m20261027_160412_add_appliance_min_heating::Migration),
+            Box::new(m20261028_093512_add_nominal_value_position::Migration),
+            Box::new(m20261029_104215_add_appliance_capability_loads::Migration),
         ]
     }
 }
@@ -0,0 +1,78 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecipeAlias::Table)
+                    .if_not_exists()
+                    .col(string(RecipeAlias::Alias).primary_key().not_null())
+                    .col(string(RecipeAlias::RecipeId).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("FK_recipe_alias_recipe_id")
+                            .from(RecipeAlias::Table, RecipeAlias::RecipeId)
+                            .to(Recipe::Table, Recipe::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("idx-recipe-alias-recipe-id")
+                    .table(RecipeAlias::Table)
+                    .col(RecipeAlias::RecipeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        // keep hold of whatever ID the device currently knows each recipe by
+        db.execute_unprepared(
+            "INSERT INTO recipe_alias (alias, recipe_id)
+             SELECT exposed_id, id FROM recipe
+             WHERE exposed_id IS NOT NULL;",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                IndexDropStatement::new()
+                    .name("idx-recipe-alias-recipe-id")
+                    .table(RecipeAlias::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RecipeAlias::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum RecipeAlias {
+    Table,
+    Alias,
+    RecipeId,
+}
+
+#[derive(DeriveIden)]
+enum Recipe {
+    Table,
+    Id,
+}
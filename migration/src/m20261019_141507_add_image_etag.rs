@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // sqlite can't add columns in bulk
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(string(Image::ETag).not_null().default(""))
+                    .to_owned(),
+            )
+            .await?;
+
+        // sqlite won't let an added column default to the current time
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(timestamp_null(Image::ModifiedAt).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .drop_column(Image::ModifiedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .drop_column(Image::ETag)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Image {
+    Table,
+    ETag,
+    ModifiedAt,
+}
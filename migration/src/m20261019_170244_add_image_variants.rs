@@ -0,0 +1,70 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImageVariant::Table)
+                    .if_not_exists()
+                    .col(string(ImageVariant::ImageId).not_null())
+                    .col(integer(ImageVariant::Width).not_null())
+                    .col(integer(ImageVariant::Height).not_null())
+                    .col(string(ImageVariant::Format).not_null())
+                    .col(blob(ImageVariant::Data).not_null())
+                    .col(
+                        timestamp(ImageVariant::LastUsedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("FK_image_variant_image_id")
+                            .from(ImageVariant::Table, ImageVariant::ImageId)
+                            .to(Image::Table, Image::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .primary()
+                            .col(ImageVariant::ImageId)
+                            .col(ImageVariant::Width)
+                            .col(ImageVariant::Height)
+                            .col(ImageVariant::Format),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImageVariant::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImageVariant {
+    Table,
+    ImageId,
+    Width,
+    Height,
+    Format,
+    Data,
+    LastUsedAt,
+}
+
+#[derive(DeriveIden)]
+enum Image {
+    Table,
+    Id,
+}
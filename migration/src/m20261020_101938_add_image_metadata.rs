@@ -0,0 +1,77 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // everything uploaded before now was converted to webp
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(string(Image::ContentType).not_null().default("image/webp"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(integer_null(Image::Width).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(integer_null(Image::Height).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(string_null(Image::FileName).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Image::FileName,
+            Image::Height,
+            Image::Width,
+            Image::ContentType,
+        ] {
+            manager
+                .alter_table(
+                    TableAlterStatement::new()
+                        .table(Image::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Image {
+    Table,
+    ContentType,
+    Width,
+    Height,
+    FileName,
+}
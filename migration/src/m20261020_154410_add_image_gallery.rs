@@ -0,0 +1,112 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(string(Image::RecipeId).not_null().default(""))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(integer(Image::Position).not_null().default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(boolean(Image::IsHero).not_null().default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(double(Image::FocalX).not_null().default(0.5))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Image::Table)
+                    .add_column(double(Image::FocalY).not_null().default(0.5))
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        // images used to be keyed by the recipe they were the hero of
+        db.execute_unprepared("UPDATE image SET recipe_id = id, is_hero = TRUE;")
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("idx-image-recipe-id")
+                    .table(Image::Table)
+                    .col(Image::RecipeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                IndexDropStatement::new()
+                    .name("idx-image-recipe-id")
+                    .table(Image::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        for column in [
+            Image::FocalY,
+            Image::FocalX,
+            Image::IsHero,
+            Image::Position,
+            Image::RecipeId,
+        ] {
+            manager
+                .alter_table(
+                    TableAlterStatement::new()
+                        .table(Image::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Image {
+    Table,
+    RecipeId,
+    Position,
+    IsHero,
+    FocalX,
+    FocalY,
+}
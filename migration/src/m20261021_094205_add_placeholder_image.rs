@@ -0,0 +1,72 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PlaceholderImage::Table)
+                    .if_not_exists()
+                    .col(string(PlaceholderImage::RecipeId).not_null())
+                    .col(integer(PlaceholderImage::Width).not_null())
+                    .col(integer(PlaceholderImage::Height).not_null())
+                    .col(string(PlaceholderImage::Format).not_null())
+                    .col(string(PlaceholderImage::ETag).not_null())
+                    .col(blob(PlaceholderImage::Data).not_null())
+                    .col(
+                        timestamp(PlaceholderImage::LastUsedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("FK_placeholder_image_recipe_id")
+                            .from(PlaceholderImage::Table, PlaceholderImage::RecipeId)
+                            .to(Recipe::Table, Recipe::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .primary()
+                            .col(PlaceholderImage::RecipeId)
+                            .col(PlaceholderImage::Width)
+                            .col(PlaceholderImage::Height)
+                            .col(PlaceholderImage::Format),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PlaceholderImage::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlaceholderImage {
+    Table,
+    RecipeId,
+    Width,
+    Height,
+    Format,
+    ETag,
+    Data,
+    LastUsedAt,
+}
+
+#[derive(DeriveIden)]
+enum Recipe {
+    Table,
+    Id,
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecipeRevision::Table)
+                    .if_not_exists()
+                    .col(
+                        integer(RecipeRevision::Id)
+                            .primary_key()
+                            .auto_increment()
+                            .not_null(),
+                    )
+                    .col(string(RecipeRevision::RecipeId).not_null())
+                    .col(timestamp(RecipeRevision::CreatedAt).not_null())
+                    .col(string_null(RecipeRevision::Editor).null())
+                    .col(string(RecipeRevision::ETag).not_null())
+                    .col(json(RecipeRevision::Snapshot).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("FK_recipe_revision_recipe_id")
+                            .from(RecipeRevision::Table, RecipeRevision::RecipeId)
+                            .to(Recipe::Table, Recipe::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("idx-recipe-revision-recipe-id")
+                    .table(RecipeRevision::Table)
+                    .col(RecipeRevision::RecipeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                IndexDropStatement::new()
+                    .name("idx-recipe-revision-recipe-id")
+                    .table(RecipeRevision::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RecipeRevision::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum RecipeRevision {
+    Table,
+    Id,
+    RecipeId,
+    CreatedAt,
+    Editor,
+    ETag,
+    Snapshot,
+}
+
+#[derive(DeriveIden)]
+enum Recipe {
+    Table,
+    Id,
+}
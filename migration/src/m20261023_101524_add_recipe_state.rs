@@ -0,0 +1,59 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // everything saved before now was pushed to the device straight away
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Recipe::Table)
+                    .add_column(string(Recipe::State).not_null().default("published"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Recipe::Table)
+                    .add_column(string(Recipe::Visibility).not_null().default("all-users"))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Recipe::Table)
+                    .drop_column(Recipe::Visibility)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Recipe::Table)
+                    .drop_column(Recipe::State)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Recipe {
+    Table,
+    State,
+    Visibility,
+}
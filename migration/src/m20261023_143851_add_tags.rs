@@ -0,0 +1,105 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Tag::Table)
+                    .if_not_exists()
+                    .col(string(Tag::Id).primary_key().not_null())
+                    .col(string(Tag::Category).not_null())
+                    .col(string(Tag::Name).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecipeTag::Table)
+                    .if_not_exists()
+                    .col(string(RecipeTag::RecipeId).not_null())
+                    .col(string(RecipeTag::TagId).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("FK_recipe_tag_recipe_id")
+                            .from(RecipeTag::Table, RecipeTag::RecipeId)
+                            .to(Recipe::Table, Recipe::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("FK_recipe_tag_tag_id")
+                            .from(RecipeTag::Table, RecipeTag::TagId)
+                            .to(Tag::Table, Tag::Id),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .primary()
+                            .col(RecipeTag::RecipeId)
+                            .col(RecipeTag::TagId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                IndexCreateStatement::new()
+                    .name("idx-recipe-tag-tag-id")
+                    .table(RecipeTag::Table)
+                    .col(RecipeTag::TagId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                IndexDropStatement::new()
+                    .name("idx-recipe-tag-tag-id")
+                    .table(RecipeTag::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RecipeTag::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Tag::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tag {
+    Table,
+    Id,
+    Category,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum RecipeTag {
+    Table,
+    RecipeId,
+    TagId,
+}
+
+#[derive(DeriveIden)]
+enum Recipe {
+    Table,
+    Id,
+}
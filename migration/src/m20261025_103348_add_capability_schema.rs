@@ -0,0 +1,47 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CapabilitySchema::Table)
+                    .if_not_exists()
+                    .col(
+                        string(CapabilitySchema::CapabilityId)
+                            .primary_key()
+                            .not_null(),
+                    )
+                    .col(string(CapabilitySchema::Name).not_null())
+                    .col(integer(CapabilitySchema::Occurrences).not_null())
+                    .col(json(CapabilitySchema::Phases).not_null())
+                    .col(json(CapabilitySchema::Settings).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CapabilitySchema::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum CapabilitySchema {
+    Table,
+    CapabilityId,
+    Name,
+    Occurrences,
+    Phases,
+    Settings,
+}
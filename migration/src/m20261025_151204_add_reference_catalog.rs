@@ -0,0 +1,113 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Capability::Table)
+                    .if_not_exists()
+                    .col(string(Capability::Id).primary_key().not_null())
+                    .col(string(Capability::Name).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CapabilityPhase::Table)
+                    .if_not_exists()
+                    .col(string(CapabilityPhase::Id).primary_key().not_null())
+                    .col(string(CapabilityPhase::Name).not_null())
+                    .col(json(CapabilityPhase::CanFollowPhases).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReferenceSetting::Table)
+                    .if_not_exists()
+                    .col(string(ReferenceSetting::Id).primary_key().not_null())
+                    .col(string(ReferenceSetting::Name).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(NominalValue::Table)
+                    .if_not_exists()
+                    .col(string(NominalValue::SettingId).not_null())
+                    .col(string(NominalValue::Id).not_null())
+                    .col(string(NominalValue::Name).not_null())
+                    .primary_key(
+                        Index::create()
+                            .primary()
+                            .col(NominalValue::SettingId)
+                            .col(NominalValue::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NominalValue::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ReferenceSetting::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CapabilityPhase::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Capability::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Capability {
+    Table,
+    Id,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum CapabilityPhase {
+    Table,
+    Id,
+    Name,
+    CanFollowPhases,
+}
+
+#[derive(DeriveIden)]
+enum ReferenceSetting {
+    Table,
+    Id,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum NominalValue {
+    Table,
+    SettingId,
+    Id,
+    Name,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApplianceProfile::Table)
+                    .if_not_exists()
+                    .col(string(ApplianceProfile::Id).primary_key().not_null())
+                    .col(string(ApplianceProfile::Name).not_null())
+                    .col(json(ApplianceProfile::UserAgents).not_null())
+                    .col(json(ApplianceProfile::Capabilities).not_null())
+                    .col(json(ApplianceProfile::Settings).not_null())
+                    .col(double_null(ApplianceProfile::BowlCapacityMl).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // everything written so far was for the mixer
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Recipe::Table)
+                    .add_column(
+                        string(Recipe::Appliance)
+                            .not_null()
+                            .default("kenwood-cooking-chef"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Recipe::Table)
+                    .drop_column(Recipe::Appliance)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ApplianceProfile::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApplianceProfile {
+    Table,
+    Id,
+    Name,
+    UserAgents,
+    Capabilities,
+    Settings,
+    BowlCapacityMl,
+}
+
+#[derive(DeriveIden)]
+enum Recipe {
+    Table,
+    Appliance,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // times worked out from the steps keep the estimates they used, and
+        // ones typed in by hand have none
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Recipe::Table)
+                    .add_column(json_null(Recipe::Timing).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(Recipe::Table)
+                    .drop_column(Recipe::Timing)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Recipe {
+    Table,
+    Timing,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(ApplianceProfile::Table)
+                    .add_column(json_null(ApplianceProfile::CapabilityLoads).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        // appliances seeded before there were load limits get the built in
+        // ones, just this once so limits cleared afterwards stay cleared
+        db.execute_unprepared(
+            r#"UPDATE appliance_profile
+               SET min_heating_ml = 100,
+                   capability_loads = '[{"capability":"kitchenos:Kenwood:Whisk","min_ml":60.0},{"capability":"kitchenos:Kenwood:DoughKneading","max_ml":2600.0}]'
+               WHERE id = 'kenwood-cooking-chef' AND min_heating_ml IS NULL;"#,
+        )
+        .await?;
+        db.execute_unprepared(
+            "UPDATE appliance_profile SET min_heating_ml = 250
+             WHERE id = 'instant-pot' AND min_heating_ml IS NULL;",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                TableAlterStatement::new()
+                    .table(ApplianceProfile::Table)
+                    .drop_column(ApplianceProfile::CapabilityLoads)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApplianceProfile {
+    Table,
+    CapabilityLoads,
+}
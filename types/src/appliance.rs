@@ -0,0 +1,176 @@
+//! The appliances recipes can be written for. The KitchenOS backend serves
+//! several of them, and each only takes the capabilities and settings it has.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ReferenceSettingId;
+
+/// A setting an appliance has, and the numeric values it accepts for it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SettingLimits {
+    pub id: ReferenceSettingId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+}
+
+/// How much a capability works on, in millilitres, when it needs more or
+/// less than the bowl as a whole, like a whisk that can't reach a splash.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CapabilityLoad {
+    pub capability: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_ml: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ml: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ApplianceProfile {
+    pub id: String,
+    pub name: String,
+    /// Parts of the `User-Agent` the appliance sends, to tell which appliance
+    /// is asking for recipes
+    pub user_agents: Vec<String>,
+    /// Ids of the capabilities it has, or empty if it has all of them
+    pub capabilities: Vec<String>,
+    pub settings: Vec<SettingLimits>,
+    /// How much the bowl holds, in millilitres
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bowl_capacity_ml: Option<f64>,
+    /// The least it can heat without burning or failing to come to
+    /// pressure, in millilitres
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_heating_ml: Option<f64>,
+    /// Loads of particular capabilities, on top of the bowl's
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capability_loads: Vec<CapabilityLoad>,
+}
+
+impl ApplianceProfile {
+    pub const KENWOOD_COOKING_CHEF: &str = "kenwood-cooking-chef";
+    pub const INSTANT_POT: &str = "instant-pot";
+
+    /// The appliances we know of out of the box, which a new database is
+    /// seeded with.
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self {
+                id: Self::KENWOOD_COOKING_CHEF.to_owned(),
+                name: "Kenwood Cooking Chef".to_owned(),
+                user_agents: vec!["Kenwood".to_owned(), "CookingChef".to_owned()],
+                capabilities: vec![],
+                settings: vec![
+                    SettingLimits {
+                        id: ReferenceSettingId::KeepWarm,
+                        min: None,
+                        max: None,
+                    },
+                    SettingLimits {
+                        id: ReferenceSettingId::Temperature,
+                        min: Some(0.0),
+                        max: Some(180.0),
+                    },
+                    SettingLimits {
+                        id: ReferenceSettingId::Speed,
+                        min: None,
+                        max: None,
+                    },
+                    SettingLimits {
+                        id: ReferenceSettingId::Time,
+                        min: Some(1.0),
+                        max: Some(8.0 * 60.0 * 60.0),
+                    },
+                ],
+                bowl_capacity_ml: Some(6700.0),
+                // enough to cover the base of the bowl so it doesn't scorch
+                min_heating_ml: Some(100.0),
+                capability_loads: vec![
+                    // a couple of egg whites, for the whisk to reach
+                    CapabilityLoad {
+                        capability: "kitchenos:Kenwood:Whisk".to_owned(),
+                        min_ml: Some(60.0),
+                        max_ml: None,
+                    },
+                    // about a kilo and a half of flour's worth of dough
+                    CapabilityLoad {
+                        capability: "kitchenos:Kenwood:DoughKneading".to_owned(),
+                        min_ml: None,
+                        max_ml: Some(2600.0),
+                    },
+                ],
+            },
+            Self {
+                id: Self::INSTANT_POT.to_owned(),
+                name: "Instant Pot".to_owned(),
+                // its app goes by Instant Connect
+                user_agents: vec![
+                    "InstantConnect".to_owned(),
+                    "Instant Pot".to_owned(),
+                    "InstantPot".to_owned(),
+                ],
+                capabilities: vec![
+                    "cckg:Bake".to_owned(),
+                    "cckg:Cook".to_owned(),
+                    "cckg:Steam".to_owned(),
+                ],
+                settings: vec![
+                    SettingLimits {
+                        id: ReferenceSettingId::KeepWarm,
+                        min: None,
+                        max: None,
+                    },
+                    SettingLimits {
+                        id: ReferenceSettingId::Temperature,
+                        min: Some(0.0),
+                        max: Some(170.0),
+                    },
+                    SettingLimits {
+                        id: ReferenceSettingId::Time,
+                        min: Some(1.0),
+                        max: Some(24.0 * 60.0 * 60.0),
+                    },
+                ],
+                bowl_capacity_ml: Some(5700.0),
+                // a cup of liquid to build up steam
+                min_heating_ml: Some(250.0),
+                capability_loads: vec![],
+            },
+        ]
+    }
+
+    /// The built in appliance with `id`, or the first one when there's none,
+    /// for recipes written for an appliance that has since been removed.
+    pub fn builtin_or_default(id: &str) -> Self {
+        let mut builtin = Self::builtin();
+        let idx = builtin.iter().position(|a| a.id == id).unwrap_or(0);
+        builtin.swap_remove(idx)
+    }
+
+    pub fn has_capability(&self, id: &str) -> bool {
+        self.capabilities.is_empty() || self.capabilities.iter().any(|c| c == id)
+    }
+
+    /// The limits of a setting, or `None` if the appliance doesn't have it.
+    pub fn setting(&self, id: ReferenceSettingId) -> Option<&SettingLimits> {
+        self.settings.iter().find(|s| s.id == id)
+    }
+
+    /// The load limits of a capability, if it has any of its own.
+    pub fn capability_load(&self, id: &str) -> Option<&CapabilityLoad> {
+        self.capability_loads.iter().find(|l| l.capability == id)
+    }
+
+    /// Whether a request's `User-Agent` came from this appliance.
+    pub fn matches_user_agent(&self, user_agent: &str) -> bool {
+        user_agent_matches(&self.user_agents, user_agent)
+    }
+}
+
+/// Whether a `User-Agent` contains any of an appliance's `fragments`.
+pub fn user_agent_matches(fragments: &[String], user_agent: &str) -> bool {
+    fragments
+        .iter()
+        .any(|a| !a.is_empty() && user_agent.contains(a.as_str()))
+}
@@ -0,0 +1,138 @@
+//! Estimates how full the bowl is at each step, to catch recipes that
+//! overflow it or don't give it enough to heat.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Recipe, appliance::ApplianceProfile, convert::Conversions};
+
+/// What's in the bowl once a step has added its ingredients.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StepLoad {
+    pub step_idx: usize,
+    /// Everything added up to and including this step, in millilitres
+    pub contents_ml: f64,
+    /// Ingredients added so far that take up room we couldn't work out, like
+    /// eggs, so aren't counted
+    pub unmeasured: Vec<String>,
+}
+
+impl StepLoad {
+    /// How full the bowl is, as a fraction of what it holds. Over 1 means
+    /// it overflows.
+    pub fn fill(&self, appliance: &ApplianceProfile) -> Option<f64> {
+        appliance
+            .bowl_capacity_ml
+            .filter(|c| *c > 0.0)
+            .map(|c| self.contents_ml / c)
+    }
+}
+
+/// The bowl's contents step by step. Everything a step adds is taken to go in
+/// the bowl and stay there.
+pub fn bowl_loads(recipe: &Recipe, conversions: &Conversions) -> Vec<StepLoad> {
+    let mut contents_ml = 0.0;
+    let mut unmeasured = Vec::<String>::new();
+
+    recipe
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(step_idx, step)| {
+            for step_ingredient in &step.ingredients {
+                let Some(ingredient) = recipe
+                    .ingredients
+                    .get(step_ingredient.ingredient_idx as usize)
+                else {
+                    continue;
+                };
+
+                match conversions.volume_ml(
+                    &ingredient.reference_ingredient.id,
+                    &step_ingredient.quantity,
+                ) {
+                    Some(ml) => contents_ml += ml,
+                    None => {
+                        let name = &ingredient.reference_ingredient.name;
+                        if !unmeasured.contains(name) {
+                            unmeasured.push(name.clone());
+                        }
+                    }
+                }
+            }
+
+            StepLoad {
+                step_idx,
+                contents_ml,
+                unmeasured: unmeasured.clone(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::fixtures::{eggs, millilitres, quantity, recipe, step};
+
+    fn water() -> Conversions {
+        Conversions {
+            allowed_units: HashMap::from([
+                ("water".to_owned(), vec![millilitres()]),
+                ("egg".to_owned(), vec![eggs()]),
+            ]),
+            densities: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn adds_up_what_each_step_puts_in_the_bowl() {
+        let recipe = recipe(
+            2,
+            vec![
+                ("water", quantity(500.0, &millilitres())),
+                ("egg", quantity(2.0, &eggs())),
+            ],
+            vec![
+                step(vec![(0, quantity(300.0, &millilitres()))]),
+                step(vec![
+                    (0, quantity(200.0, &millilitres())),
+                    (1, quantity(2.0, &eggs())),
+                ]),
+                step(vec![]),
+            ],
+        );
+
+        assert_eq!(
+            bowl_loads(&recipe, &water())
+                .into_iter()
+                .map(|l| (l.step_idx, l.contents_ml, l.unmeasured))
+                .collect::<Vec<_>>(),
+            [
+                (0, 300.0, vec![]),
+                (1, 500.0, vec!["egg".to_owned()]),
+                (2, 500.0, vec!["egg".to_owned()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn overflows_past_what_the_bowl_holds() {
+        let kenwood = ApplianceProfile::builtin_or_default(ApplianceProfile::KENWOOD_COOKING_CHEF);
+        let load = |contents_ml| StepLoad {
+            step_idx: 0,
+            contents_ml,
+            unmeasured: vec![],
+        };
+
+        assert_eq!(load(3350.0).fill(&kenwood), Some(0.5));
+        assert!(load(8000.0).fill(&kenwood).is_some_and(|f| f > 1.0));
+
+        let bottomless = ApplianceProfile {
+            bowl_capacity_ml: None,
+            ..kenwood
+        };
+        assert_eq!(load(8000.0).fill(&bottomless), None);
+    }
+}
@@ -0,0 +1,95 @@
+//! The capabilities, phases, settings and values the editor offers, which are
+//! kept in the database so new attachments don't need a new build.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CapabilityPhase, KnownOptions as _, ReferenceCapability, ReferenceSetting, ReferenceSettingId,
+    ReferenceValue,
+};
+
+/// A value a nominal setting can take, like a stir speed.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NominalValue {
+    pub setting: ReferenceSettingId,
+    pub value: ReferenceValue,
+    /// Where it comes among the setting's values, like speeds from slowest
+    /// to fastest. Ones without a position go after the rest.
+    #[serde(default)]
+    pub position: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Catalog {
+    pub capabilities: Vec<ReferenceCapability>,
+    pub phases: Vec<CapabilityPhase>,
+    pub settings: Vec<ReferenceSetting>,
+    pub values: Vec<NominalValue>,
+}
+
+impl Catalog {
+    /// What's compiled in, which a new database is seeded with.
+    pub fn builtin() -> Self {
+        Self {
+            capabilities: ReferenceCapability::known_options(),
+            phases: CapabilityPhase::known_options(),
+            settings: [
+                ReferenceSettingId::KeepWarm,
+                ReferenceSettingId::Temperature,
+                ReferenceSettingId::Speed,
+                ReferenceSettingId::Time,
+            ]
+            .into_iter()
+            .map(ReferenceSettingId::reference_setting)
+            .collect(),
+            values: ReferenceValue::stir_settings()
+                .into_iter()
+                .enumerate()
+                .map(|(idx, value)| NominalValue {
+                    setting: ReferenceSettingId::Speed,
+                    value,
+                    position: Some(idx as i64),
+                })
+                .chain([NominalValue {
+                    setting: ReferenceSettingId::Temperature,
+                    value: ReferenceValue::temperature_off(),
+                    position: Some(0),
+                }])
+                .collect(),
+        }
+    }
+
+    /// The values a nominal setting can take, in order.
+    pub fn values_of(&self, setting: ReferenceSettingId) -> Vec<ReferenceValue> {
+        self.values
+            .iter()
+            .filter(|v| v.setting == setting)
+            .map(|v| v.value.clone())
+            .collect()
+    }
+
+    /// A setting as it's named in the catalog.
+    pub fn setting(&self, id: ReferenceSettingId) -> ReferenceSetting {
+        self.settings
+            .iter()
+            .find(|s| s.id == id)
+            .cloned()
+            .unwrap_or_else(|| id.reference_setting())
+    }
+
+    /// Whether `value` is one of the values `setting` can take.
+    pub fn has_value(&self, setting: ReferenceSettingId, value: &ReferenceValue) -> bool {
+        self.values
+            .iter()
+            .any(|v| v.setting == setting && v.value.id == value.id)
+    }
+
+    /// Puts the values of each setting in order, by position then name.
+    pub fn sort_values(&mut self) {
+        self.values.sort_by(|a, b| {
+            (a.setting.to_string(), a.position.is_none(), a.position)
+                .cmp(&(b.setting.to_string(), b.position.is_none(), b.position))
+                .then_with(|| a.value.name.cmp(&b.value.name))
+        });
+    }
+}
@@ -0,0 +1,451 @@
+//! Converts quantities between units: within mass, volume and count, and from
+//! one to the other of mass and volume for ingredients whose density is known.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Ingredient, IngredientAllowedUnit, Quantity, Recipe};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Mass,
+    Volume,
+    Count,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, strum::Display)]
+pub enum MeasurementSystem {
+    #[strum(to_string = "metric")]
+    Metric,
+    #[strum(to_string = "US customary")]
+    UsCustomary,
+}
+
+impl MeasurementSystem {
+    /// The system a unit was ingested with, by the id it's stored under.
+    /// Others, like imperial, have units the same names as ours but not the
+    /// same sizes, so aren't converted.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "cckg:Metric" => Some(Metric),
+            "cckg:USCustomary" => Some(UsCustomary),
+            _ => None,
+        }
+    }
+}
+
+use Dimension::{Mass, Volume};
+use MeasurementSystem::{Metric, UsCustomary};
+
+/// Units we know the size of, by abbreviation and name, in grams or
+/// millilitres.
+const KNOWN_UNITS: [(&[&str], Dimension, MeasurementSystem, f64); 16] = [
+    (&["mg", "milligram"], Mass, Metric, 0.001),
+    (&["g", "gram"], Mass, Metric, 1.0),
+    (&["kg", "kilogram"], Mass, Metric, 1000.0),
+    (&["oz", "ounce"], Mass, UsCustomary, 28.349523125),
+    (&["lb", "pound"], Mass, UsCustomary, 453.59237),
+    (&["ml", "millilitre", "milliliter"], Volume, Metric, 1.0),
+    (&["cl", "centilitre", "centiliter"], Volume, Metric, 10.0),
+    (&["dl", "decilitre", "deciliter"], Volume, Metric, 100.0),
+    (&["l", "litre", "liter"], Volume, Metric, 1000.0),
+    (&["tsp", "teaspoon"], Volume, UsCustomary, 4.92892159375),
+    (&["tbsp", "tablespoon"], Volume, UsCustomary, 14.78676478125),
+    (
+        &["fl oz", "fluid ounce"],
+        Volume,
+        UsCustomary,
+        29.5735295625,
+    ),
+    (&["cup"], Volume, UsCustomary, 236.5882365),
+    (&["pt", "pint"], Volume, UsCustomary, 473.176473),
+    (&["qt", "quart"], Volume, UsCustomary, 946.352946),
+    (&["gal", "gallon"], Volume, UsCustomary, 3785.411784),
+];
+
+/// What a unit measures, and how big it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitScale {
+    pub dimension: Dimension,
+    pub system: Option<MeasurementSystem>,
+    /// How many grams or millilitres it is. Counted units, like cloves,
+    /// only convert to themselves so have none.
+    pub to_base: Option<f64>,
+}
+
+fn known_unit(unit: &IngredientAllowedUnit) -> Option<(Dimension, MeasurementSystem, f64)> {
+    let names = [
+        unit.abbreviation.as_deref().unwrap_or_default(),
+        unit.name.as_str(),
+    ]
+    .map(|n| n.trim().trim_end_matches('.').to_lowercase());
+
+    KNOWN_UNITS
+        .iter()
+        .find(|(known, ..)| names.iter().any(|n| known.contains(&n.as_str())))
+        .map(|(_, dimension, system, to_base)| (*dimension, *system, *to_base))
+}
+
+/// What a unit measures, going by the dimension and measurement system it was
+/// ingested with, and by its name when those don't say.
+pub fn scale(unit: &IngredientAllowedUnit) -> Option<UnitScale> {
+    let known = known_unit(unit);
+
+    let dimension = unit
+        .dimension
+        .as_deref()
+        .map(str::to_lowercase)
+        .and_then(|d| {
+            if d.contains("mass") || d.contains("weight") {
+                Some(Mass)
+            } else if d.contains("volume") {
+                Some(Volume)
+            } else if d.contains("count") || d.contains("quantity") || d.contains("piece") {
+                Some(Dimension::Count)
+            } else {
+                None
+            }
+        })
+        .or(known.map(|(d, ..)| d))?;
+
+    let system = match unit.measurement_system.as_deref() {
+        Some(id) => MeasurementSystem::from_id(id),
+        None => known.map(|(_, s, _)| s),
+    };
+
+    Some(UnitScale {
+        dimension,
+        system,
+        // a unit measuring something other than what we know it as, or from
+        // another system, like an imperial pint, can't use our size for it
+        to_base: known
+            .filter(|(d, s, _)| *d == dimension && system == Some(*s))
+            .map(|(.., to_base)| to_base),
+    })
+}
+
+/// `amount` of `from` in `to`, if the two can be converted. `density` is in
+/// grams per millilitre, and lets mass and volume be converted.
+pub fn convert(
+    amount: f64,
+    from: &IngredientAllowedUnit,
+    to: &IngredientAllowedUnit,
+    density: Option<f64>,
+) -> Option<f64> {
+    if from.id == to.id {
+        return Some(amount);
+    }
+
+    let (from, to) = (scale(from)?, scale(to)?);
+    let base = amount * from.to_base?;
+    let base = match (from.dimension, to.dimension) {
+        (a, b) if a == b => base,
+        (Mass, Volume) => base / density.filter(|d| *d > 0.0)?,
+        (Volume, Mass) => base * density?,
+        _ => return None,
+    };
+
+    Some(base / to.to_base?)
+}
+
+/// How heavy an ingredient is for its volume.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IngredientDensity {
+    pub ingredient: Ingredient,
+    pub grams_per_ml: f64,
+}
+
+/// What's needed to convert the ingredients of a recipe.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Conversions {
+    /// The units each ingredient can be given in, by ingredient id
+    pub allowed_units: HashMap<String, Vec<IngredientAllowedUnit>>,
+    /// Grams per millilitre, by ingredient id
+    pub densities: HashMap<String, f64>,
+}
+
+impl Conversions {
+    /// A unit as the ingredient allows it, or as any other ingredient does.
+    fn unit(&self, ingredient_id: &str, unit_id: &str) -> Option<&IngredientAllowedUnit> {
+        let allowed = self.allowed_units.get(ingredient_id);
+
+        allowed
+            .into_iter()
+            .chain(self.allowed_units.values())
+            .flatten()
+            .find(|u| u.id == unit_id)
+    }
+
+    /// How much of the ingredient `quantity` is in one of its allowed units.
+    pub fn convert(
+        &self,
+        ingredient_id: &str,
+        quantity: &Quantity,
+        to_unit_id: &str,
+    ) -> Option<f64> {
+        let from = self.unit(ingredient_id, &quantity.reference_unit.id)?;
+        let to = self
+            .allowed_units
+            .get(ingredient_id)?
+            .iter()
+            .find(|u| u.id == to_unit_id)?;
+
+        convert(
+            quantity.amount?,
+            from,
+            to,
+            self.densities.get(ingredient_id).copied(),
+        )
+    }
+
+    /// How much room a quantity of the ingredient takes up, in millilitres.
+    /// Weights go by its density, or water's when that isn't known.
+    pub fn volume_ml(&self, ingredient_id: &str, quantity: &Quantity) -> Option<f64> {
+        let unit = self.unit(ingredient_id, &quantity.reference_unit.id)?;
+        let scale = scale(unit)?;
+        let base = quantity.amount? * scale.to_base?;
+
+        match scale.dimension {
+            Volume => Some(base),
+            Mass => {
+                let density = self
+                    .densities
+                    .get(ingredient_id)
+                    .copied()
+                    .filter(|d| *d > 0.0)
+                    .unwrap_or(1.0);
+                Some(base / density)
+            }
+            Dimension::Count => None,
+        }
+    }
+
+    /// The quantity in whichever of the ingredient's allowed units of
+    /// `system` reads best, keeping to mass or volume when it can. Quantities
+    /// already in that system, or of counted units, come back as they are.
+    pub fn to_system(
+        &self,
+        ingredient_id: &str,
+        quantity: &Quantity,
+        system: MeasurementSystem,
+        locale: &str,
+    ) -> Option<Quantity> {
+        let Some(amount) = quantity.amount else {
+            return Some(quantity.clone());
+        };
+        let from = self.unit(ingredient_id, &quantity.reference_unit.id)?;
+        let from_scale = scale(from)?;
+        if from_scale.system.is_none_or(|s| s == system) {
+            return Some(quantity.clone());
+        }
+
+        let density = self.densities.get(ingredient_id).copied();
+        let mut candidates = self
+            .allowed_units
+            .get(ingredient_id)?
+            .iter()
+            .filter_map(|u| {
+                let scale = scale(u)?;
+                (scale.system == Some(system)).then_some(())?;
+                Some((u, scale, convert(amount, from, u, density)?))
+            })
+            .collect::<Vec<_>>();
+
+        // the same dimension when there's a choice, then the biggest unit
+        // that's still at least one of it
+        let same_dimension = candidates
+            .iter()
+            .any(|(_, s, _)| s.dimension == from_scale.dimension);
+        candidates.retain(|(_, s, _)| !same_dimension || s.dimension == from_scale.dimension);
+        candidates.sort_by(|(_, a, _), (_, b, _)| {
+            a.to_base
+                .unwrap_or_default()
+                .total_cmp(&b.to_base.unwrap_or_default())
+        });
+        let (unit, _, amount) = candidates
+            .iter()
+            .rev()
+            .find(|(_, _, amount)| *amount >= 1.0)
+            .or(candidates.first())?;
+
+        let reference_unit = (*unit).clone().as_reference_unit();
+        let mut converted = Quantity {
+            amount: Some(crate::quantity::round(*amount, &reference_unit)),
+            reference_unit,
+            text: String::new(),
+        };
+        converted.sync_text(locale);
+
+        Some(converted)
+    }
+}
+
+impl Recipe {
+    /// The ids of the ingredients it uses, once each, to load their
+    /// conversions with.
+    pub fn ingredient_ids(&self) -> Vec<String> {
+        let mut ids = self
+            .ingredients
+            .iter()
+            .map(|i| i.reference_ingredient.id.clone())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// Converts every quantity to `system`, using only the units each
+    /// ingredient allows. Gives back the names of ingredients that couldn't
+    /// be converted, which are left as they were.
+    pub fn convert_units(
+        &mut self,
+        system: MeasurementSystem,
+        conversions: &Conversions,
+    ) -> Vec<String> {
+        let locale = self.locale.clone();
+        let original = self.ingredients.clone();
+        let mut failed = Vec::new();
+
+        for ingredient in &mut self.ingredients {
+            match conversions.to_system(
+                &ingredient.reference_ingredient.id,
+                &ingredient.quantity,
+                system,
+                &locale,
+            ) {
+                Some(quantity) => ingredient.quantity = quantity,
+                None => failed.push(ingredient.reference_ingredient.name.clone()),
+            }
+        }
+
+        self.remap_step_quantities(&original, |before, quantity| {
+            let Some(before) = before else {
+                return;
+            };
+
+            match conversions.to_system(&before.reference_ingredient.id, quantity, system, &locale)
+            {
+                Some(converted) => *quantity = converted,
+                None => failed.push(before.reference_ingredient.name.clone()),
+            }
+        });
+
+        failed.sort();
+        failed.dedup();
+        failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{
+        allowed, cups, eggs, grams, millilitres, ounces, quantity, recipe, step,
+    };
+
+    fn close(a: Option<f64>, b: f64) -> bool {
+        a.is_some_and(|a| (a - b).abs() < 0.01)
+    }
+
+    fn flour() -> Conversions {
+        Conversions {
+            allowed_units: HashMap::from([(
+                "flour".to_owned(),
+                vec![grams(), ounces(), cups(), millilitres()],
+            )]),
+            densities: HashMap::from([("flour".to_owned(), 0.5)]),
+        }
+    }
+
+    #[test]
+    fn scales_units() {
+        let scale = scale(&cups()).unwrap();
+        assert_eq!(scale.dimension, Volume);
+        assert_eq!(scale.system, Some(UsCustomary));
+        assert!(close(scale.to_base, 236.59));
+
+        let scale = super::scale(&eggs()).unwrap();
+        assert_eq!(scale.dimension, Dimension::Count);
+        assert_eq!(scale.to_base, None);
+    }
+
+    #[test]
+    fn keeps_other_systems_apart() {
+        let pint = allowed("pt", "pint", "pt", Some("volume"), Some("cckg:Imperial"));
+        let scale = scale(&pint).unwrap();
+        assert_eq!(scale.system, None);
+        assert_eq!(scale.to_base, None);
+        assert_eq!(convert(1.0, &pint, &millilitres(), None), None);
+
+        // nothing ingested to go by, so it goes by the name
+        let pint = allowed("pt", "pint", "pt", None, None);
+        assert_eq!(super::scale(&pint).unwrap().system, Some(UsCustomary));
+    }
+
+    #[test]
+    fn converts_within_a_dimension() {
+        assert!(close(convert(1.0, &cups(), &millilitres(), None), 236.59));
+        assert!(close(convert(1.0, &ounces(), &grams(), None), 28.35));
+        assert_eq!(convert(3.0, &eggs(), &eggs(), None), Some(3.0));
+    }
+
+    #[test]
+    fn converts_between_mass_and_volume_by_density() {
+        assert!(close(
+            convert(100.0, &grams(), &millilitres(), Some(0.5)),
+            200.0
+        ));
+        assert!(close(
+            convert(100.0, &millilitres(), &grams(), Some(0.5)),
+            50.0
+        ));
+        assert_eq!(convert(100.0, &grams(), &millilitres(), None), None);
+        assert_eq!(convert(2.0, &eggs(), &grams(), Some(1.0)), None);
+    }
+
+    #[test]
+    fn converts_to_a_system_in_the_same_dimension() {
+        let converted = flour()
+            .to_system("flour", &quantity(250.0, &grams()), UsCustomary, "en")
+            .unwrap();
+        assert_eq!(converted.reference_unit.id, "oz");
+        assert_eq!(converted.amount, Some(8.75));
+        assert_eq!(converted.text, "8¾ oz");
+
+        // already there
+        let cup = quantity(1.0, &cups());
+        assert_eq!(
+            flour().to_system("flour", &cup, UsCustomary, "en"),
+            Some(cup)
+        );
+    }
+
+    #[test]
+    fn converts_a_recipe_keeping_steps_matched() {
+        let mut recipe = recipe(
+            2,
+            vec![("flour", quantity(250.0, &grams()))],
+            vec![step(vec![(0, quantity(250.0, &grams()))])],
+        );
+
+        let failed = recipe.convert_units(UsCustomary, &flour());
+
+        assert!(failed.is_empty());
+        assert_eq!(recipe.ingredients[0].quantity.reference_unit.id, "oz");
+        assert_eq!(
+            recipe.steps[0].ingredients[0].quantity,
+            recipe.ingredients[0].quantity
+        );
+    }
+
+    #[test]
+    fn reports_what_it_cant_convert() {
+        let mut recipe = recipe(2, vec![("sugar", quantity(100.0, &grams()))], vec![]);
+
+        let failed = recipe.convert_units(UsCustomary, &flour());
+
+        assert_eq!(failed, vec!["sugar".to_owned()]);
+        assert_eq!(recipe.ingredients[0].quantity.reference_unit.id, "g");
+    }
+}
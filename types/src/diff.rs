@@ -0,0 +1,271 @@
+//! Structured differences between two versions of a recipe, for showing what
+//! changed between revisions.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Recipe, RecipeIngredient, RecipeStep, SettingValue};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Change {
+    /// Where in the recipe the change is, like `Step 2 › Speed`
+    pub path: String,
+    /// `None` when the thing was added
+    pub before: Option<String>,
+    /// `None` when the thing was removed
+    pub after: Option<String>,
+}
+
+struct Changes(Vec<Change>);
+
+impl Changes {
+    fn field(&mut self, path: impl Into<String>, before: String, after: String) {
+        if before != after {
+            self.0.push(Change {
+                path: path.into(),
+                before: Some(before),
+                after: Some(after),
+            });
+        }
+    }
+
+    fn added(&mut self, path: impl Into<String>, after: String) {
+        self.0.push(Change {
+            path: path.into(),
+            before: None,
+            after: Some(after),
+        });
+    }
+
+    fn removed(&mut self, path: impl Into<String>, before: String) {
+        self.0.push(Change {
+            path: path.into(),
+            before: Some(before),
+            after: None,
+        });
+    }
+}
+
+fn duration(d: Option<jiff::SignedDuration>) -> String {
+    d.map(|d| format!("{d:#}")).unwrap_or_default()
+}
+
+fn setting_text(value: &SettingValue) -> &str {
+    match value {
+        SettingValue::Numeric { text, .. }
+        | SettingValue::Boolean { text, .. }
+        | SettingValue::Nominal { text, .. } => text,
+    }
+}
+
+fn ingredient_summary(ingredient: &RecipeIngredient) -> String {
+    format!(
+        "{} {}",
+        ingredient.quantity.text, ingredient.reference_ingredient.name
+    )
+}
+
+fn step_ingredients(step: &RecipeStep, ingredients: &[RecipeIngredient]) -> String {
+    step.ingredients
+        .iter()
+        .map(|i| {
+            let name = ingredients
+                .get(i.ingredient_idx as usize)
+                .map_or("?", |x| x.reference_ingredient.name.as_str());
+            format!("{} {name}", i.quantity.text)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn diff_ingredient(
+    changes: &mut Changes,
+    path: &str,
+    before: &RecipeIngredient,
+    after: &RecipeIngredient,
+) {
+    changes.field(
+        format!("{path} › Ingredient"),
+        before.reference_ingredient.name.clone(),
+        after.reference_ingredient.name.clone(),
+    );
+    changes.field(
+        format!("{path} › Quantity"),
+        before.quantity.text.clone(),
+        after.quantity.text.clone(),
+    );
+
+    let preparations = |i: &RecipeIngredient| {
+        i.reference_preparations
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    changes.field(
+        format!("{path} › Preparations"),
+        preparations(before),
+        preparations(after),
+    );
+    changes.field(
+        format!("{path} › Description"),
+        before.source_text.clone().unwrap_or_default(),
+        after.source_text.clone().unwrap_or_default(),
+    );
+    changes.field(
+        format!("{path} › Images"),
+        before.media.len().to_string(),
+        after.media.len().to_string(),
+    );
+}
+
+fn diff_step(
+    changes: &mut Changes,
+    path: &str,
+    before: (&RecipeStep, &Recipe),
+    after: (&RecipeStep, &Recipe),
+) {
+    let (before, before_recipe) = before;
+    let (after, after_recipe) = after;
+
+    changes.field(
+        format!("{path} › Instructions"),
+        before.text.clone(),
+        after.text.clone(),
+    );
+
+    let capability = |s: &RecipeStep| {
+        s.capability
+            .as_ref()
+            .map(|c| c.reference_capability.name.clone())
+            .unwrap_or_default()
+    };
+    changes.field(
+        format!("{path} › Capability"),
+        capability(before),
+        capability(after),
+    );
+
+    let phase = |s: &RecipeStep| {
+        s.capability
+            .as_ref()
+            .map(|c| c.phase.name.clone())
+            .unwrap_or_default()
+    };
+    changes.field(format!("{path} › Phase"), phase(before), phase(after));
+
+    let settings = |s: &RecipeStep| {
+        s.capability
+            .iter()
+            .flat_map(|c| &c.settings)
+            .map(|x| {
+                (
+                    x.reference_setting.name.clone(),
+                    setting_text(&x.value).to_owned(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let before_settings = settings(before);
+    let after_settings = settings(after);
+    let names = before_settings
+        .iter()
+        .chain(&after_settings)
+        .map(|(name, _)| name.clone())
+        .collect::<BTreeSet<_>>();
+    for name in names {
+        let find = |settings: &[(String, String)]| {
+            settings
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.clone())
+        };
+        let setting_path = format!("{path} › {name}");
+
+        match (find(&before_settings), find(&after_settings)) {
+            (Some(b), Some(a)) => changes.field(setting_path, b, a),
+            (Some(b), None) => changes.removed(setting_path, b),
+            (None, Some(a)) => changes.added(setting_path, a),
+            (None, None) => {}
+        }
+    }
+
+    changes.field(
+        format!("{path} › Ingredients"),
+        step_ingredients(before, &before_recipe.ingredients),
+        step_ingredients(after, &after_recipe.ingredients),
+    );
+    changes.field(
+        format!("{path} › Images"),
+        before.media.len().to_string(),
+        after.media.len().to_string(),
+    );
+}
+
+/// Lists what changed going from `before` to `after`. Ingredients and steps
+/// are compared by position.
+pub fn diff_recipes(before: &Recipe, after: &Recipe) -> Vec<Change> {
+    let mut changes = Changes(Vec::new());
+
+    changes.field("Name", before.name.clone(), after.name.clone());
+    changes.field(
+        "Description",
+        before.description.clone(),
+        after.description.clone(),
+    );
+    changes.field(
+        "Serves",
+        before.serves.to_string(),
+        after.serves.to_string(),
+    );
+    changes.field("Locale", before.locale.clone(), after.locale.clone());
+    changes.field(
+        "Prep time",
+        duration(before.prep_time),
+        duration(after.prep_time),
+    );
+    changes.field(
+        "Cook time",
+        duration(before.cook_time),
+        duration(after.cook_time),
+    );
+    changes.field(
+        "Total time",
+        duration(Some(before.total_time)),
+        duration(Some(after.total_time)),
+    );
+
+    let tags = |r: &Recipe| {
+        r.reference_tags
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    changes.field("Tags", tags(before), tags(after));
+
+    for idx in 0..before.ingredients.len().max(after.ingredients.len()) {
+        let path = format!("Ingredient {}", idx + 1);
+
+        match (before.ingredients.get(idx), after.ingredients.get(idx)) {
+            (Some(b), Some(a)) => diff_ingredient(&mut changes, &path, b, a),
+            (Some(b), None) => changes.removed(path, ingredient_summary(b)),
+            (None, Some(a)) => changes.added(path, ingredient_summary(a)),
+            (None, None) => {}
+        }
+    }
+
+    for idx in 0..before.steps.len().max(after.steps.len()) {
+        let path = format!("Step {}", idx + 1);
+
+        match (before.steps.get(idx), after.steps.get(idx)) {
+            (Some(b), Some(a)) => diff_step(&mut changes, &path, (b, before), (a, after)),
+            (Some(b), None) => changes.removed(path, b.text.clone()),
+            (None, Some(a)) => changes.added(path, a.text.clone()),
+            (None, None) => {}
+        }
+    }
+
+    changes.0
+}
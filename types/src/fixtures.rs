@@ -0,0 +1,176 @@
+//! Small recipes and units for the tests to work on.
+
+use crate::{
+    Author, CapabilityPhase, CapabilitySetting, Ingredient, IngredientAllowedUnit, Quantity,
+    Recipe, RecipeIngredient, RecipeStep, ReferenceCapability, ReferenceSetting,
+    ReferenceSettingId, ReferenceUnit, SettingValue, StepCapability, StepIngredient, UID,
+};
+
+/// A unit as ingredients allow it, with the dimension and measurement system
+/// it was ingested with.
+pub fn allowed(
+    id: &str,
+    name: &str,
+    abbreviation: &str,
+    dimension: Option<&str>,
+    system: Option<&str>,
+) -> IngredientAllowedUnit {
+    IngredientAllowedUnit {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        abbreviation: (!abbreviation.is_empty()).then(|| abbreviation.to_owned()),
+        dimension: dimension.map(str::to_owned),
+        measurement_system: system.map(str::to_owned),
+    }
+}
+
+pub fn grams() -> IngredientAllowedUnit {
+    allowed("g", "gram", "g", Some("mass"), Some("cckg:Metric"))
+}
+
+pub fn millilitres() -> IngredientAllowedUnit {
+    allowed(
+        "ml",
+        "millilitre",
+        "ml",
+        Some("volume"),
+        Some("cckg:Metric"),
+    )
+}
+
+pub fn cups() -> IngredientAllowedUnit {
+    allowed(
+        "cup",
+        "cup",
+        "cup",
+        Some("volume"),
+        Some("cckg:USCustomary"),
+    )
+}
+
+pub fn ounces() -> IngredientAllowedUnit {
+    allowed("oz", "ounce", "oz", Some("mass"), Some("cckg:USCustomary"))
+}
+
+pub fn eggs() -> IngredientAllowedUnit {
+    allowed("egg", "egg", "", Some("count"), None)
+}
+
+pub fn unit(unit: &IngredientAllowedUnit) -> ReferenceUnit {
+    unit.clone().as_reference_unit()
+}
+
+pub fn quantity(amount: f64, unit: &IngredientAllowedUnit) -> Quantity {
+    let mut quantity = Quantity {
+        amount: Some(amount),
+        reference_unit: self::unit(unit),
+        text: String::new(),
+    };
+    quantity.sync_text("en");
+    quantity
+}
+
+/// A step using some of the listed ingredients, by index.
+pub fn step(ingredients: Vec<(u8, Quantity)>) -> RecipeStep {
+    RecipeStep {
+        uid: UID::new(),
+        capability: None,
+        ingredients: ingredients
+            .into_iter()
+            .map(|(ingredient_idx, quantity)| StepIngredient {
+                uid: UID::new(),
+                ingredient_idx,
+                quantity,
+            })
+            .collect(),
+        media: vec![],
+        source_text: None,
+        text: String::new(),
+    }
+}
+
+/// A step run on the appliance with some numeric settings, like a
+/// temperature or time.
+pub fn capability_step(
+    ingredients: Vec<(u8, Quantity)>,
+    settings: Vec<(ReferenceSettingId, f64)>,
+) -> RecipeStep {
+    RecipeStep {
+        capability: Some(StepCapability {
+            phase: CapabilityPhase {
+                can_follow_phases: vec![],
+                id: "cckg:ExecutionPhase".to_owned(),
+                name: "Execution".to_owned(),
+            },
+            reference_capability: ReferenceCapability {
+                id: "cckg:Cook".to_owned(),
+                name: "Cook".to_owned(),
+            },
+            settings: settings
+                .into_iter()
+                .map(|(id, value)| CapabilitySetting {
+                    uid: UID::new(),
+                    reference_setting: ReferenceSetting {
+                        id,
+                        name: id.to_string(),
+                    },
+                    value: SettingValue::Numeric {
+                        reference_unit: None,
+                        text: value.to_string(),
+                        value,
+                    },
+                })
+                .collect(),
+        }),
+        ..step(ingredients)
+    }
+}
+
+/// A recipe for `serves` listing ingredients by id and quantity.
+pub fn recipe(serves: u8, ingredients: Vec<(&str, Quantity)>, steps: Vec<RecipeStep>) -> Recipe {
+    Recipe {
+        appliance: crate::appliance::ApplianceProfile::KENWOOD_COOKING_CHEF.to_owned(),
+        author: Author {
+            id: None,
+            image: String::new(),
+            name: String::new(),
+            url: String::new(),
+        },
+        created_at: chrono::DateTime::UNIX_EPOCH,
+        created_by_id: String::new(),
+        description: String::new(),
+        etag: String::new(),
+        forked_into_other_locales: vec![],
+        id: "recipe".to_owned(),
+        ingredients: ingredients
+            .into_iter()
+            .map(|(id, quantity)| RecipeIngredient {
+                uid: UID::new(),
+                quantity,
+                reference_ingredient: Ingredient {
+                    id: id.to_owned(),
+                    name: id.to_owned(),
+                },
+                reference_preparations: vec![],
+                media: vec![],
+                source_text: None,
+            })
+            .collect(),
+        locale: "en".to_owned(),
+        modified_at: chrono::DateTime::UNIX_EPOCH,
+        name: String::new(),
+        organization_id: String::new(),
+        published_at: None,
+        reference_tags: vec![],
+        serves,
+        state: Recipe::DRAFT.to_owned(),
+        steps,
+        timing: None,
+        total_time: jiff::SignedDuration::ZERO,
+        visibility: crate::default_visibility(),
+        cook_time: None,
+        prep_time: None,
+        referenced: None,
+        requester_role: None,
+    }
+}
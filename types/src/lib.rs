@@ -1,3 +1,16 @@
+pub mod appliance;
+pub mod capacity;
+pub mod catalog;
+pub mod convert;
+pub mod diff;
+#[cfg(test)]
+mod fixtures;
+pub mod quantity;
+pub mod reconcile;
+pub mod scale;
+pub mod schema;
+pub mod timing;
+pub mod validate;
 pub mod values;
 use rand::distr::SampleString as _;
 pub use values::KnownOptions;
@@ -47,6 +60,9 @@ pub struct IngredientAllowedUnit {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dimension: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measurement_system: Option<String>,
 }
 
 impl IngredientAllowedUnit {
@@ -67,11 +83,63 @@ pub struct Ingredient {
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
 pub struct Author {
+    /// Which of our author profiles this is, for custom recipes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
     pub image: String,
     pub name: String,
     pub url: String,
 }
 
+impl Author {
+    /// One of our author profiles as recipes are served with them, pointing
+    /// at their uploaded avatar when they have one.
+    pub fn served(id: i64, name: String, image: String, url: String, has_avatar: bool) -> Self {
+        Self {
+            id: Some(id),
+            name,
+            image: if has_avatar {
+                AuthorProfile::avatar_path(id)
+            } else {
+                image
+            },
+            url,
+        }
+    }
+}
+
+/// An author as managed on the authors page.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
+pub struct AuthorProfile {
+    pub id: i64,
+    pub name: String,
+    /// Where the author's picture is when they don't have an uploaded avatar
+    pub image: String,
+    pub url: String,
+    pub has_avatar: bool,
+    /// Whether new recipes are written by this author
+    pub is_default: bool,
+    pub recipe_count: u64,
+}
+
+impl AuthorProfile {
+    /// Where the proxy serves an author's uploaded avatar from.
+    pub fn avatar_path(id: i64) -> String {
+        format!("/media/images/authors/{id}")
+    }
+
+    /// The author as recipes are served with them.
+    pub fn to_author(&self) -> Author {
+        Author::served(
+            self.id,
+            self.name.clone(),
+            self.image.clone(),
+            self.url.clone(),
+            self.has_avatar,
+        )
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
 pub struct ForkedIntoOtherLocale {
     pub id: String,
@@ -91,6 +159,23 @@ pub struct ReferencePreparation {
     pub name: String,
 }
 
+/// An image attached to a step or ingredient.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
+pub struct Media {
+    #[serde(default)]
+    pub id: String,
+
+    #[serde(rename = "type", default = "default_media_type")]
+    pub kind: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+fn default_media_type() -> String {
+    "image".to_owned()
+}
+
 #[serde_with::serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
 pub struct RecipeIngredient {
@@ -105,6 +190,10 @@ pub struct RecipeIngredient {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub reference_preparations: Vec<ReferencePreparation>,
 
+    #[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub media: Vec<Media>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_text: Option<String>,
 }
@@ -130,7 +219,16 @@ pub struct ReferenceCapability {
 }
 
 #[derive(
-    Debug, Serialize, Deserialize, Copy, Clone, PartialEq, strum::Display, strum::EnumString,
+    Debug,
+    Serialize,
+    Deserialize,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    strum::Display,
+    strum::EnumString,
 )]
 pub enum ReferenceSettingId {
     #[serde(rename = "kitchenos:Kenwood:KeepWarmSetting")]
@@ -236,6 +334,10 @@ pub struct RecipeStep {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub ingredients: Vec<StepIngredient>,
 
+    #[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub media: Vec<Media>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_text: Option<String>,
     pub text: String,
@@ -243,6 +345,9 @@ pub struct RecipeStep {
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
 pub struct Recipe {
+    /// Id of the `ApplianceProfile` the recipe is written for
+    #[serde(default = "default_appliance")]
+    pub appliance: String,
     pub author: Author,
     pub created_at: DateTime<Utc>,
     pub created_by_id: String,
@@ -263,6 +368,10 @@ pub struct Recipe {
     pub serves: u8,
     pub state: String,
     pub steps: Vec<RecipeStep>,
+    /// What the times are worked out from the steps with, or `None` when
+    /// they're typed in by hand
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<timing::TimingEstimates>,
     #[serde(with = "span_field_wise")]
     pub total_time: jiff::SignedDuration,
     #[serde(default = "default_visibility")]
@@ -289,10 +398,46 @@ pub struct Recipe {
     pub requester_role: Option<String>,
 }
 
+fn default_appliance() -> String {
+    appliance::ApplianceProfile::KENWOOD_COOKING_CHEF.to_owned()
+}
+
 fn default_visibility() -> String {
     return "all-users".to_owned();
 }
 
+impl Recipe {
+    pub const DRAFT: &str = "draft";
+    pub const PUBLISHED: &str = "published";
+
+    /// Whether the device should see this recipe at `now`. Drafts never are,
+    /// and published recipes only once their publication time has passed.
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.state == Self::PUBLISHED && self.published_at.is_none_or(|at| at <= now)
+    }
+
+    /// Carries a change to the listed ingredients, which were `original`
+    /// before it, over to the steps. Steps using the whole of an ingredient
+    /// keep matching it, and the rest go through `change` with the
+    /// ingredient as it was, if it's listed.
+    pub(crate) fn remap_step_quantities(
+        &mut self,
+        original: &[RecipeIngredient],
+        mut change: impl FnMut(Option<&RecipeIngredient>, &mut Quantity),
+    ) {
+        for step_ingredient in self.steps.iter_mut().flat_map(|s| &mut s.ingredients) {
+            let idx = step_ingredient.ingredient_idx as usize;
+
+            match (original.get(idx), self.ingredients.get(idx)) {
+                (Some(before), Some(after)) if step_ingredient.quantity == before.quantity => {
+                    step_ingredient.quantity = after.quantity.clone();
+                }
+                (before, _) => change(before, &mut step_ingredient.quantity),
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
 pub struct RecipeItem {
     pub id: String,
@@ -303,6 +448,53 @@ pub struct RecipeItem {
     pub total_time: jiff::SignedDuration,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
+pub struct RecipeRevision {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+
+    /// Who saved this revision, when we could tell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    pub etag: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
+pub struct TrashedRecipe {
+    pub id: String,
+    pub name: String,
+    pub author_name: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// What happened when the editor tried to save a recipe.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "outcome", rename_all = "kebab-case")]
+pub enum SaveOutcome {
+    /// The recipe was saved and now has this etag
+    Saved { etag: String },
+    /// Someone else saved the recipe after it was loaded, so nothing was
+    /// written; this is their version
+    Conflict { current: Box<Recipe> },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
+pub struct RecipeImage {
+    pub id: String,
+    pub is_hero: bool,
+
+    /// Where crops are centred, as fractions of the width and height
+    pub focal_x: f64,
+    pub focal_y: f64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+}
+
 pub mod span_field_wise {
     use jiff::{SignedDuration, Span, SpanRelativeTo};
     use serde::{self, Deserialize, Deserializer, Serialize, Serializer};
@@ -351,9 +543,10 @@ pub mod traits {
     pub use super::{
         AuthorStoreExt, CapabilityPhaseStoreExt, CapabilitySettingStoreExt,
         ForkedIntoOtherLocaleStoreExt, IngredientAllowedUnitStoreExt, IngredientStoreExt,
-        QuantityStoreExt, RecipeIngredientStoreExt, RecipeStepStoreExt, RecipeStoreExt,
-        ReferenceCapabilityStoreExt, ReferencePreparationStoreExt, ReferenceSettingStoreExt,
-        ReferenceTagStoreExt, ReferenceUnitStoreExt, ReferenceValueStoreExt, SettingValueStoreExt,
-        StepCapabilityStoreExt, StepIngredientStoreExt,
+        MediaStoreExt, QuantityStoreExt, RecipeImageStoreExt, RecipeIngredientStoreExt,
+        RecipeRevisionStoreExt, RecipeStepStoreExt, RecipeStoreExt, ReferenceCapabilityStoreExt,
+        ReferencePreparationStoreExt, ReferenceSettingStoreExt, ReferenceTagStoreExt,
+        ReferenceUnitStoreExt, ReferenceValueStoreExt, SettingValueStoreExt,
+        StepCapabilityStoreExt, StepIngredientStoreExt, TrashedRecipeStoreExt,
     };
 }
@@ -0,0 +1,364 @@
+//! Writes a quantity's text from its amount and unit, and reads typed text
+//! back into them, so the two can't drift apart.
+
+use crate::{Quantity, ReferenceUnit};
+
+/// Fractions shown for amounts of cups, spoons and the like.
+const FRACTIONS: [(f64, &str); 6] = [
+    (1.0 / 8.0, "⅛"),
+    (1.0 / 4.0, "¼"),
+    (1.0 / 3.0, "⅓"),
+    (1.0 / 2.0, "½"),
+    (2.0 / 3.0, "⅔"),
+    (3.0 / 4.0, "¾"),
+];
+
+/// Fractions within this of a nice one are shown as it.
+const FRACTION_TOLERANCE: f64 = 0.02;
+
+/// Languages that write `1,5` rather than `1.5`.
+const DECIMAL_COMMA_LANGUAGES: [&str; 20] = [
+    "cs", "da", "de", "el", "es", "fi", "fr", "hu", "id", "it", "nb", "nl", "no", "pl", "pt", "ro",
+    "ru", "sk", "sv", "tr",
+];
+
+/// Abbreviations of units that are weighed or measured out exactly, so are
+/// written as decimals rather than fractions.
+const METRIC_ABBREVIATIONS: [&str; 7] = ["g", "kg", "mg", "ml", "cl", "dl", "l"];
+
+fn decimal_separator(locale: &str) -> char {
+    let language = locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or(locale)
+        .to_lowercase();
+
+    if DECIMAL_COMMA_LANGUAGES.contains(&language.as_str()) {
+        ','
+    } else {
+        '.'
+    }
+}
+
+fn is_metric(unit: &ReferenceUnit) -> bool {
+    METRIC_ABBREVIATIONS.contains(&unit.abbreviation.trim().to_lowercase().as_str())
+}
+
+/// An amount rounded to what can be measured out with its unit: the nearest
+/// 5 g for big amounts of grams, an eighth of a cup, half an egg.
+pub fn round(amount: f64, unit: &ReferenceUnit) -> f64 {
+    let abbreviation = unit.abbreviation.trim().to_lowercase();
+
+    let step = if is_metric(unit) {
+        match (abbreviation.as_str(), amount) {
+            ("kg" | "l", a) if a < 1.0 => 0.05,
+            ("kg" | "l", _) => 0.1,
+            (_, a) if a >= 100.0 => 5.0,
+            (_, a) if a >= 10.0 => 1.0,
+            (_, a) if a >= 1.0 => 0.5,
+            _ => 0.1,
+        }
+    } else if abbreviation.is_empty() {
+        // counted things, like eggs
+        0.5
+    } else if amount < 1.0 {
+        1.0 / 8.0
+    } else if amount < 10.0 {
+        0.25
+    } else {
+        1.0
+    };
+
+    // never down to nothing
+    ((amount / step).round() * step).max(step)
+}
+
+/// An amount as a whole number and a fraction, like `1½`, if it's close
+/// enough to one.
+fn as_fraction(amount: f64) -> Option<String> {
+    let whole = amount.floor();
+    let part = amount - whole;
+
+    let (whole, glyph) = if part < FRACTION_TOLERANCE {
+        (whole, "")
+    } else if part > 1.0 - FRACTION_TOLERANCE {
+        (whole + 1.0, "")
+    } else {
+        let (_, glyph) = FRACTIONS
+            .iter()
+            .find(|(f, _)| (part - f).abs() < FRACTION_TOLERANCE)?;
+        (whole, *glyph)
+    };
+
+    Some(match (whole as u64, glyph) {
+        (whole, "") => whole.to_string(),
+        (0, glyph) => glyph.to_owned(),
+        (whole, glyph) => format!("{whole}{glyph}"),
+    })
+}
+
+/// An amount as a decimal, to as many places as are worth showing.
+fn as_decimal(amount: f64, locale: &str) -> String {
+    let places = match amount.abs() {
+        a if a >= 100.0 => 0,
+        a if a >= 10.0 => 1,
+        _ => 2,
+    };
+    let text = format!("{amount:.places$}");
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+
+    text.replace('.', &decimal_separator(locale).to_string())
+}
+
+/// An amount the way it reads best with its unit: fractions for cups and
+/// spoons, decimals for grams and millilitres.
+pub fn format_amount(amount: f64, unit: &ReferenceUnit, locale: &str) -> String {
+    if !is_metric(unit)
+        && amount < 10.0
+        && let Some(fraction) = as_fraction(amount)
+    {
+        return fraction;
+    }
+
+    as_decimal(amount, locale)
+}
+
+/// The text of a quantity, like `200 g`, `1½ cups` or `2 eggs`.
+pub fn format(amount: Option<f64>, unit: &ReferenceUnit, locale: &str) -> String {
+    let Some(amount) = amount else {
+        return unit.abbreviation.clone();
+    };
+
+    // a half or less of something is still read as one
+    let count = if amount <= 1.0 { 1 } else { 2 };
+    let unit_text = if unit.abbreviation.trim().is_empty() {
+        english::Noun::count(unit.name.clone(), count)
+    } else if unit.abbreviation.eq_ignore_ascii_case(&unit.name) {
+        // abbreviated to the whole word, like `cup`, rather than a symbol
+        english::Noun::count(unit.abbreviation.clone(), count)
+    } else {
+        unit.abbreviation.clone()
+    };
+
+    format!("{} {unit_text}", format_amount(amount, unit, locale))
+        .trim()
+        .to_owned()
+}
+
+/// What could be made out of typed quantity text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedQuantity {
+    pub amount: Option<f64>,
+    /// The unit the text names, if it's one of those offered
+    pub unit: Option<ReferenceUnit>,
+}
+
+fn fraction_value(glyph: char) -> Option<f64> {
+    FRACTIONS
+        .iter()
+        .find_map(|(value, g)| g.starts_with(glyph).then_some(*value))
+}
+
+/// A decimal written with `separator`, and digits grouped in threes by the
+/// other of `.` and `,`, like `1,500.5` or `1.500,5`. Groups that aren't
+/// threes don't read, rather than being read as the wrong number.
+fn parse_decimal(token: &str, separator: char) -> Option<f64> {
+    let grouping = if separator == ',' { '.' } else { ',' };
+    let (whole, part) = match token.split_once(separator) {
+        Some((whole, part)) => (whole, Some(part)),
+        None => (token, None),
+    };
+
+    let mut groups = whole.split(grouping);
+    let first = groups.next()?;
+    let mut digits = first.to_owned();
+    for group in groups {
+        if first.is_empty() || first.len() > 3 || group.len() != 3 {
+            return None;
+        }
+        digits.push_str(group);
+    }
+    if let Some(part) = part {
+        digits.push('.');
+        digits.push_str(part);
+    }
+
+    digits.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// A single number, like `2`, `1.5`, `3/4`, `½` or `1½`, with decimals
+/// written with `separator`.
+fn parse_number(token: &str, separator: char) -> Option<f64> {
+    if let Some(idx) = token.find(|c| fraction_value(c).is_some()) {
+        let (whole, glyph) = token.split_at(idx);
+        let mut glyphs = glyph.chars();
+        let part = fraction_value(glyphs.next()?)?;
+        if glyphs.next().is_some() {
+            return None;
+        }
+        let whole = if whole.is_empty() {
+            0.0
+        } else {
+            parse_number(whole, separator)?
+        };
+        return Some(whole + part);
+    }
+
+    if let Some((numerator, denominator)) = token.split_once('/') {
+        let denominator = parse_number(denominator, separator).filter(|d| *d != 0.0)?;
+        return Some(parse_number(numerator, separator)? / denominator);
+    }
+
+    parse_decimal(token, separator)
+}
+
+/// Finds which of `units` some text names, by abbreviation or by name.
+fn find_unit(text: &str, units: &[ReferenceUnit]) -> Option<ReferenceUnit> {
+    let text = text.trim().trim_end_matches('.');
+    if text.is_empty() {
+        return None;
+    }
+
+    units
+        .iter()
+        .find(|u| !u.abbreviation.is_empty() && u.abbreviation == text)
+        .or_else(|| {
+            units.iter().find(|u| {
+                (!u.abbreviation.is_empty() && u.abbreviation.eq_ignore_ascii_case(text))
+                    || u.name.eq_ignore_ascii_case(text)
+                    || english::Noun::count(u.name.clone(), 2).eq_ignore_ascii_case(text)
+            })
+        })
+        .cloned()
+}
+
+/// Reads typed text like `1 1/2 cups`, `200g` or `½ tsp` into an amount and
+/// one of `units`, with numbers written the way `locale` writes them.
+pub fn parse(text: &str, units: &[ReferenceUnit], locale: &str) -> ParsedQuantity {
+    let text = text.trim();
+    let separator = decimal_separator(locale);
+
+    // the number runs up to the first letter, so `200g` splits too
+    let split = text
+        .find(|c: char| c.is_alphabetic() && fraction_value(c).is_none())
+        .unwrap_or(text.len());
+    let (number, rest) = text.split_at(split);
+
+    let mut tokens = number
+        .split_whitespace()
+        .map(|token| parse_number(token, separator));
+    let amount = match (tokens.next(), tokens.next(), tokens.next()) {
+        (Some(Some(a)), None, _) => Some(a),
+        // a whole number then a fraction, like `1 1/2`
+        (Some(Some(a)), Some(Some(b)), None) if b < 1.0 => Some(a + b),
+        _ => None,
+    };
+
+    ParsedQuantity {
+        amount,
+        unit: find_unit(rest, units),
+    }
+}
+
+impl Quantity {
+    /// Writes `text` afresh from the amount and unit.
+    pub fn sync_text(&mut self, locale: &str) {
+        self.text = format(self.amount, &self.reference_unit, locale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{allowed, cups, eggs, grams, unit};
+
+    fn units() -> Vec<ReferenceUnit> {
+        [grams(), cups(), eggs()].iter().map(unit).collect()
+    }
+
+    #[test]
+    fn formats_with_the_locale_and_unit() {
+        assert_eq!(format(Some(1.5), &unit(&cups()), "en"), "1½ cups");
+        assert_eq!(format(Some(0.75), &unit(&cups()), "en"), "¾ cup");
+        assert_eq!(format(Some(1.5), &unit(&grams()), "en"), "1.5 g");
+        assert_eq!(format(Some(1.5), &unit(&grams()), "de-DE"), "1,5 g");
+        assert_eq!(format(Some(250.0), &unit(&grams()), "en"), "250 g");
+        assert_eq!(format(Some(2.0), &unit(&eggs()), "en"), "2 eggs");
+        assert_eq!(format(Some(0.5), &unit(&eggs()), "en"), "½ egg");
+        assert_eq!(format(None, &unit(&grams()), "en"), "g");
+    }
+
+    #[test]
+    fn pluralises_units_abbreviated_to_a_word() {
+        assert_eq!(format(Some(2.0), &unit(&cups()), "en"), "2 cups");
+        assert_eq!(format(Some(1.0), &unit(&cups()), "en"), "1 cup");
+        // symbols stay as they are
+        assert_eq!(format(Some(2.0), &unit(&grams()), "en"), "2 g");
+        let tbsp = allowed("tbsp", "tablespoon", "tbsp", Some("volume"), None);
+        assert_eq!(format(Some(2.0), &unit(&tbsp), "en"), "2 tbsp");
+    }
+
+    #[test]
+    fn rounds_to_what_the_unit_measures() {
+        assert_eq!(round(123.0, &unit(&grams())), 125.0);
+        assert_eq!(round(12.3, &unit(&grams())), 12.0);
+        assert_eq!(round(0.3, &unit(&cups())), 0.25);
+        assert_eq!(round(1.3, &unit(&eggs())), 1.5);
+        // never down to nothing
+        assert_eq!(round(0.01, &unit(&grams())), 0.1);
+    }
+
+    #[test]
+    fn parses_amounts_and_units() {
+        let parsed = parse("1 1/2 cups", &units(), "en");
+        assert_eq!(parsed.amount, Some(1.5));
+        assert_eq!(parsed.unit, Some(unit(&cups())));
+
+        let parsed = parse("200g", &units(), "en");
+        assert_eq!(parsed.amount, Some(200.0));
+        assert_eq!(parsed.unit, Some(unit(&grams())));
+
+        assert_eq!(parse("1½ cup", &units(), "en").amount, Some(1.5));
+        assert_eq!(parse("3/4 cup", &units(), "en").amount, Some(0.75));
+        assert_eq!(parse("2 eggs", &units(), "en").unit, Some(unit(&eggs())));
+    }
+
+    #[test]
+    fn parses_numbers_the_way_the_locale_writes_them() {
+        assert_eq!(parse("1,500 g", &units(), "en").amount, Some(1500.0));
+        assert_eq!(parse("1.5 g", &units(), "en").amount, Some(1.5));
+        assert_eq!(parse("1,5 g", &units(), "de").amount, Some(1.5));
+        assert_eq!(parse("1.500 g", &units(), "de").amount, Some(1500.0));
+        assert_eq!(parse("1.500,5 g", &units(), "de").amount, Some(1500.5));
+        // not a grouping of thousands, so not read as one
+        assert_eq!(parse("1,5 g", &units(), "en").amount, None);
+    }
+
+    #[test]
+    fn leaves_what_it_cant_read() {
+        let parsed = parse("a pinch", &units(), "en");
+        assert_eq!(parsed.amount, None);
+        assert_eq!(parsed.unit, None);
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        for (amount, unit, locale) in [
+            (1.5, unit(&cups()), "en"),
+            (0.75, unit(&cups()), "en"),
+            (1500.0, unit(&grams()), "en"),
+            (2.25, unit(&grams()), "fr"),
+        ] {
+            let text = format(Some(amount), &unit, locale);
+            assert_eq!(
+                parse(&text, &units(), locale).amount,
+                Some(amount),
+                "{text}"
+            );
+        }
+    }
+}
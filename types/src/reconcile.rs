@@ -0,0 +1,239 @@
+//! Checks the steps use up what the recipe lists, no more and no less.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Recipe, convert::Conversions};
+
+/// How far what the steps use can be off what's listed, as a fraction of it,
+/// before it's reported. Leaves room for rounding.
+const TOLERANCE: f64 = 0.02;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum Balance {
+    /// The steps use what's listed
+    Matches,
+    /// The steps use less than what's listed, by this much
+    Short(f64),
+    /// The steps use more than what's listed, by this much
+    Over(f64),
+    /// No step uses it
+    Unused,
+    /// The steps' quantities couldn't be added up in the listed unit
+    Unknown,
+}
+
+/// An ingredient's listed quantity against what the steps use of it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IngredientBalance {
+    pub ingredient_idx: usize,
+    pub name: String,
+    /// What the steps use between them, in the listed unit
+    pub used: Option<f64>,
+    /// The steps it's used in
+    pub steps: Vec<usize>,
+    pub balance: Balance,
+}
+
+/// A step using an ingredient that isn't listed.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UnlistedUse {
+    pub step_idx: usize,
+    pub ingredient_idx: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Reconciliation {
+    pub ingredients: Vec<IngredientBalance>,
+    pub unlisted: Vec<UnlistedUse>,
+}
+
+impl Reconciliation {
+    /// Whether there's anything to look at.
+    pub fn is_balanced(&self) -> bool {
+        self.unlisted.is_empty()
+            && self
+                .ingredients
+                .iter()
+                .all(|i| i.balance == Balance::Matches)
+    }
+}
+
+/// Adds up what each step uses of each ingredient, converting to the listed
+/// unit where the steps use another.
+pub fn reconcile(recipe: &Recipe, conversions: &Conversions) -> Reconciliation {
+    let unlisted = recipe
+        .steps
+        .iter()
+        .enumerate()
+        .flat_map(|(step_idx, step)| {
+            step.ingredients
+                .iter()
+                .map(|i| i.ingredient_idx as usize)
+                .filter(|idx| *idx >= recipe.ingredients.len())
+                .map(move |ingredient_idx| UnlistedUse {
+                    step_idx,
+                    ingredient_idx,
+                })
+        })
+        .collect();
+
+    let ingredients = recipe
+        .ingredients
+        .iter()
+        .enumerate()
+        .map(|(ingredient_idx, ingredient)| {
+            let listed = &ingredient.quantity;
+            let uses = recipe
+                .steps
+                .iter()
+                .enumerate()
+                .flat_map(|(step_idx, step)| {
+                    step.ingredients
+                        .iter()
+                        .filter(|i| i.ingredient_idx as usize == ingredient_idx)
+                        .map(move |i| (step_idx, &i.quantity))
+                })
+                .collect::<Vec<_>>();
+
+            let mut steps = uses
+                .iter()
+                .map(|(step_idx, _)| *step_idx)
+                .collect::<Vec<_>>();
+            steps.dedup();
+
+            let used = uses
+                .iter()
+                .map(|(_, quantity)| {
+                    if quantity.reference_unit.id == listed.reference_unit.id {
+                        quantity.amount
+                    } else {
+                        conversions.convert(
+                            &ingredient.reference_ingredient.id,
+                            quantity,
+                            &listed.reference_unit.id,
+                        )
+                    }
+                })
+                .sum::<Option<f64>>();
+
+            let balance = match (used, listed.amount) {
+                _ if uses.is_empty() => Balance::Unused,
+                (Some(used), Some(listed)) if (used - listed).abs() <= listed * TOLERANCE => {
+                    Balance::Matches
+                }
+                (Some(used), Some(listed)) if used < listed => Balance::Short(listed - used),
+                (Some(used), Some(listed)) => Balance::Over(used - listed),
+                _ => Balance::Unknown,
+            };
+
+            IngredientBalance {
+                ingredient_idx,
+                name: ingredient.reference_ingredient.name.clone(),
+                used,
+                steps,
+                balance,
+            }
+        })
+        .collect();
+
+    Reconciliation {
+        ingredients,
+        unlisted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::fixtures::{cups, grams, millilitres, quantity, recipe, step};
+
+    #[test]
+    fn matches_what_the_steps_use_up() {
+        let recipe = recipe(
+            2,
+            vec![("flour", quantity(250.0, &grams()))],
+            vec![
+                step(vec![(0, quantity(150.0, &grams()))]),
+                step(vec![(0, quantity(102.0, &grams()))]),
+            ],
+        );
+
+        let reconciliation = reconcile(&recipe, &Conversions::default());
+
+        assert!(reconciliation.is_balanced());
+        assert_eq!(reconciliation.ingredients[0].used, Some(252.0));
+        assert_eq!(reconciliation.ingredients[0].steps, vec![0, 1]);
+    }
+
+    #[test]
+    fn reports_short_over_and_unused() {
+        let recipe = recipe(
+            2,
+            vec![
+                ("flour", quantity(250.0, &grams())),
+                ("sugar", quantity(100.0, &grams())),
+                ("salt", quantity(5.0, &grams())),
+            ],
+            vec![step(vec![
+                (0, quantity(200.0, &grams())),
+                (1, quantity(150.0, &grams())),
+            ])],
+        );
+
+        let balances = reconcile(&recipe, &Conversions::default())
+            .ingredients
+            .into_iter()
+            .map(|i| i.balance)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            balances,
+            vec![Balance::Short(50.0), Balance::Over(50.0), Balance::Unused]
+        );
+    }
+
+    #[test]
+    fn converts_steps_to_the_listed_unit() {
+        let recipe = recipe(
+            2,
+            vec![("milk", quantity(500.0, &millilitres()))],
+            vec![step(vec![(0, quantity(1.0, &cups()))])],
+        );
+        let conversions = Conversions {
+            allowed_units: HashMap::from([("milk".to_owned(), vec![millilitres(), cups()])]),
+            densities: HashMap::new(),
+        };
+
+        let balance = &reconcile(&recipe, &conversions).ingredients[0];
+        assert!(matches!(balance.balance, Balance::Short(s) if (s - 263.41).abs() < 0.01));
+
+        // nothing to convert with
+        let balance = &reconcile(&recipe, &Conversions::default()).ingredients[0];
+        assert_eq!(balance.balance, Balance::Unknown);
+    }
+
+    #[test]
+    fn reports_steps_using_unlisted_ingredients() {
+        let recipe = recipe(
+            2,
+            vec![("flour", quantity(250.0, &grams()))],
+            vec![step(vec![
+                (0, quantity(250.0, &grams())),
+                (3, quantity(1.0, &grams())),
+            ])],
+        );
+
+        let reconciliation = reconcile(&recipe, &Conversions::default());
+
+        assert!(!reconciliation.is_balanced());
+        assert_eq!(
+            reconciliation.unlisted,
+            vec![UnlistedUse {
+                step_idx: 0,
+                ingredient_idx: 3
+            }]
+        );
+    }
+}
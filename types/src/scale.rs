@@ -0,0 +1,207 @@
+//! Scales a recipe to serve more or fewer people.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Quantity, Recipe, ReferenceSettingId,
+    timing::{heats, setting},
+};
+
+/// Why a step might need a look after scaling.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleConcern {
+    /// It runs for a set time on what's now more or less in the bowl, which
+    /// rarely takes proportionally longer or shorter
+    Time,
+    /// It heats what's now more or less in the bowl, which has a practical
+    /// minimum and maximum that don't scale
+    Load,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScaleWarning {
+    pub step_idx: usize,
+    pub concern: ScaleConcern,
+}
+
+impl fmt::Display for ScaleWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let step = self.step_idx + 1;
+        match self.concern {
+            ScaleConcern::Time => write!(
+                f,
+                "Step {step} runs for a set time, which may need changing for the new amounts"
+            ),
+            ScaleConcern::Load => write!(
+                f,
+                "Step {step} heats what's in the bowl, which may now be too much or too little"
+            ),
+        }
+    }
+}
+
+fn scale_quantity(quantity: &mut Quantity, factor: f64, locale: &str) {
+    if let Some(amount) = quantity.amount {
+        quantity.amount = Some(crate::quantity::round(
+            amount * factor,
+            &quantity.reference_unit,
+        ));
+        quantity.sync_text(locale);
+    }
+}
+
+impl Recipe {
+    /// Scales every amount to serve `serves` people, rounding each to what
+    /// its unit can measure out. Gives back the timed and heating steps whose
+    /// bowl now holds a different amount, as their times and loads may not
+    /// scale along with it.
+    pub fn scale(&mut self, serves: u8) -> Vec<ScaleWarning> {
+        if serves == 0 || serves == self.serves {
+            return vec![];
+        }
+
+        let factor = f64::from(serves) / f64::from(self.serves.max(1));
+        let locale = self.locale.clone();
+        let original = self.ingredients.clone();
+
+        for ingredient in &mut self.ingredients {
+            scale_quantity(&mut ingredient.quantity, factor, &locale);
+        }
+
+        let original_steps = self.steps.clone();
+        self.remap_step_quantities(&original, |_, quantity| {
+            scale_quantity(quantity, factor, &locale);
+        });
+
+        self.serves = serves;
+
+        // the bowl holds something different from the first step whose
+        // amounts changed on
+        let mut load_changed = false;
+        self.steps
+            .iter()
+            .zip(&original_steps)
+            .enumerate()
+            .flat_map(|(step_idx, (step, before))| {
+                load_changed |= step
+                    .ingredients
+                    .iter()
+                    .zip(&before.ingredients)
+                    .any(|(a, b)| a.quantity.amount != b.quantity.amount);
+                let timed = setting(step, ReferenceSettingId::Time).is_some();
+
+                [
+                    (load_changed && timed).then_some(ScaleConcern::Time),
+                    (load_changed && heats(step)).then_some(ScaleConcern::Load),
+                ]
+                .into_iter()
+                .flatten()
+                .map(move |concern| ScaleWarning { step_idx, concern })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{capability_step, cups, grams, quantity, recipe, step};
+
+    #[test]
+    fn scales_amounts_and_text() {
+        let mut recipe = recipe(
+            2,
+            vec![
+                ("flour", quantity(250.0, &grams())),
+                ("milk", quantity(1.0, &cups())),
+            ],
+            vec![],
+        );
+
+        recipe.scale(4);
+
+        assert_eq!(recipe.serves, 4);
+        assert_eq!(recipe.ingredients[0].quantity.amount, Some(500.0));
+        assert_eq!(recipe.ingredients[0].quantity.text, "500 g");
+        assert_eq!(recipe.ingredients[1].quantity.amount, Some(2.0));
+        assert_eq!(recipe.ingredients[1].quantity.text, "2 cups");
+    }
+
+    #[test]
+    fn keeps_steps_using_the_whole_of_an_ingredient_matched() {
+        let mut recipe = recipe(
+            3,
+            vec![("flour", quantity(250.0, &grams()))],
+            vec![
+                step(vec![(0, quantity(250.0, &grams()))]),
+                step(vec![(0, quantity(100.0, &grams()))]),
+            ],
+        );
+
+        recipe.scale(1);
+
+        // 250 / 3 rounds to 83, and 100 / 3 to 33
+        assert_eq!(recipe.ingredients[0].quantity.amount, Some(83.0));
+        assert_eq!(
+            recipe.steps[0].ingredients[0].quantity,
+            recipe.ingredients[0].quantity
+        );
+        assert_eq!(recipe.steps[1].ingredients[0].quantity.amount, Some(33.0));
+    }
+
+    #[test]
+    fn warns_of_timed_and_heating_steps_whose_load_changes() {
+        let mut recipe = recipe(
+            2,
+            vec![("flour", quantity(250.0, &grams()))],
+            vec![
+                // nothing in the bowl yet
+                capability_step(vec![], vec![(ReferenceSettingId::Time, 60.0)]),
+                step(vec![(0, quantity(250.0, &grams()))]),
+                capability_step(vec![], vec![(ReferenceSettingId::Time, 60.0)]),
+                capability_step(
+                    vec![],
+                    vec![
+                        (ReferenceSettingId::Temperature, 100.0),
+                        (ReferenceSettingId::Time, 300.0),
+                    ],
+                ),
+                // neither timed nor heating
+                capability_step(vec![], vec![]),
+            ],
+        );
+
+        let warnings = recipe.scale(4);
+
+        assert_eq!(
+            warnings,
+            vec![
+                ScaleWarning {
+                    step_idx: 2,
+                    concern: ScaleConcern::Time
+                },
+                ScaleWarning {
+                    step_idx: 3,
+                    concern: ScaleConcern::Time
+                },
+                ScaleWarning {
+                    step_idx: 3,
+                    concern: ScaleConcern::Load
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_the_recipe_for_the_same_or_no_servings() {
+        let original = recipe(2, vec![("flour", quantity(250.0, &grams()))], vec![]);
+
+        for serves in [0, 2] {
+            let mut recipe = original.clone();
+            assert!(recipe.scale(serves).is_empty());
+            assert_eq!(recipe, original);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! What official recipes actually do with each capability, so the editor can
+//! stick to settings and values the mixer is known to accept.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CapabilitySetting, ReferenceCapability, ReferenceSettingId, ReferenceUnit, ReferenceValue,
+    SettingValue, catalog::Catalog,
+};
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingValueKind {
+    Numeric,
+    Boolean,
+    Nominal,
+}
+
+impl SettingValue {
+    pub fn kind(&self) -> SettingValueKind {
+        match self {
+            SettingValue::Numeric { .. } => SettingValueKind::Numeric,
+            SettingValue::Boolean { .. } => SettingValueKind::Boolean,
+            SettingValue::Nominal { .. } => SettingValueKind::Nominal,
+        }
+    }
+}
+
+/// How official recipes use one setting of a capability.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SettingSchema {
+    pub id: ReferenceSettingId,
+    /// How many steps using the capability had this setting
+    pub occurrences: u64,
+    pub kinds: Vec<SettingValueKind>,
+    pub units: Vec<ReferenceUnit>,
+    /// Ids of the nominal values seen. What they're called, and their order,
+    /// come from the catalog.
+    #[serde(default)]
+    pub nominal_value_ids: Vec<String>,
+    /// Smallest numeric value seen
+    pub min: Option<f64>,
+    /// Largest numeric value seen
+    pub max: Option<f64>,
+    /// The value official recipes use most often
+    pub typical: Option<SettingValue>,
+}
+
+/// What official recipes do with a capability, learned from them when they're
+/// ingested.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CapabilitySchema {
+    pub capability: ReferenceCapability,
+    /// How many steps use the capability
+    pub occurrences: u64,
+    /// Ids of the phases the capability is used in
+    pub phases: Vec<String>,
+    /// Most used first
+    pub settings: Vec<SettingSchema>,
+}
+
+impl SettingSchema {
+    /// The catalog's values for the setting that official recipes use, or all
+    /// of them when none have been seen.
+    pub fn nominal_values(&self, catalog: &Catalog) -> Vec<ReferenceValue> {
+        let values = catalog.values_of(self.id);
+        let seen = values
+            .iter()
+            .filter(|v| self.nominal_value_ids.contains(&v.id))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if seen.is_empty() { values } else { seen }
+    }
+}
+
+impl CapabilitySchema {
+    pub fn setting(&self, id: ReferenceSettingId) -> Option<&SettingSchema> {
+        self.settings.iter().find(|s| s.id == id)
+    }
+
+    /// Settings official recipes give the capability nearly every time it's
+    /// used, so that leaving one out is probably a mistake.
+    pub fn usual_settings(&self) -> impl Iterator<Item = ReferenceSettingId> + '_ {
+        self.settings
+            .iter()
+            .filter(|s| s.occurrences * 10 >= self.occurrences * 9)
+            .map(|s| s.id)
+    }
+}
+
+impl CapabilitySetting {
+    /// A new setting, starting from what official recipes usually use when
+    /// that's known, and otherwise from the first of the catalog's values.
+    pub fn starting_value(
+        id: ReferenceSettingId,
+        schema: Option<&SettingSchema>,
+        catalog: &Catalog,
+    ) -> Self {
+        let nominal = || {
+            catalog
+                .values_of(id)
+                .into_iter()
+                .next()
+                .map(|v| SettingValue::Nominal {
+                    text: v.name.clone(),
+                    reference_value: v,
+                })
+        };
+
+        let value = schema
+            .and_then(|s| s.typical.clone())
+            .unwrap_or_else(|| match id {
+                ReferenceSettingId::KeepWarm => SettingValue::Boolean {
+                    text: "placeholder".to_owned(),
+                    value: false,
+                },
+                ReferenceSettingId::Temperature => {
+                    nominal().unwrap_or_else(|| SettingValue::Numeric {
+                        reference_unit: Some(ReferenceUnit::celcius()),
+                        text: "100".to_owned(),
+                        value: 100.0,
+                    })
+                }
+                // speeds can only be picked from the catalog, so with none
+                // there the editor shows it needs fixing
+                ReferenceSettingId::Speed => nominal().unwrap_or(SettingValue::Numeric {
+                    reference_unit: None,
+                    text: String::new(),
+                    value: 0.0,
+                }),
+                ReferenceSettingId::Time => SettingValue::Numeric {
+                    reference_unit: None,
+                    text: "1m".to_owned(),
+                    value: 60.0,
+                },
+            });
+
+        Self {
+            uid: Default::default(),
+            reference_setting: catalog.setting(id),
+            value,
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Works out how long a recipe takes from its steps: the appliance's own
+//! time settings, plus estimates for what the cook does by hand.
+
+use jiff::SignedDuration;
+use serde::{Deserialize, Serialize};
+
+use crate::{Recipe, RecipeStep, ReferenceSettingId, SettingValue};
+
+/// How long steps without a time setting are reckoned to take.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct TimingEstimates {
+    /// Any step done by hand
+    pub manual_step_secs: f64,
+    /// On top of that, for each ingredient the step adds
+    pub per_ingredient_secs: f64,
+}
+
+impl Default for TimingEstimates {
+    fn default() -> Self {
+        Self {
+            manual_step_secs: 60.0,
+            per_ingredient_secs: 30.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum StepTimeKind {
+    /// Done by hand, so the time is an estimate
+    Manual,
+    /// The appliance runs without heat, like kneading
+    Machine,
+    /// The appliance heats or keeps warm
+    Cooking,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StepTiming {
+    pub step_idx: usize,
+    pub kind: StepTimeKind,
+    /// How far into the recipe the step starts
+    pub start: SignedDuration,
+    pub duration: SignedDuration,
+}
+
+impl StepTiming {
+    pub fn end(&self) -> SignedDuration {
+        self.start + self.duration
+    }
+
+    /// Whether the cook can walk away while it runs.
+    pub fn hands_off(&self) -> bool {
+        self.kind != StepTimeKind::Manual
+    }
+}
+
+/// How long a recipe takes, step by step and in total.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Timing {
+    pub steps: Vec<StepTiming>,
+    /// Manual steps and the appliance running without heat
+    pub prep: SignedDuration,
+    /// The appliance heating or keeping warm
+    pub cook: SignedDuration,
+    /// The part of prep and cook the appliance gets on with by itself
+    pub hands_off: SignedDuration,
+}
+
+impl Timing {
+    pub fn total(&self) -> SignedDuration {
+        self.prep + self.cook
+    }
+
+    /// Prep and cook time rounded up to whole minutes, which is what the
+    /// device shows.
+    pub fn minutes(&self) -> (SignedDuration, SignedDuration) {
+        (whole_minutes(self.prep), whole_minutes(self.cook))
+    }
+}
+
+/// `m:ss`, or `h:mm:ss` for an hour or more.
+pub fn clock(d: SignedDuration) -> String {
+    let secs = d.as_secs();
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+/// The value a step's capability gives a setting, if it has it.
+pub fn setting(step: &RecipeStep, id: ReferenceSettingId) -> Option<&SettingValue> {
+    step.capability
+        .iter()
+        .flat_map(|c| &c.settings)
+        .find(|s| s.reference_setting.id == id)
+        .map(|s| &s.value)
+}
+
+/// Whether the appliance heats or keeps warm during the step.
+pub(crate) fn heats(step: &RecipeStep) -> bool {
+    let temperature = matches!(
+        setting(step, ReferenceSettingId::Temperature),
+        Some(SettingValue::Numeric { value, .. }) if *value > 0.0
+    );
+    let keep_warm = matches!(
+        setting(step, ReferenceSettingId::KeepWarm),
+        Some(SettingValue::Boolean { value: true, .. })
+    );
+
+    temperature || keep_warm
+}
+
+fn step_timing(step: &RecipeStep, estimates: TimingEstimates) -> (StepTimeKind, f64) {
+    match setting(step, ReferenceSettingId::Time) {
+        Some(SettingValue::Numeric { value, .. }) if *value > 0.0 => {
+            let kind = if heats(step) {
+                StepTimeKind::Cooking
+            } else {
+                StepTimeKind::Machine
+            };
+            (kind, *value)
+        }
+        _ => (
+            StepTimeKind::Manual,
+            estimates.manual_step_secs
+                + estimates.per_ingredient_secs * step.ingredients.len() as f64,
+        ),
+    }
+}
+
+/// Times each step in turn, one after the other.
+pub fn timing(steps: &[RecipeStep], estimates: TimingEstimates) -> Timing {
+    let mut start = SignedDuration::ZERO;
+    let mut prep = SignedDuration::ZERO;
+    let mut cook = SignedDuration::ZERO;
+    let mut hands_off = SignedDuration::ZERO;
+
+    let steps = steps
+        .iter()
+        .enumerate()
+        .map(|(step_idx, step)| {
+            let (kind, secs) = step_timing(step, estimates);
+            let duration =
+                SignedDuration::try_from_secs_f64(secs.max(0.0)).unwrap_or(SignedDuration::ZERO);
+
+            match kind {
+                StepTimeKind::Cooking => cook += duration,
+                StepTimeKind::Manual | StepTimeKind::Machine => prep += duration,
+            }
+
+            let timing = StepTiming {
+                step_idx,
+                kind,
+                start,
+                duration,
+            };
+            if timing.hands_off() {
+                hands_off += duration;
+            }
+            start += duration;
+
+            timing
+        })
+        .collect();
+
+    Timing {
+        steps,
+        prep,
+        cook,
+        hands_off,
+    }
+}
+
+fn whole_minutes(d: SignedDuration) -> SignedDuration {
+    SignedDuration::from_mins((d.as_secs() + 59).div_euclid(60))
+}
+
+impl Recipe {
+    /// Fills in the prep, cook and total times from the steps, unless they're
+    /// set by hand.
+    pub fn sync_times(&mut self) {
+        let Some(estimates) = self.timing else {
+            return;
+        };
+
+        let (prep, cook) = timing(&self.steps, estimates).minutes();
+
+        self.prep_time = Some(prep);
+        self.cook_time = Some(cook);
+        self.total_time = prep + cook;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{capability_step, eggs, quantity, recipe, step};
+
+    fn steps() -> Vec<RecipeStep> {
+        vec![
+            // cracking a couple of eggs in by hand
+            step(vec![(0, quantity(2.0, &eggs()))]),
+            capability_step(vec![], vec![(ReferenceSettingId::Time, 120.0)]),
+            capability_step(
+                vec![],
+                vec![
+                    (ReferenceSettingId::Temperature, 100.0),
+                    (ReferenceSettingId::Time, 600.0),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn times_steps_by_their_settings_or_estimates() {
+        let timing = timing(&steps(), TimingEstimates::default());
+
+        assert_eq!(
+            timing
+                .steps
+                .iter()
+                .map(|s| (s.kind, s.start.as_secs(), s.duration.as_secs()))
+                .collect::<Vec<_>>(),
+            [
+                (StepTimeKind::Manual, 0, 90),
+                (StepTimeKind::Machine, 90, 120),
+                (StepTimeKind::Cooking, 210, 600),
+            ]
+        );
+        assert_eq!(timing.prep, SignedDuration::from_secs(210));
+        assert_eq!(timing.cook, SignedDuration::from_mins(10));
+        assert_eq!(timing.hands_off, SignedDuration::from_secs(720));
+        assert_eq!(
+            timing.minutes(),
+            (SignedDuration::from_mins(4), SignedDuration::from_mins(10))
+        );
+    }
+
+    #[test]
+    fn fills_in_times_unless_set_by_hand() {
+        let mut synced = recipe(2, vec![], steps());
+        synced.timing = Some(TimingEstimates::default());
+        synced.sync_times();
+        assert_eq!(synced.prep_time, Some(SignedDuration::from_mins(4)));
+        assert_eq!(synced.cook_time, Some(SignedDuration::from_mins(10)));
+        assert_eq!(synced.total_time, SignedDuration::from_mins(14));
+
+        let mut manual = recipe(2, vec![], steps());
+        manual.prep_time = Some(SignedDuration::from_mins(20));
+        manual.total_time = SignedDuration::from_mins(20);
+        manual.sync_times();
+        assert_eq!(manual.prep_time, Some(SignedDuration::from_mins(20)));
+        assert_eq!(manual.cook_time, None);
+        assert_eq!(manual.total_time, SignedDuration::from_mins(20));
+    }
+
+    #[test]
+    fn writes_durations_as_a_clock() {
+        assert_eq!(clock(SignedDuration::from_secs(65)), "1:05");
+        assert_eq!(clock(SignedDuration::from_secs(3725)), "1:02:05");
+    }
+}
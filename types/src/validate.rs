@@ -0,0 +1,658 @@
+//! Checks a recipe against what its appliance accepts, so problems show up in
+//! the editor instead of halfway through cooking.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CapabilitySetting, Recipe, RecipeStep, ReferenceSettingId, ReferenceUnit, SettingValue,
+    appliance::ApplianceProfile, catalog::Catalog, convert::Conversions, reconcile::Balance,
+    schema::CapabilitySchema,
+};
+
+/// What a recipe is checked against.
+#[derive(Clone, Copy)]
+pub struct Rules<'a> {
+    /// Nominal values are expected to be in here
+    pub catalog: &'a Catalog,
+    /// What official recipes do with each capability, to tell which settings
+    /// they're usually given
+    pub schemas: &'a [CapabilitySchema],
+    /// The appliance the recipe is written for
+    pub appliance: &'a ApplianceProfile,
+    /// To work out how much the ingredients take up, and add up quantities
+    /// in different units. Without any, the bowl isn't checked
+    pub conversions: &'a Conversions,
+}
+
+const EXECUTION_PHASE: &str = "cckg:ExecutionPhase";
+const TAKE_DOWN_PHASE: &str = "cckg:TakeDownPhase";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Probably a mistake, but the appliance will cope
+    Warning,
+    /// The appliance won't accept this, so it can't be saved
+    Error,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Where in the recipe the problem is, like `Step 2 › Speed`
+    pub path: String,
+    pub message: String,
+}
+
+struct Issues(Vec<Issue>);
+
+impl Issues {
+    fn error(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(Issue {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn warning(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(Issue {
+            severity: Severity::Warning,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+}
+
+/// Whether any of `issues` should stop the recipe being saved.
+pub fn has_errors(issues: &[Issue]) -> bool {
+    issues.iter().any(|i| i.severity == Severity::Error)
+}
+
+/// A numeric setting value as it'd be written, like `180 °C` or `1h 30m`.
+fn describe(id: ReferenceSettingId, value: f64) -> String {
+    match id {
+        ReferenceSettingId::Temperature => format!("{value} °C"),
+        ReferenceSettingId::Time => format!("{:#}", jiff::SignedDuration::from_secs_f64(value)),
+        _ => value.to_string(),
+    }
+}
+
+fn validate_setting(
+    issues: &mut Issues,
+    path: &str,
+    setting: &CapabilitySetting,
+    rules: Rules<'_>,
+) {
+    let path = format!("{path} › {}", setting.reference_setting.name);
+    let id = setting.reference_setting.id;
+
+    let Some(limits) = rules.appliance.setting(id) else {
+        issues.error(
+            &path,
+            format!("The {} doesn't have this setting", rules.appliance.name),
+        );
+        return;
+    };
+
+    if let SettingValue::Numeric { value, .. } = &setting.value {
+        if let Some(min) = limits.min
+            && *value < min
+        {
+            issues.error(
+                &path,
+                format!(
+                    "The {} can't go below {}",
+                    rules.appliance.name,
+                    describe(id, min)
+                ),
+            );
+        }
+        if let Some(max) = limits.max
+            && *value > max
+        {
+            issues.error(
+                &path,
+                format!(
+                    "The {} can't go above {}",
+                    rules.appliance.name,
+                    describe(id, max)
+                ),
+            );
+        }
+    }
+
+    match (setting.reference_setting.id, &setting.value) {
+        (
+            ReferenceSettingId::Temperature,
+            SettingValue::Numeric {
+                value,
+                reference_unit,
+                ..
+            },
+        ) => {
+            if reference_unit
+                .as_ref()
+                .is_some_and(|u| u.id != ReferenceUnit::celcius().id)
+            {
+                issues.error(&path, "Temperatures have to be in °C");
+            }
+            if *value <= 0.0 {
+                issues.error(&path, "Temperature has to be above 0 °C");
+            }
+        }
+        // values taken out of the catalog may still be fine on the appliance
+        (
+            ReferenceSettingId::Temperature,
+            SettingValue::Nominal {
+                reference_value, ..
+            },
+        ) => {
+            if !rules
+                .catalog
+                .has_value(ReferenceSettingId::Temperature, reference_value)
+            {
+                issues.warning(
+                    &path,
+                    format!(
+                        "{} isn't a temperature in the catalog",
+                        reference_value.name
+                    ),
+                );
+            }
+        }
+        (
+            ReferenceSettingId::Speed,
+            SettingValue::Nominal {
+                reference_value, ..
+            },
+        ) => {
+            if !rules
+                .catalog
+                .has_value(ReferenceSettingId::Speed, reference_value)
+            {
+                issues.warning(
+                    &path,
+                    format!("{} isn't a speed in the catalog", reference_value.name),
+                );
+            }
+        }
+        (ReferenceSettingId::Time, SettingValue::Numeric { value, .. }) => {
+            if *value <= 0.0 {
+                issues.error(&path, "Time has to be longer than 0 seconds");
+            }
+        }
+        (ReferenceSettingId::KeepWarm, SettingValue::Boolean { .. }) => {}
+        _ => issues.error(&path, "This kind of value doesn't fit this setting"),
+    }
+}
+
+fn validate_step(
+    issues: &mut Issues,
+    path: &str,
+    step: &RecipeStep,
+    recipe: &Recipe,
+    rules: Rules<'_>,
+) {
+    if step.text.trim().is_empty() {
+        issues.error(format!("{path} › Instructions"), "Steps need instructions");
+    }
+
+    for (idx, ingredient) in step.ingredients.iter().enumerate() {
+        if ingredient.ingredient_idx as usize >= recipe.ingredients.len() {
+            issues.error(
+                format!("{path} › Ingredient {}", idx + 1),
+                "Uses an ingredient that isn't in the recipe",
+            );
+        }
+        if ingredient.quantity.amount.is_some_and(|a| a < 0.0) {
+            issues.error(
+                format!("{path} › Ingredient {}", idx + 1),
+                "Quantities can't be negative",
+            );
+        }
+    }
+
+    let Some(capability) = &step.capability else {
+        return;
+    };
+
+    if !rules
+        .appliance
+        .has_capability(&capability.reference_capability.id)
+    {
+        issues.error(
+            format!("{path} › Capability"),
+            format!(
+                "The {} can't {}",
+                rules.appliance.name, capability.reference_capability.name
+            ),
+        );
+    }
+
+    for setting in &capability.settings {
+        validate_setting(issues, path, setting, rules);
+    }
+
+    let has = |id: ReferenceSettingId| {
+        capability
+            .settings
+            .iter()
+            .filter(|s| s.reference_setting.id == id)
+            .count()
+    };
+
+    for id in [
+        ReferenceSettingId::KeepWarm,
+        ReferenceSettingId::Temperature,
+        ReferenceSettingId::Speed,
+        ReferenceSettingId::Time,
+    ] {
+        if has(id) > 1 {
+            issues.error(
+                format!("{path} › {}", id.reference_setting().name),
+                "Set more than once",
+            );
+        }
+    }
+
+    if capability.phase.id == EXECUTION_PHASE {
+        if has(ReferenceSettingId::Time) == 0 {
+            issues.error(format!("{path} › Time"), "Running steps need a time");
+        }
+
+        // settings official recipes nearly always give, besides the time
+        // every running step needs. Preset programs can fill these in
+        // themselves, so missing ones are only warned about
+        let usual = rules
+            .schemas
+            .iter()
+            .find(|s| s.capability.id == capability.reference_capability.id)
+            .into_iter()
+            .flat_map(|s| s.usual_settings())
+            .filter(|id| *id != ReferenceSettingId::Time);
+
+        for id in usual {
+            if has(id) == 0 {
+                issues.warning(
+                    format!("{path} › {}", id.reference_setting().name),
+                    format!(
+                        "{} usually has a {} setting",
+                        capability.reference_capability.name,
+                        id.reference_setting().name.to_lowercase()
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Checks that each step's phase can follow the one before it. A take down
+/// finishes a program, so whatever comes after starts afresh.
+fn validate_phases(issues: &mut Issues, recipe: &Recipe) {
+    let mut previous: Option<&str> = None;
+
+    for (idx, step) in recipe.steps.iter().enumerate() {
+        let Some(capability) = &step.capability else {
+            continue;
+        };
+        let phase = &capability.phase;
+
+        if let Some(previous) = previous
+            && !phase.can_follow_phases.iter().any(|p| p == previous)
+        {
+            issues.error(
+                format!("Step {} › Phase", idx + 1),
+                format!("A {} phase can't come straight after this one", phase.name),
+            );
+        }
+
+        previous = (phase.id != TAKE_DOWN_PHASE).then_some(phase.id.as_str());
+    }
+}
+
+/// Checks the bowl neither overflows nor has too little in it to heat.
+fn validate_loads(issues: &mut Issues, recipe: &Recipe, rules: Rules<'_>) {
+    if rules.conversions.allowed_units.is_empty() {
+        return;
+    }
+
+    let mut overflowed = false;
+
+    for load in crate::capacity::bowl_loads(recipe, rules.conversions) {
+        let path = format!("Step {} › Bowl", load.step_idx + 1);
+
+        // only the first step to overflow, since the rest follow from it
+        if let Some(capacity) = rules.appliance.bowl_capacity_ml
+            && load.contents_ml > capacity
+            && !overflowed
+        {
+            overflowed = true;
+            issues.warning(
+                path.clone(),
+                format!(
+                    "Holds about {:.0} ml by now, more than the {capacity:.0} ml the bowl takes",
+                    load.contents_ml
+                ),
+            );
+        }
+
+        // with anything unmeasured there may well be enough
+        if let Some(min) = rules.appliance.min_heating_ml
+            && load.unmeasured.is_empty()
+            && load.contents_ml < min
+            && recipe
+                .steps
+                .get(load.step_idx)
+                .is_some_and(crate::timing::heats)
+        {
+            issues.warning(
+                path.clone(),
+                format!(
+                    "Heats only about {:.0} ml, less than the {min:.0} ml the appliance needs",
+                    load.contents_ml
+                ),
+            );
+        }
+
+        let Some(capability) = recipe
+            .steps
+            .get(load.step_idx)
+            .and_then(|s| s.capability.as_ref())
+        else {
+            continue;
+        };
+        let Some(limits) = rules
+            .appliance
+            .capability_load(&capability.reference_capability.id)
+        else {
+            continue;
+        };
+        let name = &capability.reference_capability.name;
+
+        if let Some(min) = limits.min_ml
+            && load.unmeasured.is_empty()
+            && load.contents_ml < min
+        {
+            issues.warning(
+                path.clone(),
+                format!(
+                    "{name} works on only about {:.0} ml, less than the {min:.0} ml it needs",
+                    load.contents_ml
+                ),
+            );
+        }
+        if let Some(max) = limits.max_ml
+            && load.contents_ml > max
+        {
+            issues.warning(
+                path,
+                format!(
+                    "{name} works on about {:.0} ml, more than the {max:.0} ml it takes",
+                    load.contents_ml
+                ),
+            );
+        }
+    }
+}
+
+/// Checks the steps use up what's listed. Steps using unlisted ingredients
+/// are already errors of their own.
+fn validate_usage(issues: &mut Issues, recipe: &Recipe, rules: Rules<'_>) {
+    let reconciliation = crate::reconcile::reconcile(recipe, rules.conversions);
+
+    for ingredient in reconciliation.ingredients {
+        let path = format!("Ingredient {} › Steps", ingredient.ingredient_idx + 1);
+        let Some(listed) = recipe.ingredients.get(ingredient.ingredient_idx) else {
+            continue;
+        };
+        let amount = |a: Option<f64>| {
+            crate::quantity::format(a, &listed.quantity.reference_unit, &recipe.locale)
+        };
+
+        match ingredient.balance {
+            Balance::Matches | Balance::Unknown => {}
+            Balance::Unused => issues.warning(path, "Isn't used in any step"),
+            Balance::Short(missing) => issues.warning(
+                path,
+                format!(
+                    "The steps use {} less than the {} listed",
+                    amount(Some(missing)),
+                    amount(listed.quantity.amount)
+                ),
+            ),
+            Balance::Over(extra) => issues.warning(
+                path,
+                format!(
+                    "The steps use {} more than the {} listed",
+                    amount(Some(extra)),
+                    amount(listed.quantity.amount)
+                ),
+            ),
+        }
+    }
+}
+
+fn validate_times(issues: &mut Issues, recipe: &Recipe) {
+    if recipe.total_time.is_zero() {
+        issues.warning("Total time", "No total time is set");
+    }
+
+    if let (Some(prep), Some(cook)) = (recipe.prep_time, recipe.cook_time)
+        && prep + cook != recipe.total_time
+    {
+        issues.warning("Total time", "Doesn't add up to the prep and cook times");
+    }
+
+    let step_seconds = recipe
+        .steps
+        .iter()
+        .flat_map(|s| &s.capability)
+        .flat_map(|c| &c.settings)
+        .filter_map(|s| match (s.reference_setting.id, &s.value) {
+            (ReferenceSettingId::Time, SettingValue::Numeric { value, .. }) => Some(*value),
+            _ => None,
+        })
+        .sum::<f64>();
+
+    if step_seconds > recipe.total_time.as_secs_f64() {
+        issues.warning(
+            "Total time",
+            format!(
+                "The steps run for {} minutes, longer than the total time",
+                (step_seconds / 60.0).ceil()
+            ),
+        );
+    }
+}
+
+/// Lists everything wrong with a recipe, errors first.
+pub fn validate(recipe: &Recipe, rules: Rules<'_>) -> Vec<Issue> {
+    let mut issues = Issues(Vec::new());
+
+    if recipe.name.trim().is_empty() {
+        issues.error("Name", "Recipes need a name");
+    }
+    if recipe.description.trim().is_empty() {
+        issues.warning("Description", "No description is set");
+    }
+    if recipe.serves == 0 {
+        issues.error("Serves", "Recipes have to serve at least one person");
+    }
+    if recipe.ingredients.is_empty() {
+        issues.warning("Ingredients", "No ingredients are listed");
+    }
+    if recipe.steps.is_empty() {
+        issues.error("Steps", "Recipes need at least one step");
+    }
+
+    for (idx, ingredient) in recipe.ingredients.iter().enumerate() {
+        let path = format!("Ingredient {}", idx + 1);
+
+        if ingredient.quantity.text.trim().is_empty() {
+            issues.warning(format!("{path} › Quantity"), "No quantity is given");
+        }
+        if ingredient.quantity.amount.is_some_and(|a| a < 0.0) {
+            issues.error(format!("{path} › Quantity"), "Quantities can't be negative");
+        }
+    }
+
+    for (idx, step) in recipe.steps.iter().enumerate() {
+        validate_step(
+            &mut issues,
+            &format!("Step {}", idx + 1),
+            step,
+            recipe,
+            rules,
+        );
+    }
+
+    validate_phases(&mut issues, recipe);
+    validate_times(&mut issues, recipe);
+    validate_loads(&mut issues, recipe, rules);
+    validate_usage(&mut issues, recipe, rules);
+
+    let mut issues = issues.0;
+    issues.sort_by_key(|i| std::cmp::Reverse(i.severity));
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{capability_step, millilitres, quantity, recipe};
+
+    fn issues(appliance: &str, settings: Vec<(ReferenceSettingId, f64)>) -> Vec<String> {
+        let recipe = recipe(2, vec![], vec![capability_step(vec![], settings)]);
+
+        validate(
+            &recipe,
+            Rules {
+                catalog: &Catalog::default(),
+                schemas: &[],
+                appliance: &ApplianceProfile::builtin_or_default(appliance),
+                conversions: &Conversions::default(),
+            },
+        )
+        .into_iter()
+        .filter(|i| i.severity == Severity::Error && i.path.starts_with("Step 1 › "))
+        .filter(|i| !i.path.ends_with("Instructions"))
+        .map(|i| i.message)
+        .collect()
+    }
+
+    #[test]
+    fn holds_settings_to_the_appliance_limits() {
+        let kenwood = ApplianceProfile::KENWOOD_COOKING_CHEF;
+
+        assert_eq!(
+            issues(
+                kenwood,
+                vec![
+                    (ReferenceSettingId::Temperature, 100.0),
+                    (ReferenceSettingId::Time, 60.0),
+                ]
+            ),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            issues(
+                kenwood,
+                vec![
+                    (ReferenceSettingId::Temperature, 200.0),
+                    (ReferenceSettingId::Time, 9.0 * 60.0 * 60.0),
+                ]
+            ),
+            [
+                "The Kenwood Cooking Chef can't go above 180 °C",
+                "The Kenwood Cooking Chef can't go above 8h",
+            ]
+        );
+        assert_eq!(
+            issues(kenwood, vec![(ReferenceSettingId::Time, 0.5)]),
+            ["The Kenwood Cooking Chef can't go below 1s"]
+        );
+    }
+
+    #[test]
+    fn warns_of_too_little_to_heat_or_too_much_for_the_bowl() {
+        let conversions = Conversions {
+            allowed_units: std::collections::HashMap::from([(
+                "water".to_owned(),
+                vec![millilitres()],
+            )]),
+            densities: Default::default(),
+        };
+        let warnings = |ml: f64| {
+            let heat = capability_step(
+                vec![(0, quantity(ml, &millilitres()))],
+                vec![
+                    (ReferenceSettingId::Temperature, 100.0),
+                    (ReferenceSettingId::Time, 60.0),
+                ],
+            );
+            let recipe = recipe(2, vec![("water", quantity(ml, &millilitres()))], vec![heat]);
+
+            validate(
+                &recipe,
+                Rules {
+                    catalog: &Catalog::default(),
+                    schemas: &[],
+                    appliance: &ApplianceProfile::builtin_or_default(ApplianceProfile::INSTANT_POT),
+                    conversions: &conversions,
+                },
+            )
+            .into_iter()
+            .filter(|i| i.path == "Step 1 › Bowl")
+            .map(|i| i.message)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            warnings(100.0),
+            ["Heats only about 100 ml, less than the 250 ml the appliance needs"]
+        );
+        assert_eq!(warnings(1000.0), Vec::<String>::new());
+        assert_eq!(
+            warnings(6000.0),
+            ["Holds about 6000 ml by now, more than the 5700 ml the bowl takes"]
+        );
+    }
+
+    #[test]
+    fn goes_by_the_recipes_appliance() {
+        let settings = vec![
+            (ReferenceSettingId::Temperature, 175.0),
+            (ReferenceSettingId::Time, 12.0 * 60.0 * 60.0),
+        ];
+
+        assert_eq!(
+            issues(ApplianceProfile::INSTANT_POT, settings.clone()),
+            ["The Instant Pot can't go above 170 °C"]
+        );
+        assert_eq!(
+            issues(ApplianceProfile::KENWOOD_COOKING_CHEF, settings),
+            ["The Kenwood Cooking Chef can't go above 8h"]
+        );
+    }
+
+    #[test]
+    fn needs_a_time_and_a_setting_the_appliance_has() {
+        assert_eq!(
+            issues(
+                ApplianceProfile::KENWOOD_COOKING_CHEF,
+                vec![(ReferenceSettingId::Temperature, 100.0)]
+            ),
+            ["Running steps need a time"]
+        );
+        assert!(
+            issues(
+                ApplianceProfile::INSTANT_POT,
+                vec![
+                    (ReferenceSettingId::Speed, 3.0),
+                    (ReferenceSettingId::Time, 60.0),
+                ]
+            )
+            .contains(&"The Instant Pot doesn't have this setting".to_owned())
+        );
+    }
+}
@@ -306,8 +306,33 @@ impl KnownOptions for ReferenceCapability {
 }
 
 impl ReferenceValue {
+    /// The mixer's speeds, from slowest to fastest.
     pub fn stir_settings() -> Vec<ReferenceValue> {
         vec![
+            ReferenceValue {
+                id: "kitchenos:Kenwood:SpeedOff".to_owned(),
+                name: "No stir".to_owned(),
+            },
+            ReferenceValue {
+                id: "kitchenos:Kenwood:SpeedStir1".to_owned(),
+                name: "Stir 1".to_owned(),
+            },
+            ReferenceValue {
+                id: "kitchenos:Kenwood:SpeedStir2".to_owned(),
+                name: "Stir 2".to_owned(),
+            },
+            ReferenceValue {
+                id: "kitchenos:Kenwood:SpeedStir3".to_owned(),
+                name: "Stir 3".to_owned(),
+            },
+            ReferenceValue {
+                id: "kitchenos:Kenwood:SpeedStir4".to_owned(),
+                name: "Stir 4".to_owned(),
+            },
+            ReferenceValue {
+                id: "kitchenos:Kenwood:SpeedMin".to_owned(),
+                name: "Min".to_owned(),
+            },
             ReferenceValue {
                 id: "kitchenos:Kenwood:Speed1".to_owned(),
                 name: "Speed level 1".to_owned(),
@@ -336,30 +361,6 @@ impl ReferenceValue {
                 id: "kitchenos:Kenwood:SpeedMax".to_owned(),
                 name: "Max".to_owned(),
             },
-            ReferenceValue {
-                id: "kitchenos:Kenwood:SpeedMin".to_owned(),
-                name: "Min".to_owned(),
-            },
-            ReferenceValue {
-                id: "kitchenos:Kenwood:SpeedOff".to_owned(),
-                name: "No stir".to_owned(),
-            },
-            ReferenceValue {
-                id: "kitchenos:Kenwood:SpeedStir1".to_owned(),
-                name: "Stir 1".to_owned(),
-            },
-            ReferenceValue {
-                id: "kitchenos:Kenwood:SpeedStir2".to_owned(),
-                name: "Stir 2".to_owned(),
-            },
-            ReferenceValue {
-                id: "kitchenos:Kenwood:SpeedStir3".to_owned(),
-                name: "Stir 3".to_owned(),
-            },
-            ReferenceValue {
-                name: "Stir 4".to_owned(),
-                id: "kitchenos:Kenwood:SpeedStir4".to_owned(),
-            },
         ]
     }
 
@@ -0,0 +1,400 @@
+use dioxus::{fullstack::MultipartFormData, prelude::*};
+use itertools::Itertools;
+
+use crate::components::{
+    button::{Button, ButtonVariant},
+    error_toast, info_toast,
+    input::Input,
+    label::Label,
+};
+
+/// How wide thumbnails are shown in the gallery, in pixels.
+const THUMBNAIL_WIDTH: f64 = 160.0;
+
+#[component]
+pub fn Gallery(recipe_id: String) -> Element {
+    // bumped to reload the gallery after changing it
+    let mut generation = use_signal(|| 0u64);
+    let images = {
+        let recipe_id = recipe_id.clone();
+        use_loader(move || {
+            generation();
+            images_server(recipe_id.clone())
+        })?
+    };
+
+    let ids = use_memo(move || images.read().iter().map(|i| i.id.clone()).collect_vec());
+
+    let reorder = use_callback({
+        let recipe_id = recipe_id.clone();
+        move |(from, to): (usize, usize)| {
+            let recipe_id = recipe_id.clone();
+            let mut ids = ids();
+            async move {
+                if to >= ids.len() {
+                    return;
+                }
+                ids.swap(from, to);
+
+                if let Err(e) = reorder_images_server(recipe_id, ids).await {
+                    error_toast("Reordering images failed", e);
+                }
+                generation += 1;
+            }
+        }
+    });
+
+    rsx! {
+        Label { html_for: "data", "Images" }
+
+        div { class: "flex flex-row flex-wrap gap-4",
+
+            for (idx , image) in images.cloned().into_iter().enumerate() {
+                div {
+                    key: "{image.id}",
+                    class: "flex flex-col gap-2",
+
+                    div { class: "relative",
+                        img {
+                            width: "{THUMBNAIL_WIDTH}px",
+                            src: "/gallery/{image.id}",
+                            title: "Click to set where crops are centred",
+                            onclick: {
+                                let image = image.clone();
+                                move |e: MouseEvent| {
+                                    let image = image.clone();
+                                    async move {
+                                        // without the dimensions we can't tell
+                                        // how the thumbnail was scaled
+                                        let (Some(width), Some(height)) = (image.width, image.height) else {
+                                            return;
+                                        };
+                                        let shown_height = THUMBNAIL_WIDTH * height as f64 / width as f64;
+                                        let point = e.element_coordinates();
+
+                                        if let Err(e) = set_focal_point_server(
+                                                image.id,
+                                                point.x / THUMBNAIL_WIDTH,
+                                                point.y / shown_height,
+                                            )
+                                            .await
+                                        {
+                                            error_toast("Setting focal point failed", e);
+                                        }
+                                        generation += 1;
+                                    }
+                                }
+                            },
+                        }
+                        div {
+                            class: "absolute w-2 h-2 -ml-1 -mt-1 rounded-full bg-red-500 pointer-events-none",
+                            left: "{image.focal_x * 100.0}%",
+                            top: "{image.focal_y * 100.0}%",
+                        }
+                    }
+
+                    div { class: "flex flex-row gap-2",
+                        Button {
+                            variant: ButtonVariant::Outline,
+                            disabled: idx == 0,
+                            onclick: move |_| reorder((idx, idx.saturating_sub(1))),
+                            "<"
+                        }
+                        Button {
+                            variant: ButtonVariant::Outline,
+                            onclick: move |_| reorder((idx, idx + 1)),
+                            ">"
+                        }
+                        Button {
+                            variant: if image.is_hero { ButtonVariant::Primary } else { ButtonVariant::Secondary },
+                            disabled: image.is_hero,
+                            onclick: {
+                                let recipe_id = recipe_id.clone();
+                                let image_id = image.id.clone();
+                                move |_| {
+                                    let recipe_id = recipe_id.clone();
+                                    let image_id = image_id.clone();
+                                    async move {
+                                        if let Err(e) = set_hero_image_server(recipe_id, image_id).await {
+                                            error_toast("Setting hero image failed", e);
+                                        }
+                                        generation += 1;
+                                    }
+                                }
+                            },
+                            "Hero"
+                        }
+                        Button {
+                            variant: ButtonVariant::Destructive,
+                            onclick: {
+                                let image_id = image.id.clone();
+                                move |_| {
+                                    let image_id = image_id.clone();
+                                    async move {
+                                        if let Err(e) = delete_image_server(image_id).await {
+                                            error_toast("Deleting image failed", e);
+                                        }
+                                        generation += 1;
+                                    }
+                                }
+                            },
+                            "X"
+                        }
+                    }
+                }
+            }
+        }
+
+        form {
+            class: "flex flex-col gap-4",
+
+            onsubmit: move |e: FormEvent| async move {
+                e.prevent_default();
+
+                if let Err(e) = upload_image(e.into()).await {
+                    error_toast("Uploading image failed", e);
+                    return;
+                }
+
+                info_toast("Uploaded image");
+                generation += 1;
+            },
+
+            input { name: "id", hidden: true, value: "{recipe_id}" }
+
+            div { class: "flex flex-row gap-4 justify-start items-center",
+                Input {
+                    id: "data",
+                    r#type: "file",
+                    name: "data",
+                    accept: ".png,.jpg,.jpeg,.webp",
+                }
+                Input {
+                    r#type: "submit",
+                    name: "submit",
+                    value: "Add image",
+                }
+            }
+        }
+    }
+}
+
+/// Images attached to a step or ingredient. `kind` is which of the two they
+/// belong to, and picks the route the device fetches them through.
+#[component]
+pub fn MediaAttachments(
+    recipe_id: String,
+    kind: &'static str,
+    media: Store<Vec<types::Media>>,
+) -> Element {
+    rsx! {
+        div { class: "flex flex-row flex-wrap gap-4 items-end",
+
+            for (idx , item) in media.iter().enumerate() {
+                div { key: "{item.id()}", class: "flex flex-col gap-2",
+                    img { width: "{THUMBNAIL_WIDTH}px", src: "/gallery/{item.id()}" }
+
+                    Button {
+                        variant: ButtonVariant::Outline,
+                        onclick: move |_| {
+                            media.remove(idx);
+                        },
+
+                        "X"
+                    }
+                }
+            }
+        }
+
+        form {
+            class: "flex flex-row gap-4 justify-start items-center",
+
+            onsubmit: move |e: FormEvent| async move {
+                e.prevent_default();
+
+                match upload_attached_image(e.into()).await {
+                    Ok(id) => {
+                        media
+                            .push(types::Media {
+                                id,
+                                kind: "image".to_owned(),
+                                url: None,
+                            })
+                    }
+                    Err(e) => error_toast("Uploading image failed", e),
+                }
+            },
+
+            input { name: "id", hidden: true, value: "{recipe_id}" }
+            input { name: "kind", hidden: true, value: "{kind}" }
+
+            Input {
+                r#type: "file",
+                name: "data",
+                accept: ".png,.jpg,.jpeg,.webp",
+            }
+            Input {
+                r#type: "submit",
+                name: "submit",
+                value: "Attach image",
+            }
+        }
+    }
+}
+
+#[server]
+async fn images_server(recipe_id: String) -> Result<Vec<types::RecipeImage>> {
+    use dioxus::CapturedError;
+
+    db::queries::images::list_images(crate::db::db(), &recipe_id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+#[middleware(dioxus::fullstack::axum_core::extract::DefaultBodyLimit::max(1024 * 1024 * 64))]
+async fn upload_image(mut form: MultipartFormData) -> Result<()> {
+    use dioxus::CapturedError;
+
+    let mut recipe_id = None;
+    let mut data = None;
+    let mut file_name = None;
+
+    while let Ok(Some(field)) = form.next_field().await {
+        if field.name() == Some("id") {
+            recipe_id = Some(field.text().await?);
+            continue;
+        }
+
+        if field.name() == Some("data") {
+            file_name = field.file_name().map(ToOwned::to_owned);
+            data = Some(field.bytes().await?);
+            continue;
+        }
+    }
+
+    let (Some(recipe_id), Some(data)) = (recipe_id, data) else {
+        return Ok(());
+    };
+
+    if data.is_empty() {
+        return Err(CapturedError::from_display("No image was selected"));
+    }
+
+    let image_id = db::queries::images::add_image(
+        crate::db::db(),
+        &recipe_id,
+        db::entities::sea_orm_active_enums::ImageKind::Gallery,
+        data.to_vec(),
+        file_name,
+    )
+    .await
+    .map_err(CapturedError::from_display)?;
+
+    db::queries::images::pregenerate_variants(crate::db::db(), &image_id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+
+    Ok(())
+}
+
+#[server]
+#[middleware(dioxus::fullstack::axum_core::extract::DefaultBodyLimit::max(1024 * 1024 * 64))]
+async fn upload_attached_image(mut form: MultipartFormData) -> Result<String> {
+    use db::entities::sea_orm_active_enums::ImageKind;
+    use dioxus::CapturedError;
+
+    let mut recipe_id = None;
+    let mut kind = None;
+    let mut data = None;
+    let mut file_name = None;
+
+    while let Ok(Some(field)) = form.next_field().await {
+        match field.name() {
+            Some("id") => recipe_id = Some(field.text().await?),
+            Some("kind") => kind = Some(field.text().await?),
+            Some("data") => {
+                file_name = field.file_name().map(ToOwned::to_owned);
+                data = Some(field.bytes().await?);
+            }
+            _ => {}
+        }
+    }
+
+    let (Some(recipe_id), Some(kind), Some(data)) = (recipe_id, kind, data) else {
+        return Err(CapturedError::from_display("Incomplete upload"));
+    };
+
+    let kind = match kind.as_str() {
+        "step" => ImageKind::Step,
+        "ingredient" => ImageKind::Ingredient,
+        _ => return Err(CapturedError::from_display("Unknown kind of image")),
+    };
+
+    if data.is_empty() {
+        return Err(CapturedError::from_display("No image was selected"));
+    }
+
+    db::queries::images::add_image(crate::db::db(), &recipe_id, kind, data.to_vec(), file_name)
+        .await
+        .map_err(CapturedError::from_display)
+}
+
+#[server]
+async fn set_hero_image_server(recipe_id: String, image_id: String) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::images::set_hero_image(crate::db::db(), &recipe_id, &image_id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+
+    db::queries::images::pregenerate_variants(crate::db::db(), &image_id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+
+    Ok(())
+}
+
+#[server]
+async fn set_focal_point_server(image_id: String, focal_x: f64, focal_y: f64) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::images::set_focal_point(crate::db::db(), &image_id, focal_x, focal_y)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn reorder_images_server(recipe_id: String, image_ids: Vec<String>) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::images::reorder_images(crate::db::db(), &recipe_id, &image_ids)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn delete_image_server(image_id: String) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::images::delete_image(crate::db::db(), &image_id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[get("/gallery/:image_id", headers: http::HeaderMap)]
+async fn gallery_image(image_id: String) -> Result<dioxus_fullstack::response::Response> {
+    use dioxus::fullstack::response::IntoResponse;
+    use dioxus::logger::tracing::{info_span, Instrument as _};
+
+    let image = db::queries::images::get_image(crate::db::db(), &image_id)
+        .instrument(info_span!("Loading image"))
+        .await
+        .ok();
+
+    if let Some(image) = image {
+        return crate::components::recipe_item::serve_image(&headers, image).await;
+    }
+
+    Ok(StatusCode::NOT_FOUND.into_response())
+}
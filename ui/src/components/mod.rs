@@ -6,8 +6,16 @@ mod hero;
 
 mod echo;
 
-mod recipe_item;
+pub(crate) mod recipe_item;
 pub use recipe_item::RecipeItem;
+mod gallery;
+pub use gallery::{Gallery, MediaAttachments};
+mod revision_history;
+pub use revision_history::RevisionHistory;
+mod timeline;
+pub use timeline::Timeline;
+mod notify;
+pub use notify::{error_toast, info_toast, saved_toast};
 pub mod button;
 pub mod card;
 pub mod checkbox;
@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+use dioxus_primitives::toast::{consume_toast, ToastOptions};
+
+/// Says something went wrong, with the error as the description.
+pub fn error_toast(title: &str, e: impl ToString) {
+    consume_toast().error(
+        title.to_owned(),
+        ToastOptions::new()
+            .description(e.to_string())
+            .duration(Duration::from_secs(10)),
+    );
+}
+
+/// Says something worked.
+pub fn info_toast(title: impl Into<String>) {
+    consume_toast().info(
+        title.into(),
+        ToastOptions::new().duration(Duration::from_secs(3)),
+    );
+}
+
+pub fn saved_toast() {
+    info_toast("Saved");
+}
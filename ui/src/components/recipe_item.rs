@@ -2,25 +2,54 @@ use dioxus::prelude::*;
 
 use crate::components::button::*;
 use crate::components::card::*;
+use crate::components::error_toast;
 use crate::Route;
 
 #[component]
-pub fn RecipeItem(recipe: types::Recipe) -> Element {
+pub fn RecipeItem(recipe: types::Recipe, on_delete: EventHandler<()>) -> Element {
     let recipe_id = recipe.id.clone();
 
     rsx! {
         Card { class: "w-full",
 
             CardHeader {
-                CardTitle { "{recipe.name}" }
+                CardTitle {
+                    "{recipe.name}"
+                    if recipe.state == types::Recipe::DRAFT {
+                        span { class: "ml-2 text-sm text-(--secondary-color-5)", "Draft" }
+                    } else if !recipe.is_live(chrono::Utc::now()) {
+                        span { class: "ml-2 text-sm text-(--secondary-color-5)", "Scheduled" }
+                    }
+                }
                 CardDescription { "{recipe.description}" }
                 CardAction {
-                    LinkButton {
-                        to: Route::EditRecipe {
-                            id: recipe.id.clone(),
-                        },
+                    div { class: "flex flex-row gap-2",
+                        LinkButton {
+                            to: Route::EditRecipe {
+                                id: recipe.id.clone(),
+                            },
+
+                            "Edit"
+                        }
+
+                        Button {
+                            variant: ButtonVariant::Destructive,
+                            onclick: {
+                                let id = recipe.id.clone();
+                                move |_| {
+                                    let id = id.clone();
+                                    async move {
+                                        if let Err(e) = crate::views::delete_recipe_server(id).await {
+                                            error_toast("Deleting recipe failed", e);
+                                            return;
+                                        }
+                                        on_delete(());
+                                    }
+                                }
+                            },
 
-                        "Edit"
+                            "Delete"
+                        }
                     }
                 }
             }
@@ -36,23 +65,67 @@ pub fn RecipeItem(recipe: types::Recipe) -> Element {
     }
 }
 
-#[get("/image/:recipe_id")]
+/// Serves a stored image in whichever format the client prefers, or a 304 if
+/// it already has it.
+#[cfg(feature = "server")]
+pub(crate) async fn serve_image(
+    headers: &http::HeaderMap,
+    image: db::queries::images::StoredImage,
+) -> Result<dioxus_fullstack::response::Response> {
+    use dioxus::fullstack::response::IntoResponse;
+    use dioxus::logger::tracing::{info_span, Instrument as _};
+    use dioxus::CapturedError;
+    use http::{header, HeaderValue};
+
+    let format = db::queries::images::negotiate_format(
+        headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok()),
+        image.format(),
+    );
+    let etag = format!("\"{}.{}\"", image.etag, format.extensions_str()[0]);
+
+    let mut cache_headers = http::HeaderMap::new();
+    cache_headers.insert(header::ETAG, HeaderValue::from_str(&etag)?);
+    cache_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    cache_headers.insert(header::VARY, HeaderValue::from_static("accept"));
+    if let Some(modified_at) = image.modified_at {
+        cache_headers.insert(
+            header::LAST_MODIFIED,
+            HeaderValue::from_str(&modified_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())?,
+        );
+    }
+
+    if db::etag::not_modified(headers, &etag, image.modified_at) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    let data = db::queries::images::get_converted(crate::db::db(), &image, format)
+        .instrument(info_span!("Converting image"))
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+
+    Ok((
+        StatusCode::OK,
+        cache_headers,
+        [(header::CONTENT_TYPE, format.to_mime_type())],
+        data,
+    )
+        .into_response())
+}
+
+#[get("/image/:recipe_id", headers: http::HeaderMap)]
 async fn image(recipe_id: String) -> Result<dioxus_fullstack::response::Response> {
     use dioxus::fullstack::response::IntoResponse;
     use dioxus::logger::tracing::{info_span, Instrument as _};
 
-    let image = db::queries::images::get_image(crate::db::db(), &recipe_id)
+    let image = db::queries::images::get_hero_image(crate::db::db(), &recipe_id)
         .instrument(info_span!("Loading image"))
         .await
         .ok();
 
     if let Some(image) = image {
-        return Ok((
-            StatusCode::OK,
-            [(http::header::CONTENT_TYPE, "image/webp")],
-            image,
-        )
-            .into_response());
+        return serve_image(&headers, image).await;
     }
 
     Ok(StatusCode::NOT_FOUND.into_response())
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{consume_toast, ToastOptions};
+
+use crate::components::{
+    button::{Button, ButtonVariant},
+    card::*,
+};
+
+/// Lists a recipe's saved revisions, diffs any two of them, and restores old
+/// ones into `recipe`. `saved` should be bumped whenever the recipe is saved.
+#[component]
+pub fn RevisionHistory(recipe: Store<types::Recipe>, saved: ReadSignal<u64>) -> Element {
+    let mut restored = use_signal(|| 0u64);
+    let revisions = use_loader(move || {
+        saved();
+        restored();
+        revisions_server(recipe.peek().id.clone())
+    })?;
+
+    // revisions to diff from and to, defaulting to the last change made
+    let mut from = use_signal(|| None::<i64>);
+    let mut to = use_signal(|| None::<i64>);
+    let from_id = use_memo(move || from().or_else(|| revisions.read().get(1).map(|r| r.id)));
+    let to_id = use_memo(move || to().or_else(|| revisions.read().first().map(|r| r.id)));
+
+    let changes = use_loader(move || diff_server(from_id(), to_id()))?;
+
+    rsx! {
+        Card { class: "w-full",
+            CardHeader {
+                CardTitle { "History" }
+                CardDescription { "Pick two revisions to compare them" }
+            }
+
+            CardContent { class: "flex flex-col gap-4",
+
+                for revision in revisions.cloned() {
+                    div {
+                        key: "{revision.id}",
+                        class: "flex flex-row gap-2 items-center",
+
+                        span { class: "grow",
+                            "{revision.created_at.format(\"%Y-%m-%d %H:%M:%S\")}"
+                            if let Some(editor) = &revision.editor {
+                                " by {editor}"
+                            }
+                        }
+
+                        Button {
+                            variant: if from_id() == Some(revision.id) { ButtonVariant::Primary } else { ButtonVariant::Outline },
+                            onclick: move |_| from.set(Some(revision.id)),
+                            "From"
+                        }
+                        Button {
+                            variant: if to_id() == Some(revision.id) { ButtonVariant::Primary } else { ButtonVariant::Outline },
+                            onclick: move |_| to.set(Some(revision.id)),
+                            "To"
+                        }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            onclick: move |_| async move {
+                                let toast_api = consume_toast();
+
+                                match restore_revision_server(revision.id).await {
+                                    Ok(r) => {
+                                        recipe.set(r);
+                                        restored += 1;
+                                        toast_api
+                                            .info(
+                                                "Restored revision".to_owned(),
+                                                ToastOptions::new().duration(Duration::from_secs(3)),
+                                            );
+                                    }
+                                    Err(e) => {
+                                        toast_api
+                                            .error(
+                                                "Restoring revision failed".to_owned(),
+                                                ToastOptions::new()
+                                                    .description(e.to_string())
+                                                    .duration(Duration::from_secs(10)),
+                                            );
+                                    }
+                                }
+                            },
+                            "Restore"
+                        }
+                    }
+                }
+
+                if changes.read().is_empty() {
+                    p { "No differences" }
+                } else {
+                    table { class: "w-full text-left",
+                        thead {
+                            tr {
+                                th { "What" }
+                                th { "From" }
+                                th { "To" }
+                            }
+                        }
+                        tbody {
+                            for change in changes.cloned() {
+                                tr {
+                                    td { "{change.path}" }
+                                    td { class: "text-red-500", "{change.before.unwrap_or_default()}" }
+                                    td { class: "text-green-500", "{change.after.unwrap_or_default()}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[server]
+async fn revisions_server(recipe_id: String) -> Result<Vec<types::RecipeRevision>> {
+    use dioxus::CapturedError;
+
+    db::queries::revisions::list_revisions(crate::db::db(), &recipe_id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn diff_server(from: Option<i64>, to: Option<i64>) -> Result<Vec<types::diff::Change>> {
+    use dioxus::CapturedError;
+
+    let (Some(from), Some(to)) = (from, to) else {
+        return Ok(Vec::new());
+    };
+
+    let from = db::queries::revisions::get_revision(crate::db::db(), from)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+    let to = db::queries::revisions::get_revision(crate::db::db(), to)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+
+    Ok(types::diff::diff_recipes(&from, &to))
+}
+
+#[post("/api/revisions/restore", headers: http::HeaderMap)]
+async fn restore_revision_server(revision_id: i64) -> Result<types::Recipe> {
+    use dioxus::CapturedError;
+
+    db::queries::revisions::restore_revision(
+        crate::db::db(),
+        revision_id,
+        crate::db::editor_from_headers(&headers),
+    )
+    .await
+    .map_err(|e| CapturedError::from_boxed(e.into()))
+}
@@ -0,0 +1,252 @@
+use dioxus::prelude::*;
+use types::timing::{clock, setting};
+
+use crate::components::card::*;
+
+const WIDTH: f64 = 1000.0;
+const PHASE_Y: f64 = 14.0;
+const BARS_Y: f64 = 22.0;
+const BARS_HEIGHT: f64 = 28.0;
+const TEMPERATURE_Y: f64 = 64.0;
+const TEMPERATURE_HEIGHT: f64 = 90.0;
+const SPEED_Y: f64 = 170.0;
+const SPEED_HEIGHT: f64 = 50.0;
+const HEIGHT: f64 = SPEED_Y + SPEED_HEIGHT + 6.0;
+
+/// The bowl temperature during a step, with no heat as zero.
+fn temperature(step: &types::RecipeStep) -> f64 {
+    match setting(step, types::ReferenceSettingId::Temperature) {
+        Some(types::SettingValue::Numeric { value, .. }) => *value,
+        _ => 0.0,
+    }
+}
+
+/// The speed as a step up from the slowest of `speeds`, and its name.
+fn speed(step: &types::RecipeStep, speeds: &[types::ReferenceValue]) -> (usize, Option<String>) {
+    match setting(step, types::ReferenceSettingId::Speed) {
+        Some(types::SettingValue::Nominal {
+            reference_value, ..
+        }) => (
+            speeds
+                .iter()
+                .position(|s| s.id == reference_value.id)
+                .unwrap_or(0),
+            Some(reference_value.name.clone()),
+        ),
+        _ => (0, None),
+    }
+}
+
+/// Steps of the same capability share a colour, and manual steps are grey.
+fn colour(step: &types::RecipeStep) -> String {
+    match &step.capability {
+        Some(capability) => {
+            let hue = capability
+                .reference_capability
+                .id
+                .bytes()
+                .fold(7u32, |h, b| h.wrapping_mul(31).wrapping_add(b as u32))
+                % 360;
+            format!("hsl({hue}, 60%, 55%)")
+        }
+        None => "var(--secondary-color-5)".to_owned(),
+    }
+}
+
+fn kind_label(kind: types::timing::StepTimeKind) -> &'static str {
+    match kind {
+        types::timing::StepTimeKind::Manual => "by hand",
+        types::timing::StepTimeKind::Machine => "mixing",
+        types::timing::StepTimeKind::Cooking => "cooking",
+    }
+}
+
+/// What a step does, for its tooltip.
+fn describe(
+    idx: usize,
+    step: &types::RecipeStep,
+    timing: &types::timing::StepTiming,
+    speeds: &[types::ReferenceValue],
+) -> String {
+    let mut parts = vec![format!("Step {}", idx + 1)];
+    if let Some(capability) = &step.capability {
+        parts.push(capability.reference_capability.name.clone());
+    }
+    parts.push(if timing.hands_off() {
+        format!("{}, hands-off", kind_label(timing.kind))
+    } else {
+        format!("{} (estimated)", kind_label(timing.kind))
+    });
+    let temperature = temperature(step);
+    if temperature > 0.0 {
+        parts.push(format!("{temperature} °C"));
+    }
+    if let (_, Some(name)) = speed(step, speeds) {
+        parts.push(format!("on {name}"));
+    }
+    parts.push(format!(
+        "for {} from {}",
+        clock(timing.duration),
+        clock(timing.start)
+    ));
+
+    parts.join(", ")
+}
+
+/// A step function through `(start, end, value)` runs, as SVG points.
+fn curve(runs: &[(f64, f64, f64)]) -> String {
+    runs.iter()
+        .flat_map(|(start, end, y)| [format!("{start:.1},{y:.1}"), format!("{end:.1},{y:.1}")])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The recipe laid out over time: a bar per step, coloured by capability,
+/// with the temperature and speed it runs at underneath and where each phase
+/// starts marked along the top.
+#[component]
+pub fn Timeline(
+    steps: Vec<types::RecipeStep>,
+    timing: types::timing::Timing,
+    speeds: Vec<types::ReferenceValue>,
+) -> Element {
+    let total = timing.total().as_secs_f64().max(1.0);
+    let x = |d: jiff::SignedDuration| d.as_secs_f64() / total * WIDTH;
+
+    let runs = timing
+        .steps
+        .iter()
+        .filter_map(|t| Some((t, steps.get(t.step_idx)?)))
+        .collect::<Vec<_>>();
+
+    let max_temperature = runs
+        .iter()
+        .map(|(_, s)| temperature(s))
+        .fold(100.0_f64, f64::max);
+    let temperatures = runs
+        .iter()
+        .map(|(t, s)| {
+            let y = TEMPERATURE_Y + TEMPERATURE_HEIGHT
+                - temperature(s) / max_temperature * TEMPERATURE_HEIGHT;
+            (x(t.start), x(t.end()), y)
+        })
+        .collect::<Vec<_>>();
+    let fastest = speeds.len().saturating_sub(1).max(1) as f64;
+    let speed_curve = runs
+        .iter()
+        .map(|(t, s)| {
+            let y = SPEED_Y + SPEED_HEIGHT - speed(s, &speeds).0 as f64 / fastest * SPEED_HEIGHT;
+            (x(t.start), x(t.end()), y)
+        })
+        .collect::<Vec<_>>();
+
+    // how the time splits between the cook and the appliance
+    let breakdown = [
+        types::timing::StepTimeKind::Manual,
+        types::timing::StepTimeKind::Machine,
+        types::timing::StepTimeKind::Cooking,
+    ]
+    .map(|kind| {
+        let time = timing
+            .steps
+            .iter()
+            .filter(|t| t.kind == kind)
+            .map(|t| t.duration)
+            .sum::<jiff::SignedDuration>();
+        (kind_label(kind), clock(time))
+    });
+
+    // a marker wherever the phase changes
+    let mut phases = Vec::new();
+    let mut previous = None;
+    for (t, s) in &runs {
+        if let Some(capability) = &s.capability {
+            if previous != Some(&capability.phase.id) {
+                phases.push((x(t.start), capability.phase.name.clone()));
+            }
+            previous = Some(&capability.phase.id);
+        }
+    }
+
+    rsx! {
+        Card { class: "w-full",
+            CardHeader {
+                CardTitle { "Timeline" }
+                CardDescription {
+                    "{clock(timing.total())} in all, with the temperature (up to {max_temperature} °C) in orange and the speed in blue"
+                }
+            }
+            CardContent {
+                svg {
+                    class: "w-full",
+                    view_box: "0 0 {WIDTH} {HEIGHT}",
+                    preserve_aspect_ratio: "none",
+
+                    for (at , name) in phases {
+                        line {
+                            x1: "{at}",
+                            x2: "{at}",
+                            y1: "{PHASE_Y}",
+                            y2: "{HEIGHT}",
+                            stroke: "currentColor",
+                            stroke_opacity: "0.3",
+                            stroke_dasharray: "4 4",
+                        }
+                        text {
+                            x: "{at + 3.0}",
+                            y: "{PHASE_Y - 3.0}",
+                            font_size: "11",
+                            fill: "currentColor",
+                            "{name}"
+                        }
+                    }
+
+                    for (t , s) in runs.iter() {
+                        rect {
+                            x: "{x(t.start)}",
+                            y: "{BARS_Y}",
+                            width: "{(x(t.end()) - x(t.start)).max(1.0)}",
+                            height: "{BARS_HEIGHT}",
+                            rx: "3",
+                            fill: colour(s),
+                            stroke: "var(--primary-color)",
+                            stroke_width: "1",
+                            title { "{describe(t.step_idx, s, t, &speeds)}" }
+                        }
+                        if x(t.end()) - x(t.start) > 24.0 {
+                            text {
+                                x: "{x(t.start) + 4.0}",
+                                y: "{BARS_Y + BARS_HEIGHT / 2.0 + 4.0}",
+                                font_size: "12",
+                                fill: "white",
+                                "{t.step_idx + 1}"
+                            }
+                        }
+                    }
+
+                    polyline {
+                        points: curve(&temperatures),
+                        fill: "none",
+                        stroke: "rgb(249 115 22)",
+                        stroke_width: "2",
+                    }
+                    polyline {
+                        points: curve(&speed_curve),
+                        fill: "none",
+                        stroke: "rgb(14 165 233)",
+                        stroke_width: "2",
+                    }
+                }
+
+                div { class: "flex flex-row flex-wrap gap-4 text-sm",
+                    for (label , time) in breakdown {
+                        span { "{time} {label}" }
+                    }
+                    span { class: "text-(--secondary-color-5)",
+                        "{clock(timing.hands_off)} hands-off, steps done by hand are estimates"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -6,3 +6,12 @@ pub static DB: Lazy<DatabaseConnection> = Lazy::new(async || db::connect().await
 pub fn db() -> &'static DatabaseConnection {
     DB.get()
 }
+
+/// Who's making a request, as told to us by Home Assistant's ingress proxy.
+pub fn editor_from_headers(headers: &http::HeaderMap) -> Option<String> {
+    ["x-remote-user-display-name", "x-remote-user-name"]
+        .into_iter()
+        .find_map(|name| headers.get(name)?.to_str().ok())
+        .filter(|editor| !editor.is_empty())
+        .map(ToOwned::to_owned)
+}
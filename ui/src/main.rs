@@ -2,7 +2,7 @@
 // need dioxus
 use dioxus::prelude::*;
 
-use views::{EditRecipe, Home, Ingest, Navbar, NewRecipe};
+use views::{Appliances, Authors, Catalog, EditRecipe, Home, Ingest, Navbar, NewRecipe, Trash};
 
 /// Define a components module that contains all shared components for our app.
 mod components;
@@ -29,6 +29,14 @@ enum Route {
     NewRecipe {},
     #[route("/ingest")]
     Ingest {},
+    #[route("/trash")]
+    Trash {},
+    #[route("/authors")]
+    Authors {},
+    #[route("/catalog")]
+    Catalog {},
+    #[route("/appliances")]
+    Appliances {},
 }
 
 // We can import assets in dioxus with the `asset!` macro. This macro takes a path to an asset relative to the crate root.
@@ -0,0 +1,373 @@
+use dioxus::prelude::*;
+use dioxus_primitives::checkbox::CheckboxState;
+use types::appliance::{ApplianceProfile, CapabilityLoad, SettingLimits};
+
+use crate::components::{
+    button::{Button, ButtonVariant},
+    card::*,
+    checkbox::*,
+    error_toast,
+    input::Input,
+    label::Label,
+    native_select, saved_toast,
+};
+
+fn checkbox_state(checked: bool) -> Option<CheckboxState> {
+    Some(if checked {
+        CheckboxState::Checked
+    } else {
+        CheckboxState::Unchecked
+    })
+}
+
+/// A number input's value, blank meaning no limit.
+fn parse_limit(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok()
+}
+
+/// The appliances recipes can be written for, and what each of them accepts.
+#[component]
+pub fn Appliances() -> Element {
+    // bumped to reload the appliances after changing them
+    let mut generation = use_signal(|| 0u64);
+    let appliances = use_loader(move || {
+        generation();
+        appliances_server()
+    })?;
+    let catalog = use_loader(crate::views::catalog_server)?;
+
+    let mut new_id = use_signal(String::new);
+    let mut new_name = use_signal(String::new);
+
+    rsx! {
+        div { class: "flex flex-col gap-4",
+
+            for appliance in appliances.cloned() {
+                ApplianceCard {
+                    key: "{appliance.id}",
+                    appliance,
+                    catalog: catalog.cloned(),
+                    on_change: move |_| generation += 1,
+                }
+            }
+
+            Card { class: "w-full",
+                CardHeader {
+                    CardTitle { "New appliance" }
+                    CardDescription { "It starts out with every capability and no settings" }
+                }
+                CardContent { class: "flex flex-row gap-4 items-center",
+                    Input {
+                        placeholder: "Id, like instant-pot",
+                        value: "{new_id}",
+                        oninput: move |e: FormEvent| new_id.set(e.value()),
+                    }
+                    Input {
+                        placeholder: "Name",
+                        value: "{new_name}",
+                        oninput: move |e: FormEvent| new_name.set(e.value()),
+                    }
+                    Button {
+                        onclick: move |_| async move {
+                            let appliance = ApplianceProfile {
+                                id: new_id(),
+                                name: new_name(),
+                                user_agents: vec![],
+                                capabilities: vec![],
+                                settings: vec![],
+                                bowl_capacity_ml: None,
+                                min_heating_ml: None,
+                                capability_loads: vec![],
+                            };
+                            if let Err(e) = save_appliance_server(appliance).await {
+                                error_toast("Adding appliance failed", e);
+                                return;
+                            }
+                            new_id.set(String::new());
+                            new_name.set(String::new());
+                            generation += 1;
+                        },
+                        "Add appliance"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ApplianceCard(
+    appliance: ApplianceProfile,
+    catalog: types::catalog::Catalog,
+    on_change: EventHandler<()>,
+) -> Element {
+    let id = appliance.id.clone();
+    let mut name = use_signal(|| appliance.name.clone());
+    let mut user_agents = use_signal(|| appliance.user_agents.join(", "));
+    let mut capabilities = use_signal(|| appliance.capabilities.clone());
+    let mut settings = use_signal(|| appliance.settings.clone());
+    let mut bowl_capacity = use_signal(|| {
+        appliance
+            .bowl_capacity_ml
+            .map(|c| c.to_string())
+            .unwrap_or_default()
+    });
+    let mut min_heating = use_signal(|| {
+        appliance
+            .min_heating_ml
+            .map(|c| c.to_string())
+            .unwrap_or_default()
+    });
+    let mut capability_loads = use_signal(|| appliance.capability_loads.clone());
+    let mut new_load_capability = use_signal(|| None::<String>);
+
+    rsx! {
+        Card { class: "w-full",
+            CardHeader {
+                CardTitle { "{appliance.name}" }
+                CardDescription { "{appliance.id}" }
+            }
+            CardContent { class: "flex flex-col gap-4",
+
+                Label { html_for: "appliance_name_{id}", "Name" }
+                Input {
+                    id: "appliance_name_{id}",
+                    value: "{name}",
+                    oninput: move |e: FormEvent| name.set(e.value()),
+                }
+
+                Label { html_for: "appliance_user_agents_{id}", "User agents" }
+                Input {
+                    id: "appliance_user_agents_{id}",
+                    placeholder: "Parts of the User-Agent it sends, separated by commas",
+                    value: "{user_agents}",
+                    oninput: move |e: FormEvent| user_agents.set(e.value()),
+                }
+                span { class: "text-sm text-(--secondary-color-5)", "Devices matching no appliance are taken to be the Kenwood" }
+
+                Label { html_for: "appliance_bowl_{id}", "Bowl capacity (ml)" }
+                Input {
+                    id: "appliance_bowl_{id}",
+                    r#type: "number",
+                    min: 0,
+                    value: "{bowl_capacity}",
+                    oninput: move |e: FormEvent| bowl_capacity.set(e.value()),
+                }
+
+                Label { html_for: "appliance_min_heating_{id}", "Least it can heat (ml)" }
+                Input {
+                    id: "appliance_min_heating_{id}",
+                    r#type: "number",
+                    min: 0,
+                    value: "{min_heating}",
+                    oninput: move |e: FormEvent| min_heating.set(e.value()),
+                }
+
+                Label { html_for: "capability_loads", "Loads by capability (ml)" }
+                span { class: "text-sm text-(--secondary-color-5)", "For capabilities that need more in the bowl, or take less, than the bowl as a whole" }
+                for load in capability_loads() {
+                    div { class: "flex flex-row gap-4 items-center",
+                        span { class: "w-1/3",
+                            {
+                                catalog
+                                    .capabilities
+                                    .iter()
+                                    .find(|c| c.id == load.capability)
+                                    .map(|c| c.name.clone())
+                                    .unwrap_or_else(|| load.capability.clone())
+                            }
+                        }
+                        Input {
+                            r#type: "number",
+                            min: 0,
+                            placeholder: "Min",
+                            value: load.min_ml.map(|m| m.to_string()).unwrap_or_default(),
+                            oninput: {
+                                let capability = load.capability.clone();
+                                move |e: FormEvent| {
+                                    if let Some(l) = capability_loads.write().iter_mut().find(|l| l.capability == capability) {
+                                        l.min_ml = parse_limit(&e.value());
+                                    }
+                                }
+                            },
+                        }
+                        Input {
+                            r#type: "number",
+                            min: 0,
+                            placeholder: "Max",
+                            value: load.max_ml.map(|m| m.to_string()).unwrap_or_default(),
+                            oninput: {
+                                let capability = load.capability.clone();
+                                move |e: FormEvent| {
+                                    if let Some(l) = capability_loads.write().iter_mut().find(|l| l.capability == capability) {
+                                        l.max_ml = parse_limit(&e.value());
+                                    }
+                                }
+                            },
+                        }
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: {
+                                let capability = load.capability.clone();
+                                move |_| capability_loads.write().retain(|l| l.capability != capability)
+                            },
+                            "Remove"
+                        }
+                    }
+                }
+                div { class: "flex flex-row gap-4 items-center",
+                    native_select::NativeSelect::<String> {
+                        value: Some(new_load_capability()),
+                        on_value_change: move |v: Option<String>| new_load_capability.set(v),
+
+                        for capability in catalog.capabilities.clone() {
+                            if !capability_loads.read().iter().any(|l| l.capability == capability.id) {
+                                native_select::NativeSelectOption::<String> {
+                                    value: capability.id.clone(),
+                                    "{capability.name}"
+                                }
+                            }
+                        }
+                    }
+                    Button {
+                        variant: ButtonVariant::Outline,
+                        onclick: move |_| {
+                            if let Some(capability) = new_load_capability() {
+                                capability_loads.write().push(CapabilityLoad {
+                                    capability,
+                                    min_ml: None,
+                                    max_ml: None,
+                                });
+                                new_load_capability.set(None);
+                            }
+                        },
+                        "Add load"
+                    }
+                }
+
+                Label { html_for: "capabilities", "Capabilities" }
+                span { class: "text-sm text-(--secondary-color-5)", "None ticked means it has all of them" }
+                div { class: "flex flex-row flex-wrap gap-4 items-center",
+                    for capability in catalog.capabilities.clone() {
+                        div { class: "flex flex-row gap-2 items-center",
+                            Checkbox {
+                                name: "capability_{id}_{capability.id}",
+                                checked: checkbox_state(capabilities.read().contains(&capability.id)),
+                                on_checked_change: {
+                                    let capability = capability.id.clone();
+                                    move |v: CheckboxState| {
+                                        let checked: bool = v.into();
+                                        let mut capabilities = capabilities.write();
+                                        capabilities.retain(|c| *c != capability);
+                                        if checked {
+                                            capabilities.push(capability.clone());
+                                        }
+                                    }
+                                },
+                            }
+                            Label { html_for: "capability_{id}_{capability.id}", "{capability.name}" }
+                        }
+                    }
+                }
+
+                Label { html_for: "settings", "Settings" }
+                for setting in catalog.settings.clone() {
+                    div { class: "flex flex-row gap-4 items-center",
+                        div { class: "flex flex-row gap-2 items-center w-1/3",
+                            Checkbox {
+                                name: "setting_{id}_{setting.id}",
+                                checked: checkbox_state(settings.read().iter().any(|s| s.id == setting.id)),
+                                on_checked_change: move |v: CheckboxState| {
+                                    let checked: bool = v.into();
+                                    let mut settings = settings.write();
+                                    settings.retain(|s| s.id != setting.id);
+                                    if checked {
+                                        settings.push(SettingLimits {
+                                            id: setting.id,
+                                            min: None,
+                                            max: None,
+                                        });
+                                    }
+                                },
+                            }
+                            Label { html_for: "setting_{id}_{setting.id}", "{setting.name}" }
+                        }
+
+                        if let Some(limits) = settings.read().iter().find(|s| s.id == setting.id).cloned() {
+                            Input {
+                                r#type: "number",
+                                placeholder: "Min",
+                                value: limits.min.map(|m| m.to_string()).unwrap_or_default(),
+                                oninput: move |e: FormEvent| {
+                                    if let Some(s) = settings.write().iter_mut().find(|s| s.id == setting.id) {
+                                        s.min = parse_limit(&e.value());
+                                    }
+                                },
+                            }
+                            Input {
+                                r#type: "number",
+                                placeholder: "Max",
+                                value: limits.max.map(|m| m.to_string()).unwrap_or_default(),
+                                oninput: move |e: FormEvent| {
+                                    if let Some(s) = settings.write().iter_mut().find(|s| s.id == setting.id) {
+                                        s.max = parse_limit(&e.value());
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+
+                div { class: "flex flex-row justify-end",
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: move |_| {
+                            let appliance = ApplianceProfile {
+                                id: id.clone(),
+                                name: name(),
+                                user_agents: user_agents
+                                    .read()
+                                    .split(',')
+                                    .map(|a| a.trim().to_owned())
+                                    .filter(|a| !a.is_empty())
+                                    .collect(),
+                                capabilities: capabilities(),
+                                settings: settings(),
+                                bowl_capacity_ml: parse_limit(&bowl_capacity.read()),
+                                min_heating_ml: parse_limit(&min_heating.read()),
+                                capability_loads: capability_loads(),
+                            };
+                            async move {
+                                if let Err(e) = save_appliance_server(appliance).await {
+                                    error_toast("Saving appliance failed", e);
+                                    return;
+                                }
+                                saved_toast();
+                                on_change(());
+                            }
+                        },
+                        "Save"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[server]
+pub(crate) async fn appliances_server() -> Result<Vec<ApplianceProfile>> {
+    use dioxus::CapturedError;
+
+    db::queries::appliances::list_appliances(crate::db::db())
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn save_appliance_server(appliance: ApplianceProfile) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::appliances::save_appliance(crate::db::db(), appliance)
+        .await
+        .map_err(CapturedError::from_display)
+}
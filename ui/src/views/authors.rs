@@ -0,0 +1,328 @@
+use dioxus::{fullstack::MultipartFormData, prelude::*};
+
+use crate::components::{
+    button::{Button, ButtonVariant},
+    card::*,
+    error_toast, info_toast,
+    input::Input,
+    label::Label,
+};
+
+#[component]
+pub fn Authors() -> Element {
+    // bumped to reload the authors after changing them
+    let mut generation = use_signal(|| 0u64);
+    let authors = use_loader(move || {
+        generation();
+        authors_server()
+    })?;
+
+    let mut new_name = use_signal(String::new);
+
+    rsx! {
+        div { class: "flex flex-col gap-4",
+
+            for author in authors.cloned() {
+                AuthorCard {
+                    key: "{author.id}",
+                    author,
+                    generation: generation(),
+                    on_change: move |_| generation += 1,
+                }
+            }
+
+            Card { class: "w-full",
+                CardHeader {
+                    CardTitle { "New author" }
+                    CardDescription { "Someone in the household who writes recipes" }
+                }
+
+                CardContent { class: "flex flex-row gap-4 items-center",
+                    Input {
+                        placeholder: "Name",
+                        value: "{new_name}",
+                        oninput: move |e: FormEvent| new_name.set(e.value()),
+                    }
+                    Button {
+                        disabled: new_name.read().trim().is_empty(),
+                        onclick: move |_| async move {
+                            let name = new_name();
+                            if let Err(e) = save_author_server(None, name, String::new(), String::new()).await {
+                                error_toast("Adding author failed", e);
+                                return;
+                            }
+                            new_name.set(String::new());
+                            generation += 1;
+                        },
+                        "Add author"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AuthorCard(
+    author: types::AuthorProfile,
+    /// Changes whenever the authors are reloaded, so new avatars get fetched
+    generation: u64,
+    on_change: EventHandler<()>,
+) -> Element {
+    let id = author.id;
+    let mut name = use_signal(|| author.name.clone());
+    let mut image = use_signal(|| author.image.clone());
+    let mut url = use_signal(|| author.url.clone());
+
+    rsx! {
+        Card { class: "w-full",
+            CardHeader {
+                CardTitle {
+                    "{author.name}"
+                    if author.is_default {
+                        span { class: "ml-2 text-sm text-(--secondary-color-5)", "Default" }
+                    }
+                }
+                CardDescription { "{author.recipe_count} recipes" }
+                CardAction {
+                    div { class: "flex flex-row gap-2",
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            disabled: author.is_default,
+                            onclick: move |_| async move {
+                                if let Err(e) = set_default_author_server(id).await {
+                                    error_toast("Setting default author failed", e);
+                                }
+                                on_change(());
+                            },
+                            "Make default"
+                        }
+                        Button {
+                            variant: ButtonVariant::Destructive,
+                            onclick: move |_| async move {
+                                if let Err(e) = delete_author_server(id).await {
+                                    error_toast("Deleting author failed", e);
+                                }
+                                on_change(());
+                            },
+                            "Delete"
+                        }
+                    }
+                }
+            }
+
+            CardContent { class: "flex flex-col gap-4",
+                div { class: "flex flex-row gap-4 items-center",
+                    if author.has_avatar {
+                        img {
+                            width: "64px",
+                            height: "64px",
+                            class: "rounded-full",
+                            src: "/authors/{id}/avatar?v={generation}",
+                        }
+                        Button {
+                            variant: ButtonVariant::Outline,
+                            onclick: move |_| async move {
+                                if let Err(e) = remove_avatar_server(id).await {
+                                    error_toast("Removing avatar failed", e);
+                                }
+                                on_change(());
+                            },
+                            "Remove avatar"
+                        }
+                    } else if !author.image.is_empty() {
+                        img {
+                            width: "64px",
+                            height: "64px",
+                            class: "rounded-full",
+                            src: "{author.image}",
+                        }
+                    }
+
+                    form {
+                        class: "flex flex-row gap-4 items-center",
+
+                        onsubmit: move |e: FormEvent| async move {
+                            e.prevent_default();
+
+                            if let Err(e) = upload_avatar(e.into()).await {
+                                error_toast("Uploading avatar failed", e);
+                                return;
+                            }
+                            on_change(());
+                        },
+
+                        input { name: "id", hidden: true, value: "{id}" }
+
+                        Input {
+                            r#type: "file",
+                            name: "data",
+                            accept: ".png,.jpg,.jpeg,.webp",
+                        }
+                        Input {
+                            r#type: "submit",
+                            name: "submit",
+                            value: "Upload avatar",
+                        }
+                    }
+                }
+
+                Label { html_for: "author_name_{id}", "Name" }
+                Input {
+                    id: "author_name_{id}",
+                    value: "{name}",
+                    oninput: move |e: FormEvent| name.set(e.value()),
+                }
+
+                Label { html_for: "author_image_{id}", "Picture URL, used without an avatar" }
+                Input {
+                    id: "author_image_{id}",
+                    value: "{image}",
+                    oninput: move |e: FormEvent| image.set(e.value()),
+                }
+
+                Label { html_for: "author_url_{id}", "Link" }
+                Input {
+                    id: "author_url_{id}",
+                    value: "{url}",
+                    oninput: move |e: FormEvent| url.set(e.value()),
+                }
+
+                div { class: "flex flex-row justify-end",
+                    Button {
+                        onclick: move |_| async move {
+                            if let Err(e) = save_author_server(Some(id), name(), image(), url()).await {
+                                error_toast("Saving author failed", e);
+                                return;
+                            }
+
+                            info_toast("Saved author");
+                            on_change(());
+                        },
+                        "Save"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[server]
+pub(crate) async fn authors_server() -> Result<Vec<types::AuthorProfile>> {
+    use dioxus::CapturedError;
+
+    db::queries::authors::list_authors(crate::db::db())
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+pub(crate) async fn default_author_server() -> Result<Option<types::Author>> {
+    use dioxus::CapturedError;
+
+    db::queries::authors::default_author(crate::db::db())
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn save_author_server(
+    id: Option<i64>,
+    name: String,
+    image: String,
+    url: String,
+) -> Result<i64> {
+    use dioxus::CapturedError;
+
+    db::queries::authors::save_author(crate::db::db(), id, &name, &image, &url)
+        .await
+        .map_err(CapturedError::from_display)
+}
+
+#[server]
+async fn delete_author_server(id: i64) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::authors::delete_author(crate::db::db(), id)
+        .await
+        .map_err(CapturedError::from_display)
+}
+
+#[server]
+async fn set_default_author_server(id: i64) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::authors::set_default_author(crate::db::db(), id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn remove_avatar_server(id: i64) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::authors::remove_avatar(crate::db::db(), id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+#[middleware(dioxus::fullstack::axum_core::extract::DefaultBodyLimit::max(1024 * 1024 * 64))]
+async fn upload_avatar(mut form: MultipartFormData) -> Result<()> {
+    use dioxus::CapturedError;
+
+    let mut id = None;
+    let mut data = None;
+
+    while let Ok(Some(field)) = form.next_field().await {
+        match field.name() {
+            Some("id") => id = field.text().await?.parse::<i64>().ok(),
+            Some("data") => data = Some(field.bytes().await?),
+            _ => {}
+        }
+    }
+
+    let (Some(id), Some(data)) = (id, data) else {
+        return Err(CapturedError::from_display("Incomplete upload"));
+    };
+
+    if data.is_empty() {
+        return Err(CapturedError::from_display("No image was selected"));
+    }
+
+    db::queries::authors::set_avatar(crate::db::db(), id, data.to_vec())
+        .await
+        .map_err(CapturedError::from_display)
+}
+
+#[get("/authors/:author_id/avatar", headers: http::HeaderMap)]
+async fn author_avatar(author_id: i64) -> Result<dioxus_fullstack::response::Response> {
+    use dioxus::fullstack::response::IntoResponse;
+    use dioxus::CapturedError;
+    use http::{header, HeaderValue};
+
+    let avatar = db::queries::authors::get_avatar(crate::db::db(), author_id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+
+    let Some((data, etag)) = avatar else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    let etag = format!("\"{etag}\"");
+
+    let mut cache_headers = http::HeaderMap::new();
+    cache_headers.insert(header::ETAG, HeaderValue::from_str(&etag)?);
+    cache_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+    if db::etag::not_modified(&headers, &etag, None) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    Ok((
+        StatusCode::OK,
+        cache_headers,
+        [(header::CONTENT_TYPE, "image/png")],
+        data,
+    )
+        .into_response())
+}
@@ -0,0 +1,673 @@
+use dioxus::prelude::*;
+use dioxus_primitives::checkbox::CheckboxState;
+
+use crate::components::{
+    button::{Button, ButtonVariant},
+    card::*,
+    checkbox::*,
+    error_toast,
+    input::Input,
+    label::Label,
+    native_select, saved_toast,
+};
+
+/// Where the capabilities, phases, settings and values the editor offers are
+/// looked after, along with the ingredient densities unit conversion uses.
+#[component]
+pub fn Catalog() -> Element {
+    // bumped to reload the catalog after changing it
+    let mut generation = use_signal(|| 0u64);
+    let catalog = use_loader(move || {
+        generation();
+        catalog_server()
+    })?;
+    let densities = use_loader(move || {
+        generation();
+        densities_server()
+    })?;
+    let ingredients = use_loader(crate::views::ingredients_server)?;
+
+    let mut new_capability_id = use_signal(String::new);
+    let mut new_capability_name = use_signal(String::new);
+    let mut new_phase_id = use_signal(String::new);
+    let mut new_phase_name = use_signal(String::new);
+    let mut new_value_setting = use_signal(|| types::ReferenceSettingId::Speed);
+    let mut new_value_id = use_signal(String::new);
+    let mut new_value_name = use_signal(String::new);
+    let mut new_density_ingredient = use_signal(|| None::<String>);
+    let mut new_density = use_signal(String::new);
+
+    rsx! {
+        div { class: "flex flex-col gap-4",
+
+            Card { class: "w-full",
+                CardHeader {
+                    CardTitle { "Capabilities" }
+                    CardDescription { "What a step can have the mixer do" }
+                }
+                CardContent { class: "flex flex-col gap-4",
+                    for capability in catalog.read().capabilities.clone() {
+                        CapabilityRow {
+                            key: "{capability.id}",
+                            capability,
+                            on_change: move |_| generation += 1,
+                        }
+                    }
+
+                    div { class: "flex flex-row gap-4 items-center",
+                        Input {
+                            placeholder: "Id, like kitchenos:Kenwood:Cook",
+                            value: "{new_capability_id}",
+                            oninput: move |e: FormEvent| new_capability_id.set(e.value()),
+                        }
+                        Input {
+                            placeholder: "Name",
+                            value: "{new_capability_name}",
+                            oninput: move |e: FormEvent| new_capability_name.set(e.value()),
+                        }
+                        Button {
+                            onclick: move |_| async move {
+                                let capability = types::ReferenceCapability {
+                                    id: new_capability_id(),
+                                    name: new_capability_name(),
+                                };
+                                if let Err(e) = save_capability_server(capability).await {
+                                    error_toast("Adding capability failed", e);
+                                    return;
+                                }
+                                new_capability_id.set(String::new());
+                                new_capability_name.set(String::new());
+                                generation += 1;
+                            },
+                            "Add capability"
+                        }
+                    }
+                }
+            }
+
+            Card { class: "w-full",
+                CardHeader {
+                    CardTitle { "Phases" }
+                    CardDescription { "Parts of a program, and which ones each can come straight after" }
+                }
+                CardContent { class: "flex flex-col gap-4",
+                    for phase in catalog.read().phases.clone() {
+                        PhaseRow {
+                            key: "{phase.id}",
+                            phase,
+                            phases: catalog.read().phases.clone(),
+                            on_change: move |_| generation += 1,
+                        }
+                    }
+
+                    div { class: "flex flex-row gap-4 items-center",
+                        Input {
+                            placeholder: "Id, like cckg:ExecutionPhase",
+                            value: "{new_phase_id}",
+                            oninput: move |e: FormEvent| new_phase_id.set(e.value()),
+                        }
+                        Input {
+                            placeholder: "Name",
+                            value: "{new_phase_name}",
+                            oninput: move |e: FormEvent| new_phase_name.set(e.value()),
+                        }
+                        Button {
+                            onclick: move |_| async move {
+                                let phase = types::CapabilityPhase {
+                                    can_follow_phases: vec![],
+                                    id: new_phase_id(),
+                                    name: new_phase_name(),
+                                };
+                                if let Err(e) = save_phase_server(phase).await {
+                                    error_toast("Adding phase failed", e);
+                                    return;
+                                }
+                                new_phase_id.set(String::new());
+                                new_phase_name.set(String::new());
+                                generation += 1;
+                            },
+                            "Add phase"
+                        }
+                    }
+                }
+            }
+
+            Card { class: "w-full",
+                CardHeader {
+                    CardTitle { "Settings" }
+                    CardDescription { "What the settings are called in the editor" }
+                }
+                CardContent { class: "flex flex-col gap-4",
+                    for setting in catalog.read().settings.clone() {
+                        SettingRow {
+                            key: "{setting.id}",
+                            setting,
+                            on_change: move |_| generation += 1,
+                        }
+                    }
+                }
+            }
+
+            Card { class: "w-full",
+                CardHeader {
+                    CardTitle { "Values" }
+                    CardDescription {
+                        "The choices settings like speed can be set to, in the order they go, like slowest to fastest"
+                    }
+                }
+                CardContent { class: "flex flex-col gap-4",
+                    for value in catalog.read().values.clone() {
+                        ValueRow {
+                            key: "{value.setting}-{value.value.id}",
+                            value,
+                            setting_name: catalog.read().setting(value.setting).name,
+                            on_change: move |_| generation += 1,
+                        }
+                    }
+
+                    div { class: "flex flex-row gap-4 items-center",
+                        native_select::NativeSelect::<types::ReferenceSettingId> {
+                            value: Some(Some(new_value_setting())),
+                            on_value_change: move |v: Option<types::ReferenceSettingId>| {
+                                if let Some(v) = v {
+                                    new_value_setting.set(v);
+                                }
+                            },
+
+                            for setting in catalog.read().settings.clone() {
+                                native_select::NativeSelectOption::<types::ReferenceSettingId> {
+                                    value: setting.id,
+                                    "{setting.name}"
+                                }
+                            }
+                        }
+                        Input {
+                            placeholder: "Id, like kitchenos:Kenwood:Speed1",
+                            value: "{new_value_id}",
+                            oninput: move |e: FormEvent| new_value_id.set(e.value()),
+                        }
+                        Input {
+                            placeholder: "Name",
+                            value: "{new_value_name}",
+                            oninput: move |e: FormEvent| new_value_name.set(e.value()),
+                        }
+                        Button {
+                            onclick: move |_| async move {
+                                let value = types::catalog::NominalValue {
+                                    setting: new_value_setting(),
+                                    value: types::ReferenceValue {
+                                        id: new_value_id(),
+                                        name: new_value_name(),
+                                    },
+                                    // new values go last until they're moved
+                                    position: None,
+                                };
+                                if let Err(e) = save_value_server(value).await {
+                                    error_toast("Adding value failed", e);
+                                    return;
+                                }
+                                new_value_id.set(String::new());
+                                new_value_name.set(String::new());
+                                generation += 1;
+                            },
+                            "Add value"
+                        }
+                    }
+                }
+            }
+
+            Card { class: "w-full",
+                CardHeader {
+                    CardTitle { "Densities" }
+                    CardDescription {
+                        "How many grams a millilitre of an ingredient weighs, so its amounts can go between weights and volumes"
+                    }
+                }
+                CardContent { class: "flex flex-col gap-4",
+                    for density in densities.cloned() {
+                        DensityRow {
+                            key: "{density.ingredient.id}",
+                            density,
+                            on_change: move |_| generation += 1,
+                        }
+                    }
+
+                    div { class: "flex flex-row gap-4 items-center",
+                        native_select::NativeSelect::<String> {
+                            value: Some(new_density_ingredient()),
+                            on_value_change: move |v: Option<String>| new_density_ingredient.set(v),
+
+                            for ingredient in ingredients.cloned() {
+                                native_select::NativeSelectOption::<String> {
+                                    value: ingredient.id.clone(),
+                                    "{ingredient.name}"
+                                }
+                            }
+                        }
+                        Input {
+                            r#type: "number",
+                            min: 0,
+                            step: "any",
+                            placeholder: "Grams per millilitre",
+                            value: "{new_density}",
+                            oninput: move |e: FormEvent| new_density.set(e.value()),
+                        }
+                        Button {
+                            onclick: move |_| async move {
+                                let Some(id) = new_density_ingredient() else {
+                                    error_toast("Adding density failed", "Pick an ingredient");
+                                    return;
+                                };
+                                let Ok(density) = new_density.read().trim().parse::<f64>() else {
+                                    error_toast("Adding density failed", "Density has to be a number");
+                                    return;
+                                };
+                                if let Err(e) = set_density_server(id, Some(density)).await {
+                                    error_toast("Adding density failed", e);
+                                    return;
+                                }
+                                new_density_ingredient.set(None);
+                                new_density.set(String::new());
+                                generation += 1;
+                            },
+                            "Add density"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CapabilityRow(capability: types::ReferenceCapability, on_change: EventHandler<()>) -> Element {
+    let id = capability.id.clone();
+    let mut name = use_signal(|| capability.name.clone());
+
+    rsx! {
+        div { class: "flex flex-row gap-4 items-center",
+            span { class: "w-1/3 text-sm", "{capability.id}" }
+            Input {
+                value: "{name}",
+                oninput: move |e: FormEvent| name.set(e.value()),
+            }
+            Button {
+                variant: ButtonVariant::Secondary,
+                onclick: {
+                    let id = id.clone();
+                    move |_| {
+                        let id = id.clone();
+                        async move {
+                            let capability = types::ReferenceCapability {
+                                id,
+                                name: name(),
+                            };
+                            if let Err(e) = save_capability_server(capability).await {
+                                error_toast("Saving capability failed", e);
+                                return;
+                            }
+                            saved_toast();
+                            on_change(());
+                        }
+                    }
+                },
+                "Save"
+            }
+            Button {
+                variant: ButtonVariant::Destructive,
+                onclick: move |_| {
+                    let id = id.clone();
+                    async move {
+                        if let Err(e) = delete_capability_server(id).await {
+                            error_toast("Deleting capability failed", e);
+                        }
+                        on_change(());
+                    }
+                },
+                "Delete"
+            }
+        }
+    }
+}
+
+#[component]
+fn PhaseRow(
+    phase: types::CapabilityPhase,
+    /// Every phase, to pick which ones this can follow
+    phases: Vec<types::CapabilityPhase>,
+    on_change: EventHandler<()>,
+) -> Element {
+    let id = phase.id.clone();
+    let mut name = use_signal(|| phase.name.clone());
+    let mut can_follow = use_signal(|| phase.can_follow_phases.clone());
+
+    rsx! {
+        div { class: "flex flex-col gap-2",
+            div { class: "flex flex-row gap-4 items-center",
+                span { class: "w-1/3 text-sm", "{phase.id}" }
+                Input {
+                    value: "{name}",
+                    oninput: move |e: FormEvent| name.set(e.value()),
+                }
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    onclick: {
+                        let id = id.clone();
+                        move |_| {
+                            let id = id.clone();
+                            async move {
+                                let phase = types::CapabilityPhase {
+                                    can_follow_phases: can_follow(),
+                                    id,
+                                    name: name(),
+                                };
+                                if let Err(e) = save_phase_server(phase).await {
+                                    error_toast("Saving phase failed", e);
+                                    return;
+                                }
+                                saved_toast();
+                                on_change(());
+                            }
+                        }
+                    },
+                    "Save"
+                }
+                Button {
+                    variant: ButtonVariant::Destructive,
+                    onclick: move |_| {
+                        let id = id.clone();
+                        async move {
+                            if let Err(e) = delete_phase_server(id).await {
+                                error_toast("Deleting phase failed", e);
+                            }
+                            on_change(());
+                        }
+                    },
+                    "Delete"
+                }
+            }
+
+            div { class: "flex flex-row flex-wrap gap-4 items-center",
+                span { class: "text-sm", "Can follow" }
+                for other in phases {
+                    div { class: "flex flex-row gap-2 items-center",
+                        Checkbox {
+                            name: "follows_{phase.id}_{other.id}",
+                            checked: Some(
+                                if can_follow.read().contains(&other.id) {
+                                    CheckboxState::Checked
+                                } else {
+                                    CheckboxState::Unchecked
+                                },
+                            ),
+                            on_checked_change: {
+                                let other = other.id.clone();
+                                move |v: CheckboxState| {
+                                    let checked: bool = v.into();
+                                    let mut can_follow = can_follow.write();
+                                    can_follow.retain(|p| *p != other);
+                                    if checked {
+                                        can_follow.push(other.clone());
+                                    }
+                                }
+                            },
+                        }
+                        Label { html_for: "follows_{phase.id}_{other.id}", "{other.name}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SettingRow(setting: types::ReferenceSetting, on_change: EventHandler<()>) -> Element {
+    let id = setting.id;
+    let mut name = use_signal(|| setting.name.clone());
+
+    rsx! {
+        div { class: "flex flex-row gap-4 items-center",
+            span { class: "w-1/3 text-sm", "{id}" }
+            Input {
+                value: "{name}",
+                oninput: move |e: FormEvent| name.set(e.value()),
+            }
+            Button {
+                variant: ButtonVariant::Secondary,
+                onclick: move |_| async move {
+                    if let Err(e) = rename_setting_server(id, name()).await {
+                        error_toast("Renaming setting failed", e);
+                        return;
+                    }
+                    saved_toast();
+                    on_change(());
+                },
+                "Save"
+            }
+        }
+    }
+}
+
+#[component]
+fn ValueRow(
+    value: types::catalog::NominalValue,
+    setting_name: String,
+    on_change: EventHandler<()>,
+) -> Element {
+    let setting = value.setting;
+    let id = value.value.id.clone();
+    let mut name = use_signal(|| value.value.name.clone());
+    let mut position = use_signal(|| value.position.map(|p| p.to_string()).unwrap_or_default());
+
+    rsx! {
+        div { class: "flex flex-row gap-4 items-center",
+            span { class: "w-1/6 text-sm", "{setting_name}" }
+            span { class: "w-1/3 text-sm", "{id}" }
+            Input {
+                value: "{name}",
+                oninput: move |e: FormEvent| name.set(e.value()),
+            }
+            Input {
+                class: "w-20",
+                r#type: "number",
+                placeholder: "Order",
+                value: "{position}",
+                oninput: move |e: FormEvent| position.set(e.value()),
+            }
+            Button {
+                variant: ButtonVariant::Secondary,
+                onclick: {
+                    let id = id.clone();
+                    move |_| {
+                        let position = position();
+                        let position = match position.trim() {
+                            "" => Ok(None),
+                            p => p.parse::<i64>().map(Some),
+                        };
+                        let value = position.map(|position| types::catalog::NominalValue {
+                            setting,
+                            value: types::ReferenceValue {
+                                id: id.clone(),
+                                name: name(),
+                            },
+                            position,
+                        });
+                        async move {
+                            let value = match value {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    error_toast("Order has to be a whole number", e);
+                                    return;
+                                }
+                            };
+                            if let Err(e) = save_value_server(value).await {
+                                error_toast("Saving value failed", e);
+                                return;
+                            }
+                            saved_toast();
+                            on_change(());
+                        }
+                    }
+                },
+                "Save"
+            }
+            Button {
+                variant: ButtonVariant::Destructive,
+                onclick: move |_| {
+                    let id = id.clone();
+                    async move {
+                        if let Err(e) = delete_value_server(setting, id).await {
+                            error_toast("Deleting value failed", e);
+                        }
+                        on_change(());
+                    }
+                },
+                "Delete"
+            }
+        }
+    }
+}
+
+#[component]
+fn DensityRow(density: types::convert::IngredientDensity, on_change: EventHandler<()>) -> Element {
+    let id = density.ingredient.id.clone();
+    let mut grams_per_ml = use_signal(|| density.grams_per_ml.to_string());
+
+    rsx! {
+        div { class: "flex flex-row gap-4 items-center",
+            span { class: "w-1/3 text-sm", "{density.ingredient.name}" }
+            Input {
+                r#type: "number",
+                min: 0,
+                step: "any",
+                value: "{grams_per_ml}",
+                oninput: move |e: FormEvent| grams_per_ml.set(e.value()),
+            }
+            Button {
+                variant: ButtonVariant::Secondary,
+                onclick: {
+                    let id = id.clone();
+                    move |_| {
+                        let id = id.clone();
+                        async move {
+                            let Ok(density) = grams_per_ml.read().trim().parse::<f64>() else {
+                                error_toast("Saving density failed", "Density has to be a number");
+                                return;
+                            };
+                            if let Err(e) = set_density_server(id, Some(density)).await {
+                                error_toast("Saving density failed", e);
+                                return;
+                            }
+                            saved_toast();
+                            on_change(());
+                        }
+                    }
+                },
+                "Save"
+            }
+            Button {
+                variant: ButtonVariant::Destructive,
+                onclick: move |_| {
+                    let id = id.clone();
+                    async move {
+                        if let Err(e) = set_density_server(id, None).await {
+                            error_toast("Removing density failed", e);
+                        }
+                        on_change(());
+                    }
+                },
+                "Remove"
+            }
+        }
+    }
+}
+
+#[server]
+pub(crate) async fn catalog_server() -> Result<types::catalog::Catalog> {
+    use dioxus::CapturedError;
+
+    db::queries::catalog::get_catalog(crate::db::db())
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn save_capability_server(capability: types::ReferenceCapability) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::catalog::save_capability(crate::db::db(), capability)
+        .await
+        .map_err(CapturedError::from_display)
+}
+
+#[server]
+async fn delete_capability_server(id: String) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::catalog::delete_capability(crate::db::db(), &id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn save_phase_server(phase: types::CapabilityPhase) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::catalog::save_phase(crate::db::db(), phase)
+        .await
+        .map_err(CapturedError::from_display)
+}
+
+#[server]
+async fn delete_phase_server(id: String) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::catalog::delete_phase(crate::db::db(), &id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn rename_setting_server(id: types::ReferenceSettingId, name: String) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::catalog::rename_setting(crate::db::db(), id, &name)
+        .await
+        .map_err(CapturedError::from_display)
+}
+
+#[server]
+async fn save_value_server(value: types::catalog::NominalValue) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::catalog::save_value(crate::db::db(), value)
+        .await
+        .map_err(CapturedError::from_display)
+}
+
+#[server]
+async fn delete_value_server(setting: types::ReferenceSettingId, id: String) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::catalog::delete_value(crate::db::db(), setting, &id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn densities_server() -> Result<Vec<types::convert::IngredientDensity>> {
+    use dioxus::CapturedError;
+
+    db::queries::ingredients::list_densities(crate::db::db())
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn set_density_server(id: String, grams_per_ml: Option<f64>) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::ingredients::set_density(crate::db::db(), &id, grams_per_ml)
+        .await
+        .map_err(CapturedError::from_display)
+}
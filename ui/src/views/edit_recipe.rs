@@ -1,20 +1,20 @@
 use core::str::FromStr;
 use rand::distr::SampleString;
-use std::{string::ToString, time::Duration};
+use std::{collections::HashMap, string::ToString, time::Duration};
 
-use dioxus::{fullstack::MultipartFormData, prelude::*};
+use dioxus::prelude::*;
 use dioxus_primitives::{
     checkbox::CheckboxState,
     toast::{consume_toast, ToastOptions},
 };
 use itertools::Itertools;
-use types::KnownOptions;
-use types::{traits::*, UID};
+use types::{appliance::ApplianceProfile, convert::MeasurementSystem, traits::*, UID};
 
 use crate::components::{
-    button::{Button, ButtonVariant},
+    button::{Button, ButtonVariant, LinkButton},
     card::*,
     checkbox::*,
+    error_toast, info_toast,
     input::Input,
     label::Label,
     native_select,
@@ -22,6 +22,7 @@ use crate::components::{
     select,
     tabs::*,
     textarea::Textarea,
+    Gallery, MediaAttachments, RevisionHistory, Timeline,
 };
 
 #[component]
@@ -72,6 +73,7 @@ fn PreparationSelector(
 fn Quantity(
     quantity: Store<types::Quantity>,
     allowed_units: Memo<Vec<types::IngredientAllowedUnit>>,
+    locale: Memo<String>,
 ) -> Element {
     trace!("Render quantity");
     let unit_search_input = use_signal(|| String::new());
@@ -88,26 +90,40 @@ fn Quantity(
             .collect_vec()
     });
 
+    // text that couldn't be read, like `a pinch`, is kept as it was typed
     let update_text = use_callback(move |()| {
-        let text = if let Some(amount) = *quantity.amount().read() {
-            let abbr = quantity.reference_unit().abbreviation().cloned();
-            let abbr = if abbr.trim().is_empty() {
-                english::Noun::count(quantity.reference_unit().name().cloned(), amount as u32)
-            } else {
-                abbr
-            };
-            format!("{amount} {abbr}",)
-        } else {
-            quantity.reference_unit().abbreviation().cloned()
-        };
-
-        quantity.text().set(text);
+        if quantity.amount().peek().is_some() {
+            quantity.write().sync_text(&locale.read());
+        }
     });
 
     rsx! {
-        Label { html_for: "quantity", "Quantity ({quantity.text()})" }
+        Label { html_for: "quantity", "Quantity" }
         div { class: "flex flex-col sm:flex-row sm:items-center gap-4",
 
+            // typed text fills in the amount and unit, and is written out
+            // afresh from them once it's left
+            Input {
+                value: quantity.text(),
+                placeholder: "Like 200 g or 1½ cups",
+                oninput: move |e: FormEvent| {
+                    let units = allowed_units
+                        .read()
+                        .iter()
+                        .cloned()
+                        .map(types::IngredientAllowedUnit::as_reference_unit)
+                        .collect_vec();
+                    let parsed = types::quantity::parse(&e.value(), &units, &locale.read());
+
+                    quantity.amount().set(parsed.amount);
+                    if let Some(unit) = parsed.unit {
+                        quantity.reference_unit().set(unit);
+                    }
+                    quantity.text().set(e.value());
+                },
+                onblur: move |_| update_text.call(()),
+            }
+
             SearchingSelect::<types::IngredientAllowedUnit> {
                 name: "quantity",
                 placeholder: "{quantity.reference_unit().name()}",
@@ -159,9 +175,11 @@ fn Quantity(
 
 #[component]
 fn Ingredient(
+    recipe_id: String,
     ingredient: Store<types::RecipeIngredient>,
     ingredients_matcher: Memo<FuzzyFinder<types::Ingredient>>,
     preparations_matcher: Memo<FuzzyFinder<types::ReferencePreparation>>,
+    locale: Memo<String>,
     delete: EventHandler<MouseEvent>,
 ) -> Element {
     trace!("Render ingredient");
@@ -236,7 +254,11 @@ fn Ingredient(
 
             CardContent { class: "flex flex-col gap-4 justify-center",
 
-                Quantity { quantity: ingredient.quantity(), allowed_units }
+                Quantity {
+                    quantity: ingredient.quantity(),
+                    allowed_units,
+                    locale,
+                }
 
                 Label { html_for: "preparations", "Preparations" }
                 div { class: "flex flex-col justify-start gap-4",
@@ -271,6 +293,9 @@ fn Ingredient(
                         "Add preparation"
                     }
                 }
+
+                Label { html_for: "media", "Images" }
+                MediaAttachments { recipe_id, kind: "ingredient", media: ingredient.media() }
             }
         }
     }
@@ -307,8 +332,38 @@ fn KeepWarmSettingSelector(setting: WriteSignal<types::CapabilitySetting>) -> El
     }
 }
 
+/// The range official recipes use a numeric setting over, if known.
+fn observed_range(schema: &Option<types::schema::SettingSchema>) -> Option<(f64, f64)> {
+    schema.as_ref().and_then(|s| s.min.zip(s.max))
+}
+
 #[component]
-fn TemperatureSettingSelector(setting: WriteSignal<types::CapabilitySetting>) -> Element {
+fn TemperatureSettingSelector(
+    setting: WriteSignal<types::CapabilitySetting>,
+    schema: Memo<Option<types::schema::SettingSchema>>,
+    appliance: Memo<ApplianceProfile>,
+) -> Element {
+    let limits = use_memo(move || {
+        appliance
+            .read()
+            .setting(types::ReferenceSettingId::Temperature)
+            .cloned()
+    });
+    // what official recipes use, kept within what the appliance takes
+    let min = use_memo(move || {
+        let limit = limits().and_then(|l| l.min);
+        match (observed_range(&schema()).map(|(min, _)| min), limit) {
+            (Some(min), Some(limit)) => min.max(limit),
+            (min, limit) => min.or(limit).unwrap_or(0.0),
+        }
+    });
+    let max = use_memo(move || {
+        let limit = limits().and_then(|l| l.max);
+        match (observed_range(&schema()).map(|(_, max)| max), limit) {
+            (Some(max), Some(limit)) => Some(max.min(limit)),
+            (max, limit) => max.or(limit),
+        }
+    });
     let value = use_memo(move || match setting().value {
         types::SettingValue::Numeric { value, .. } => Some(value),
         types::SettingValue::Nominal { .. } => Some(0.0),
@@ -324,8 +379,8 @@ fn TemperatureSettingSelector(setting: WriteSignal<types::CapabilitySetting>) ->
                 Input {
                     id: "recipe_prep_time",
                     r#type: "number",
-                    min: 0,
-                    max: 200,
+                    min: min(),
+                    max: max(),
                     value: value().map(|x| x.to_string()).unwrap_or_else(|| "Enter temperature".to_owned()),
                     oninput: move |e: FormEvent| {
                         if let Ok(t) = e.value().parse::<f64>() {
@@ -345,13 +400,23 @@ fn TemperatureSettingSelector(setting: WriteSignal<types::CapabilitySetting>) ->
                     },
                 }
                 span { "°C" }
+
+                if let Some((min, max)) = observed_range(&schema()) {
+                    span { class: "text-sm text-(--secondary-color-5)",
+                        "Official recipes use {min}–{max} °C"
+                    }
+                }
             }
         }
     }
 }
 
 #[component]
-fn SpeedSettingSelector(setting: WriteSignal<types::CapabilitySetting>) -> Element {
+fn SpeedSettingSelector(
+    setting: WriteSignal<types::CapabilitySetting>,
+    schema: Memo<Option<types::schema::SettingSchema>>,
+    catalog: Memo<types::catalog::Catalog>,
+) -> Element {
     let value = use_memo(move || match setting().value {
         types::SettingValue::Nominal {
             reference_value, ..
@@ -359,10 +424,16 @@ fn SpeedSettingSelector(setting: WriteSignal<types::CapabilitySetting>) -> Eleme
         _ => None,
     });
 
+    // only the speeds official recipes use with this capability, when known
+    let speeds = use_memo(move || match schema() {
+        Some(schema) => schema.nominal_values(&catalog.read()),
+        None => catalog.read().values_of(types::ReferenceSettingId::Speed),
+    });
+
     rsx! {
         div { class: "flex w-full flex-row gap-4",
 
-            Label { html_for: "speed", "Speed" }
+            Label { html_for: "speed", "{catalog.read().setting(types::ReferenceSettingId::Speed).name}" }
             native_select::NativeSelect::<types::ReferenceValue> {
                 value: Some(value()),
                 on_value_change: move |v: Option<types::ReferenceValue>| {
@@ -374,7 +445,7 @@ fn SpeedSettingSelector(setting: WriteSignal<types::CapabilitySetting>) -> Eleme
                     }
                 },
 
-                for (_idx , value) in types::ReferenceValue::stir_settings().into_iter().enumerate() {
+                for (_idx , value) in speeds().into_iter().enumerate() {
                     native_select::NativeSelectOption::<types::ReferenceValue> { value: value.clone(), "{value.name}" }
                 }
             }
@@ -383,7 +454,10 @@ fn SpeedSettingSelector(setting: WriteSignal<types::CapabilitySetting>) -> Eleme
 }
 
 #[component]
-fn TimeSettingSelector(setting: WriteSignal<types::CapabilitySetting>) -> Element {
+fn TimeSettingSelector(
+    setting: WriteSignal<types::CapabilitySetting>,
+    schema: Memo<Option<types::schema::SettingSchema>>,
+) -> Element {
     let value = use_memo(move || match setting().value {
         types::SettingValue::Numeric { value, .. } => {
             jiff::Span::try_from(jiff::SignedDuration::from_secs_f64(value))
@@ -451,47 +525,87 @@ fn TimeSettingSelector(setting: WriteSignal<types::CapabilitySetting>) -> Elemen
                 }
 
                 span { "s" }
+
+                if let Some((min, max)) = observed_range(&schema()) {
+                    span { class: "text-sm text-(--secondary-color-5)",
+                        "Official recipes run for {jiff::SignedDuration::from_secs_f64(min):#}–{jiff::SignedDuration::from_secs_f64(max):#}"
+                    }
+                }
             }
         }
     }
 }
 
+/// The settings the capability can be given: the ones official recipes use
+/// with it once that's been learned, or all of them before, as long as the
+/// appliance has them.
+fn allowed_settings(
+    schema: &Option<types::schema::CapabilitySchema>,
+    appliance: &ApplianceProfile,
+) -> Vec<types::ReferenceSettingId> {
+    let settings = match schema {
+        Some(schema) if !schema.settings.is_empty() => {
+            schema.settings.iter().map(|s| s.id).collect()
+        }
+        _ => vec![
+            types::ReferenceSettingId::KeepWarm,
+            types::ReferenceSettingId::Temperature,
+            types::ReferenceSettingId::Speed,
+            types::ReferenceSettingId::Time,
+        ],
+    };
+
+    settings
+        .into_iter()
+        .filter(|id| appliance.setting(*id).is_some())
+        .collect()
+}
+
 #[component]
-fn SettingsSelector(setting: WriteSignal<types::CapabilitySetting>) -> Element {
+fn SettingsSelector(
+    setting: WriteSignal<types::CapabilitySetting>,
+    schema: Memo<Option<types::schema::CapabilitySchema>>,
+    catalog: Memo<types::catalog::Catalog>,
+    appliance: Memo<ApplianceProfile>,
+) -> Element {
     trace!("Render step selector");
     let type_ = setting().reference_setting.id;
     let type_str = use_memo(move || Some(setting().reference_setting.id.to_string()));
+    let setting_schema =
+        use_memo(move || schema().and_then(|s| s.setting(setting().reference_setting.id).cloned()));
+    let tabs = use_memo(move || {
+        let mut tabs = allowed_settings(&schema(), &appliance.read());
+        let current = setting().reference_setting.id;
+        if !tabs.contains(&current) {
+            tabs.push(current);
+        }
+        tabs
+    });
 
     rsx! {
         div { class: "flex flex-col sm:w-full sm:flex-row sm:items-center justify-start gap-4",
             Tabs {
                 value: type_str,
                 on_value_change: move |v: String| {
-                    setting.write().reference_setting = types::ReferenceSettingId::from_str(&v)
-                        .unwrap()
-                        .reference_setting();
+                    let id = types::ReferenceSettingId::from_str(&v).unwrap();
+                    let schema = schema();
+                    let uid = setting().uid;
+
+                    // start the new kind of setting from what official
+                    // recipes use, rather than keeping a value that won't fit
+                    setting.set(types::CapabilitySetting {
+                        uid,
+                        ..types::CapabilitySetting::starting_value(
+                            id,
+                            schema.as_ref().and_then(|s| s.setting(id)),
+                            &catalog.read(),
+                        )
+                    });
                 },
 
                 TabList {
-                    TabTrigger {
-                        value: types::ReferenceSettingId::KeepWarm.to_string(),
-                        index: 0usize,
-                        "Keep warm"
-                    }
-                    TabTrigger {
-                        value: types::ReferenceSettingId::Temperature.to_string(),
-                        index: 1usize,
-                        "Temperature"
-                    }
-                    TabTrigger {
-                        value: types::ReferenceSettingId::Speed.to_string(),
-                        index: 2usize,
-                        "Speed"
-                    }
-                    TabTrigger {
-                        value: types::ReferenceSettingId::Time.to_string(),
-                        index: 3usize,
-                        "Time"
+                    for (index , id) in tabs().into_iter().enumerate() {
+                        TabTrigger { value: id.to_string(), index, "{catalog.read().setting(id).name}" }
                     }
                 }
             }
@@ -501,13 +615,13 @@ fn SettingsSelector(setting: WriteSignal<types::CapabilitySetting>) -> Element {
                     KeepWarmSettingSelector { setting }
                 },
                 types::ReferenceSettingId::Temperature => rsx! {
-                    TemperatureSettingSelector { setting }
+                    TemperatureSettingSelector { setting, schema: setting_schema, appliance }
                 },
                 types::ReferenceSettingId::Speed => rsx! {
-                    SpeedSettingSelector { setting }
+                    SpeedSettingSelector { setting, schema: setting_schema, catalog }
                 },
                 types::ReferenceSettingId::Time => rsx! {
-                    TimeSettingSelector { setting }
+                    TimeSettingSelector { setting, schema: setting_schema }
                 },
             }
         }
@@ -515,8 +629,51 @@ fn SettingsSelector(setting: WriteSignal<types::CapabilitySetting>) -> Element {
 }
 
 #[component]
-fn StepCapability(capability: Store<types::StepCapability>) -> Element {
+fn StepCapability(
+    capability: Store<types::StepCapability>,
+    schemas: Memo<HashMap<String, types::schema::CapabilitySchema>>,
+    catalog: Memo<types::catalog::Catalog>,
+    appliance: Memo<ApplianceProfile>,
+) -> Element {
     trace!("Render step capability");
+    let schema = use_memo(move || {
+        schemas
+            .read()
+            .get(&capability.reference_capability().read().id)
+            .cloned()
+    });
+    // phases official recipes use the capability in, once that's been learned
+    let phases = use_memo(move || {
+        let current = capability.phase().cloned();
+        let schema = schema();
+
+        catalog
+            .read()
+            .phases
+            .iter()
+            .filter(|p| {
+                p.id == current.id
+                    || schema
+                        .as_ref()
+                        .is_none_or(|s| s.phases.is_empty() || s.phases.contains(&p.id))
+            })
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+    // only what the appliance can do, plus whatever the step already uses
+    let capabilities = use_memo(move || {
+        let current = capability.reference_capability().cloned();
+        let appliance = appliance.read();
+
+        catalog
+            .read()
+            .capabilities
+            .iter()
+            .filter(|c| c.id == current.id || appliance.has_capability(&c.id))
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
     rsx! {
         Card {
             CardContent { class: "flex flex-col gap-4 justify-center",
@@ -531,7 +688,7 @@ fn StepCapability(capability: Store<types::StepCapability>) -> Element {
                             }
                         },
 
-                        for (_idx , phase) in types::CapabilityPhase::known_options().into_iter().enumerate() {
+                        for (_idx , phase) in phases().into_iter().enumerate() {
                             native_select::NativeSelectOption::<types::CapabilityPhase> { value: phase.clone(), "{phase.name}" }
                         }
                     }
@@ -545,7 +702,7 @@ fn StepCapability(capability: Store<types::StepCapability>) -> Element {
                             }
                         },
 
-                        for (_idx , capability) in types::ReferenceCapability::known_options().into_iter().enumerate() {
+                        for (_idx , capability) in capabilities().into_iter().enumerate() {
                             native_select::NativeSelectOption::<types::ReferenceCapability> { value: capability.clone(), "{capability.name}" }
                         }
 
@@ -570,7 +727,7 @@ fn StepCapability(capability: Store<types::StepCapability>) -> Element {
                                 "X"
                             }
 
-                            SettingsSelector { setting }
+                            SettingsSelector { setting, schema, catalog, appliance }
                         }
                     }
 
@@ -578,7 +735,30 @@ fn StepCapability(capability: Store<types::StepCapability>) -> Element {
                         class: "sm:max-w-1/2",
                         variant: ButtonVariant::Secondary,
                         onclick: move |_| {
-                            capability.settings().push(types::CapabilitySetting::keep_warm_default());
+                            let schema = schema();
+                            let used = capability
+                                .settings()
+                                .read()
+                                .iter()
+                                .map(|s| s.reference_setting.id)
+                                .collect::<Vec<_>>();
+                            let allowed = allowed_settings(&schema, &appliance.read());
+                            let id = allowed
+                                .iter()
+                                .find(|id| !used.contains(id))
+                                .or(allowed.first())
+                                .copied()
+                                .unwrap_or(types::ReferenceSettingId::KeepWarm);
+
+                            capability
+                                .settings()
+                                .push(
+                                    types::CapabilitySetting::starting_value(
+                                        id,
+                                        schema.as_ref().and_then(|s| s.setting(id)),
+                                        &catalog.read(),
+                                    ),
+                                );
                         },
 
                         "Add preparation"
@@ -593,6 +773,7 @@ fn StepCapability(capability: Store<types::StepCapability>) -> Element {
 fn StepIngredient(
     ingredients: Store<Vec<types::RecipeIngredient>>,
     ingredient: Store<types::StepIngredient>,
+    locale: Memo<String>,
 ) -> Element {
     trace!("Render step ingredients");
     // signal that tracks the ingredient, used to move the ingredient_idx when
@@ -732,6 +913,7 @@ fn StepIngredient(
                         Quantity {
                             quantity: ingredient.quantity(),
                             allowed_units,
+                            locale,
                         }
                     }
                 }
@@ -746,11 +928,45 @@ fn StepIngredient(
     }
 }
 
+/// How full the bowl is by a step, as a bar that turns red when it
+/// overflows.
+#[component]
+fn BowlFill(load: types::capacity::StepLoad, appliance: ApplianceProfile) -> Element {
+    let Some(fill) = load.fill(&appliance) else {
+        return rsx! {};
+    };
+    let percent = (fill * 100.0).round();
+    let contents = format!("{:.0}", load.contents_ml);
+    let unmeasured = load.unmeasured.join(", ");
+
+    rsx! {
+        div { class: "flex flex-col gap-1",
+            span { class: "text-sm", "Bowl about {percent}% full, {contents} ml" }
+            div { class: "w-full h-2 rounded bg-(--primary-color-5)",
+                div {
+                    class: if fill > 1.0 { "h-2 rounded bg-red-500" } else { "h-2 rounded bg-(--secondary-color-4)" },
+                    style: "width: {percent.min(100.0)}%",
+                }
+            }
+            if !unmeasured.is_empty() {
+                span { class: "text-sm text-(--secondary-color-5)", "Not counting {unmeasured}" }
+            }
+        }
+    }
+}
+
 #[component]
 fn Step(
+    recipe_id: String,
     idx: usize,
     step: Store<types::RecipeStep>,
     ingredients: Store<Vec<types::RecipeIngredient>>,
+    schemas: Memo<HashMap<String, types::schema::CapabilitySchema>>,
+    catalog: Memo<types::catalog::Catalog>,
+    appliance: Memo<ApplianceProfile>,
+    locale: Memo<String>,
+    /// What's in the bowl once the step's ingredients are in
+    load: Option<types::capacity::StepLoad>,
     delete: EventHandler<MouseEvent>,
 ) -> Element {
     trace!("Render step");
@@ -767,9 +983,13 @@ fn Step(
 
             CardContent { class: "flex flex-col gap-4 justify-center",
 
+                if let Some(load) = load {
+                    BowlFill { load, appliance: appliance() }
+                }
+
                 Label { html_for: "capability", "Capability" }
                 if let Some(capability) = step.capability().transpose() {
-                    StepCapability { capability }
+                    StepCapability { capability, schemas, catalog, appliance }
 
                     Button {
                         class: "sm:max-w-1/2",
@@ -809,7 +1029,7 @@ fn Step(
                                 fallback: |_| rsx! {
                                     div { class: "flex items-center justify-center w-full h-full", "Loading..." }
                                 },
-                                StepIngredient { ingredient, ingredients }
+                                StepIngredient { ingredient, ingredients, locale }
                             }
 
                             Button {
@@ -851,6 +1071,9 @@ fn Step(
                     value: step.text(),
                     oninput: move |e: FormEvent| step.text().set(e.value()),
                 }
+
+                Label { html_for: "media", "Images" }
+                MediaAttachments { recipe_id, kind: "step", media: step.media() }
             }
         }
     }
@@ -907,8 +1130,64 @@ impl<T> FuzzyFinder<T> {
     }
 }
 
+/// Toggles which of the known tags a recipe has, grouped by category.
 #[component]
-pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
+fn TagPicker(tags: Store<Vec<types::ReferenceTag>>) -> Element {
+    let known = use_loader(tags_server)?;
+    let by_category = use_memo(move || {
+        let selected = tags.read();
+
+        known
+            .read()
+            .iter()
+            .chunk_by(|t| t.category.clone())
+            .into_iter()
+            .map(|(category, options)| {
+                let options = options
+                    .map(|t| (t.clone(), selected.iter().any(|s| s.id == t.id)))
+                    .collect_vec();
+                (category, options)
+            })
+            .collect_vec()
+    });
+
+    rsx! {
+        for (category , options) in by_category() {
+            div { key: "{category}", class: "flex flex-col gap-2",
+                span { class: "text-sm", "{category}" }
+
+                div { class: "flex flex-row flex-wrap gap-2",
+                    for (tag , is_selected) in options {
+                        Button {
+                            key: "{tag.id}",
+                            variant: if is_selected { ButtonVariant::Primary } else { ButtonVariant::Outline },
+                            onclick: {
+                                let tag = tag.clone();
+                                move |_| {
+                                    let mut tags = tags.write();
+                                    match tags.iter().position(|t| t.id == tag.id) {
+                                        Some(idx) => {
+                                            tags.remove(idx);
+                                        }
+                                        None => tags.push(tag.clone()),
+                                    }
+                                }
+                            },
+                            "{tag.name}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn EditRecipeInner(
+    recipe: Store<types::Recipe>,
+    catalog: Memo<types::catalog::Catalog>,
+    appliances: Memo<Vec<ApplianceProfile>>,
+) -> Element {
     let ingredients = use_loader(ingredients_server)?;
     let ingredients_matcher =
         use_memo(move || FuzzyFinder::new(ingredients.iter().map(|i| (i.name.clone(), i.clone()))));
@@ -916,6 +1195,37 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
     let preparations_matcher = use_memo(move || {
         FuzzyFinder::new(preparations.iter().map(|i| (i.name.clone(), i.clone())))
     });
+    let authors = use_loader(crate::views::authors_server)?;
+    let capability_schemas = use_loader(capability_schemas_server)?;
+    let schemas = use_memo(move || {
+        capability_schemas
+            .iter()
+            .map(|s| (s.capability.id.clone(), s.clone()))
+            .collect::<HashMap<_, _>>()
+    });
+    let locale = use_memo(move || recipe.locale().cloned());
+    let appliance =
+        use_memo(move || recipe_appliance(&recipe.appliance().read(), &appliances.read()));
+    let ingredient_ids = use_memo(move || recipe.read().ingredient_ids());
+    let conversions = use_loader(move || conversions_server(ingredient_ids()))?;
+    let loads = use_memo(move || types::capacity::bowl_loads(&recipe.read(), &conversions.read()));
+    let reconciliation =
+        use_memo(move || types::reconcile::reconcile(&recipe.read(), &conversions.read()));
+    let issues = use_memo(move || {
+        types::validate::validate(
+            &recipe.read(),
+            types::validate::Rules {
+                catalog: &catalog.read(),
+                schemas: &capability_schemas.read(),
+                appliance: &appliance.read(),
+                conversions: &conversions.read(),
+            },
+        )
+    });
+
+    let mut scale_to = use_signal(String::new);
+    // steps to look over after the last scaling
+    let mut scale_warnings = use_signal(Vec::<types::scale::ScaleWarning>::new);
 
     trace!("Render editrecipe");
 
@@ -933,51 +1243,40 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
         recipe.total_time().set(prep_time + cook_time);
     });
 
+    let timing = use_memo(move || {
+        let estimates = recipe.timing().cloned().unwrap_or_default();
+        types::timing::timing(&recipe.steps().read(), estimates)
+    });
+    // keep the times in step with the steps, unless they're typed in by hand
+    use_effect(move || {
+        let mut synced = types::Recipe {
+            steps: recipe.steps().cloned(),
+            timing: recipe.timing().cloned(),
+            ..recipe.peek().clone()
+        };
+        synced.sync_times();
+
+        if *recipe.prep_time().peek() != synced.prep_time {
+            recipe.prep_time().set(synced.prep_time);
+        }
+        if *recipe.cook_time().peek() != synced.cook_time {
+            recipe.cook_time().set(synced.cook_time);
+        }
+        if *recipe.total_time().peek() != synced.total_time {
+            recipe.total_time().set(synced.total_time);
+        }
+    });
+
     rsx! {
 
         div { class: "flex justify-center",
 
             div { class: "flex flex-col w-full p-4 sm:w-3/4 gap-4 justify-center",
 
-                form {
-                    class: "flex flex-col gap-4",
-
-                    onsubmit: move |e: FormEvent| async move {
-                        e.prevent_default();
-
-                        let toast_api = consume_toast();
-
-                        if let Err(e) = upload_image(e.into()).await {
-                            toast_api
-                                .error(
-                                    "Uploaded image failed".to_owned(),
-                                    ToastOptions::new().duration(Duration::from_secs(3)),
-                                );
-                            return;
-                        }
-
-                        toast_api
-                            .info(
-                                "Uploaded image".to_owned(),
-                                ToastOptions::new().duration(Duration::from_secs(3)),
-                            );
-                    },
-
-                    input { name: "id", hidden: true, value: recipe.id() }
+                Gallery { recipe_id: recipe.id().cloned() }
 
-                    Label { html_for: "data", "Image" }
-                    div { class: "flex flex-row gap-4 justify-start items-center",
-                        Input {
-                            r#type: "file",
-                            name: "data",
-                            accept: ".png,.jpg,.jpeg,.webp",
-                        }
-                        Input {
-                            r#type: "submit",
-                            name: "submit",
-                            value: "Set image",
-                        }
-                    }
+                if !issues.read().is_empty() {
+                    ValidationIssues { issues: issues() }
                 }
 
                 Label { html_for: "recipe_name", "Name" }
@@ -994,6 +1293,115 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
                     oninput: move |e: FormEvent| recipe.description().set(e.value()),
                 }
 
+                Label { html_for: "recipe_author", "Author" }
+                native_select::NativeSelect::<i64> {
+                    id: "recipe_author",
+                    value: Some(recipe.author().read().id),
+                    on_value_change: move |id: Option<i64>| {
+                        let author = authors
+                            .read()
+                            .iter()
+                            .find(|a| Some(a.id) == id)
+                            .map(|a| a.to_author());
+                        if let Some(author) = author {
+                            recipe.author().set(author);
+                        }
+                    },
+
+                    for author in authors.cloned() {
+                        native_select::NativeSelectOption::<i64> { value: author.id, "{author.name}" }
+                    }
+                }
+
+                Label { html_for: "recipe_appliance", "Appliance" }
+                native_select::NativeSelect::<String> {
+                    id: "recipe_appliance",
+                    value: Some(Some(recipe.appliance().cloned())),
+                    on_value_change: move |id: Option<String>| {
+                        if let Some(id) = id {
+                            recipe.appliance().set(id);
+                        }
+                    },
+
+                    for appliance in appliances() {
+                        native_select::NativeSelectOption::<String> { value: appliance.id.clone(), "{appliance.name}" }
+                    }
+                }
+
+                Label { html_for: "recipe_locale", "Locale" }
+                Input {
+                    id: "recipe_locale",
+                    value: recipe.locale(),
+                    oninput: move |e: FormEvent| recipe.locale().set(e.value()),
+                }
+
+                div { class: "flex flex-row items-center gap-4",
+                    Checkbox {
+                        name: "recipe_auto_times",
+                        checked: Some(
+                            if recipe.timing().read().is_some() {
+                                CheckboxState::Checked
+                            } else {
+                                CheckboxState::Unchecked
+                            },
+                        ),
+                        on_checked_change: move |v: CheckboxState| {
+                            let checked: bool = v.into();
+                            recipe
+                                .timing()
+                                .set(checked.then(types::timing::TimingEstimates::default));
+                        },
+                    }
+                    Label { html_for: "recipe_auto_times", "Work out the times from the steps" }
+                }
+
+                if let Some(estimates) = recipe.timing().cloned() {
+                    div { class: "flex flex-row items-center gap-4",
+
+                        Label { html_for: "recipe_manual_step_secs", "Each step done by hand" }
+                        Input {
+                            id: "recipe_manual_step_secs",
+                            r#type: "number",
+                            min: 0,
+                            value: estimates.manual_step_secs.to_string(),
+                            oninput: move |e: FormEvent| {
+                                if let Ok(secs) = e.value().parse::<f64>() {
+                                    recipe
+                                        .timing()
+                                        .set(
+                                            Some(types::timing::TimingEstimates {
+                                                manual_step_secs: secs,
+                                                ..estimates
+                                            }),
+                                        );
+                                }
+                            },
+                        }
+                        span { "Seconds" }
+
+                        Label { html_for: "recipe_per_ingredient_secs", "Plus for each ingredient" }
+                        Input {
+                            id: "recipe_per_ingredient_secs",
+                            r#type: "number",
+                            min: 0,
+                            value: estimates.per_ingredient_secs.to_string(),
+                            oninput: move |e: FormEvent| {
+                                if let Ok(secs) = e.value().parse::<f64>() {
+                                    recipe
+                                        .timing()
+                                        .set(
+                                            Some(types::timing::TimingEstimates {
+                                                per_ingredient_secs: secs,
+                                                ..estimates
+                                            }),
+                                        );
+                                }
+                            },
+                        }
+                        span { "Seconds" }
+                    }
+                }
+
                 Label { html_for: "recipe_prep_time", "Prep time" }
                 div { class: "flex flex-row items-center gap-4",
 
@@ -1010,6 +1418,8 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
                             .unwrap_or(String::new()),
                         oninput: move |e: FormEvent| {
                             if let Ok(t) = e.value().parse::<i64>() {
+                                // typing a time in takes over from working it out
+                                recipe.timing().set(None);
                                 recipe.prep_time().set(Some(jiff::SignedDuration::from_mins(t)));
                                 sync_total_time.call(());
                             }
@@ -1034,6 +1444,7 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
                             .unwrap_or(String::new()),
                         oninput: move |e: FormEvent| {
                             if let Ok(t) = e.value().parse::<i64>() {
+                                recipe.timing().set(None);
                                 recipe.cook_time().set(Some(jiff::SignedDuration::from_mins(t)));
                                 sync_total_time.call(());
                             }
@@ -1059,7 +1470,104 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
                     span { "People" }
                 }
 
-                span { "Total time: {recipe.total_time().read().as_mins()} Minutes" }
+                Label { html_for: "recipe_scale", "Scale to" }
+                div { class: "flex flex-row items-center gap-4",
+
+                    Input {
+                        id: "recipe_scale",
+                        r#type: "number",
+                        min: 1,
+                        placeholder: "People",
+                        value: "{scale_to}",
+                        oninput: move |e: FormEvent| scale_to.set(e.value()),
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: move |_| {
+                            match scale_to.read().trim().parse::<u8>() {
+                                Ok(serves) if serves > 0 => {
+                                    scale_warnings.set(scale_recipe(recipe, serves));
+                                }
+                                _ => {
+                                    error_toast("Scaling failed", "Enter how many people it should serve");
+                                }
+                            }
+                        },
+                        "Scale recipe"
+                    }
+                }
+
+                if !scale_warnings.read().is_empty() {
+                    ul { class: "flex flex-col gap-2 text-sm text-(--secondary-color-5)",
+                        for warning in scale_warnings() {
+                            li { "{warning}" }
+                        }
+                    }
+                }
+
+                span {
+                    "Total time: {recipe.total_time().read().as_mins()} Minutes, about {timing.read().hands_off.as_mins()} of them hands-off"
+                }
+
+                if !timing.read().steps.is_empty() {
+                    Timeline {
+                        steps: recipe.steps().cloned(),
+                        timing: timing(),
+                        speeds: catalog.read().values_of(types::ReferenceSettingId::Speed),
+                    }
+                }
+
+                Label { html_for: "recipe_state", "Status" }
+                div { class: "flex flex-row items-center gap-4",
+
+                    native_select::NativeSelect::<String> {
+                        id: "recipe_state",
+                        value: Some(Some(recipe.state().cloned())),
+                        on_value_change: move |v| {
+                            if let Some(v) = v {
+                                recipe.state().set(v);
+                            }
+                        },
+
+                        native_select::NativeSelectOption::<String> { value: types::Recipe::DRAFT.to_owned(), "Draft" }
+                        native_select::NativeSelectOption::<String> { value: types::Recipe::PUBLISHED.to_owned(), "Published" }
+                    }
+
+                    if recipe.state().cloned() == types::Recipe::DRAFT {
+                        span { "Only shown here, not on the device" }
+                    } else if !recipe.read().is_live(chrono::Utc::now()) {
+                        span { "Scheduled, not on the device yet" }
+                    }
+                }
+
+                Label { html_for: "recipe_published_at", "Publish at (UTC)" }
+                Input {
+                    id: "recipe_published_at",
+                    r#type: "datetime-local",
+                    value: recipe
+                        .published_at()
+                        .cloned()
+                        .map(|x| x.format("%Y-%m-%dT%H:%M").to_string())
+                        .unwrap_or_default(),
+                    oninput: move |e: FormEvent| {
+                        let at = chrono::NaiveDateTime::parse_from_str(&e.value(), "%Y-%m-%dT%H:%M")
+                            .ok()
+                            .map(|x| x.and_utc());
+                        recipe.published_at().set(at);
+                    },
+                }
+
+                Label { html_for: "recipe_visibility", "Visibility" }
+                Input {
+                    id: "recipe_visibility",
+                    value: recipe.visibility(),
+                    oninput: move |e: FormEvent| recipe.visibility().set(e.value()),
+                }
+
+                Label { html_for: "recipe_tags", "Tags" }
+                div { id: "recipe_tags", class: "flex flex-col gap-4",
+                    TagPicker { tags: recipe.reference_tags() }
+                }
 
                 Tabs { default_value: "ingredients",
 
@@ -1081,9 +1589,11 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
                                             div { class: "flex items-center justify-center w-full h-full", "Loading..." }
                                         },
                                         Ingredient {
+                                            recipe_id: recipe.id().cloned(),
                                             ingredient,
                                             ingredients_matcher,
                                             preparations_matcher,
+                                            locale,
                                             delete: move |_| {
                                                 recipe.ingredients().remove(idx);
                                             },
@@ -1096,23 +1606,47 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
                                 class: "sm:max-w-1/2",
                                 variant: ButtonVariant::Secondary,
                                 onclick: move |_| {
+                                    let reference_unit = types::ReferenceUnit::gram();
                                     recipe
                                         .ingredients()
                                         .push(types::RecipeIngredient {
                                             uid: UID::new(),
                                             quantity: types::Quantity {
                                                 amount: Some(1.0),
-                                                reference_unit: types::ReferenceUnit::gram(),
-                                                text: "1 gram".to_owned(),
+                                                text: types::quantity::format(
+                                                    Some(1.0),
+                                                    &reference_unit,
+                                                    &locale.read(),
+                                                ),
+                                                reference_unit,
                                             },
                                             reference_ingredient: types::Ingredient::flour(),
                                             reference_preparations: vec![],
+                                            media: vec![],
                                             source_text: None,
                                         });
                                 },
 
                                 "Add ingredient"
                             }
+
+                            if !recipe.ingredients().read().is_empty() {
+                                IngredientUsage {
+                                    reconciliation: reconciliation(),
+                                    ingredients: recipe.ingredients().cloned(),
+                                    locale: locale(),
+                                }
+                            }
+
+                            div { class: "flex flex-row gap-4",
+                                for system in [MeasurementSystem::Metric, MeasurementSystem::UsCustomary] {
+                                    Button {
+                                        variant: ButtonVariant::Outline,
+                                        onclick: move |_| convert_units(recipe, system),
+                                        "Convert to {system}"
+                                    }
+                                }
+                            }
                         }
                     }
 
@@ -1123,9 +1657,15 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
                                     class: "flex flex-row gap-4",
                                     key: "{step.uid()}",
                                     Step {
+                                        recipe_id: recipe.id().cloned(),
                                         idx,
                                         step,
                                         ingredients: recipe.ingredients(),
+                                        schemas,
+                                        catalog,
+                                        appliance,
+                                        locale,
+                                        load: loads.read().get(idx).cloned(),
                                         delete: move |_| {
                                             recipe.steps().remove(idx);
                                         },
@@ -1143,6 +1683,7 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
                                             uid: UID::new(),
                                             capability: None,
                                             ingredients: vec![],
+                                            media: vec![],
                                             source_text: None,
                                             text: "Do something".to_owned(),
                                         });
@@ -1158,44 +1699,249 @@ pub fn EditRecipeInner(recipe: Store<types::Recipe>) -> Element {
     }
 }
 
+/// What's wrong with the recipe as it stands, errors first.
+#[component]
+fn ValidationIssues(issues: Vec<types::validate::Issue>) -> Element {
+    rsx! {
+        Card { class: "w-full",
+            CardHeader {
+                CardTitle { "Problems" }
+                CardDescription { "Errors have to be fixed before the recipe can be saved" }
+            }
+            CardContent {
+                ul { class: "flex flex-col gap-2",
+                    for issue in issues {
+                        li {
+                            class: match issue.severity {
+                                types::validate::Severity::Error => "text-red-500",
+                                types::validate::Severity::Warning => "text-(--secondary-color-5)",
+                            },
+                            span { class: "font-semibold", "{issue.path}: " }
+                            "{issue.message}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Each ingredient's listed quantity against what the steps use of it.
+#[component]
+fn IngredientUsage(
+    reconciliation: types::reconcile::Reconciliation,
+    ingredients: Vec<types::RecipeIngredient>,
+    locale: String,
+) -> Element {
+    use types::reconcile::Balance;
+
+    let rows = reconciliation
+        .ingredients
+        .iter()
+        .filter_map(|b| {
+            let quantity = &ingredients.get(b.ingredient_idx)?.quantity;
+            let amount =
+                |a: f64| types::quantity::format(Some(a), &quantity.reference_unit, &locale);
+            let used = b.used.map(amount).unwrap_or_else(|| "?".to_owned());
+            let steps = b.steps.iter().map(|s| (s + 1).to_string()).join(", ");
+            let (class, balance) = match b.balance {
+                Balance::Matches => ("", "All used".to_owned()),
+                Balance::Short(missing) => {
+                    ("text-red-500", format!("{} never added", amount(missing)))
+                }
+                Balance::Over(extra) => (
+                    "text-red-500",
+                    format!("{} more than listed", amount(extra)),
+                ),
+                Balance::Unused => ("text-red-500", "Not in any step".to_owned()),
+                Balance::Unknown => ("text-(--secondary-color-5)", "Can't be added up".to_owned()),
+            };
+
+            Some((
+                b.name.clone(),
+                quantity.text.clone(),
+                used,
+                steps,
+                class,
+                balance,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    rsx! {
+        Card { class: "w-full",
+            CardHeader {
+                CardTitle { "Usage" }
+                CardDescription { "What's listed against what the steps add" }
+            }
+            CardContent { class: "flex flex-col gap-2",
+                table { class: "text-sm",
+                    thead {
+                        tr {
+                            th { class: "text-left", "Ingredient" }
+                            th { class: "text-left", "Listed" }
+                            th { class: "text-left", "Used" }
+                            th { class: "text-left", "Steps" }
+                            th { class: "text-left", "" }
+                        }
+                    }
+                    tbody {
+                        for (name , listed , used , steps , class , balance) in rows {
+                            tr {
+                                td { "{name}" }
+                                td { "{listed}" }
+                                td { "{used}" }
+                                td { "{steps}" }
+                                td { class, "{balance}" }
+                            }
+                        }
+                    }
+                }
+                for unlisted in reconciliation.unlisted {
+                    span { class: "text-sm text-red-500",
+                        "Step {unlisted.step_idx + 1} uses ingredient {unlisted.ingredient_idx + 1}, which isn't listed"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The profile of the appliance the recipe is for. One that's been removed
+/// falls back on the built in profile of that name, or the Kenwood's.
+fn recipe_appliance(id: &str, appliances: &[ApplianceProfile]) -> ApplianceProfile {
+    appliances
+        .iter()
+        .find(|a| a.id == id)
+        .cloned()
+        .unwrap_or_else(|| ApplianceProfile::builtin_or_default(id))
+}
+
+/// Whether the recipe can be saved, toasting why not when it can't.
+fn ready_to_save(
+    recipe: &types::Recipe,
+    catalog: &types::catalog::Catalog,
+    appliances: &[ApplianceProfile],
+) -> bool {
+    let appliance = recipe_appliance(&recipe.appliance, appliances);
+    let issues = types::validate::validate(
+        recipe,
+        types::validate::Rules {
+            catalog,
+            appliance: &appliance,
+            // the bowl, ingredient usage and usual settings are only ever
+            // warned about, so needn't hold up saving
+            conversions: &types::convert::Conversions::default(),
+            schemas: &[],
+        },
+    );
+
+    if types::validate::has_errors(&issues) {
+        consume_toast().error(
+            "Recipe has errors".to_owned(),
+            ToastOptions::new()
+                .description("Fix the problems listed at the top first")
+                .duration(Duration::from_secs(5)),
+        );
+        return false;
+    }
+
+    true
+}
+
+/// Scales every amount in the recipe to serve `serves`, and gives back the
+/// steps that may need adjusting by hand.
+fn scale_recipe(mut recipe: Store<types::Recipe>, serves: u8) -> Vec<types::scale::ScaleWarning> {
+    let mut scaled = recipe();
+    let warnings = scaled.scale(serves);
+    recipe.set(scaled);
+
+    warnings
+}
+
+/// Converts every quantity in the recipe to `system`, using only the units
+/// each ingredient allows, and says which ingredients it couldn't.
+async fn convert_units(mut recipe: Store<types::Recipe>, system: MeasurementSystem) {
+    let ids = recipe.read().ingredient_ids();
+
+    let conversions = match conversions_server(ids).await {
+        Ok(conversions) => conversions,
+        Err(e) => {
+            error_toast("Converting units failed", e);
+            return;
+        }
+    };
+
+    let mut converted = recipe();
+    let failed = converted.convert_units(system, &conversions);
+    recipe.set(converted);
+
+    if failed.is_empty() {
+        info_toast(format!("Converted to {system}"));
+    } else {
+        error_toast(
+            &format!("Some amounts couldn't be converted to {system}"),
+            format!(
+                "{} have no {system} unit they can be given in",
+                failed.join(", ")
+            ),
+        );
+    }
+}
+
 #[component]
 pub fn NewRecipe() -> Element {
-    // todo: use correct author
+    // written by the install's default author, once one has been picked
+    let author = use_loader(crate::views::default_author_server)?
+        .cloned()
+        .unwrap_or_else(|| types::Author {
+            id: None,
+            name: "Household".to_owned(),
+            image: String::new(),
+            url: String::new(),
+        });
+    let created_by_id = author
+        .id
+        .map_or_else(|| "household".to_owned(), |id| id.to_string());
+
     let id = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 10);
     let recipe = use_store(move || types::Recipe {
-        author: types::Author {
-            name: "Kenwood".to_owned(),
-            image: "https://configs.fresco-kitchenos.com/fresco-1180x1180.svg".to_owned(),
-            url: "https://configs.fresco-kitchenos.com/fresco-1180x1180.svg".to_owned(),
-        },
+        appliance: ApplianceProfile::KENWOOD_COOKING_CHEF.to_owned(),
+        author,
         name: "".to_owned(),
         description: "".to_owned(),
         prep_time: None,
         cook_time: None,
         total_time: jiff::SignedDuration::new(0, 0),
         created_at: chrono::Utc::now(),
-        created_by_id: "toad".to_owned(),
+        created_by_id,
         etag: id.clone(),
         forked_into_other_locales: vec![],
         ingredients: vec![],
         locale: "en-GB".to_owned(),
         modified_at: chrono::Utc::now(),
-        organization_id: "toads".to_owned(),
+        organization_id: "household".to_owned(),
         published_at: Some(chrono::Utc::now()),
         reference_tags: vec![],
         serves: 1,
-        state: "published".to_owned(),
+        state: types::Recipe::DRAFT.to_owned(),
         steps: vec![],
+        timing: Some(types::timing::TimingEstimates::default()),
         visibility: "all-users".to_owned(),
         referenced: None,
         requester_role: None,
         id,
     });
 
+    let catalog = use_loader(crate::views::catalog_server)?;
+    let catalog = use_memo(move || catalog.cloned());
+    let appliances = use_loader(crate::views::appliances_server)?;
+    let appliances = use_memo(move || appliances.cloned());
+
     let nav = use_navigator();
 
     rsx! {
-        EditRecipeInner { recipe }
+        EditRecipeInner { recipe, catalog, appliances }
 
         div { class: "flex flex-row justify-end gap-4",
             Button {
@@ -1203,12 +1949,25 @@ pub fn NewRecipe() -> Element {
                     let recipe = recipe();
                     let id = recipe.id.clone();
                     async move {
-                        let _ = save_recipe_server(recipe, true).await;
-
-                        nav.replace(crate::Route::EditRecipe { id });
+                        if !ready_to_save(&recipe, &catalog.read(), &appliances.read()) {
+                            return;
+                        }
 
                         let toast_api = consume_toast();
 
+                        if let Err(e) = save_recipe_server(recipe, true, None).await {
+                            toast_api
+                                .error(
+                                    "Creating recipe failed".to_owned(),
+                                    ToastOptions::new()
+                                        .description(e.to_string())
+                                        .duration(Duration::from_secs(10)),
+                                );
+                            return;
+                        }
+
+                        nav.replace(crate::Route::EditRecipe { id });
+
                         toast_api
                             .info(
                                 "Created recipe".to_owned(),
@@ -1229,11 +1988,76 @@ pub fn NewRecipe() -> Element {
 pub fn EditRecipe(id: String) -> Element {
     let recipe_initial = use_loader(move || recipe_server(id.clone()))?.cloned();
     let recipe = use_store(move || recipe_initial);
+    let mut saved = use_signal(|| 0u64);
+    // someone else's version, when our last save lost out to it
+    let mut conflict = use_signal(|| None::<types::Recipe>);
+    let catalog = use_loader(crate::views::catalog_server)?;
+    let catalog = use_memo(move || catalog.cloned());
+    let appliances = use_loader(crate::views::appliances_server)?;
+    let appliances = use_memo(move || appliances.cloned());
 
     let nav = use_navigator();
 
+    let save = use_callback(
+        move |(local, base_etag): (types::Recipe, String)| async move {
+            if !ready_to_save(&local, &catalog.read(), &appliances.read()) {
+                return;
+            }
+
+            let toast_api = consume_toast();
+
+            match save_recipe_server(local, false, Some(base_etag)).await {
+                Ok(types::SaveOutcome::Saved { etag }) => {
+                    recipe.etag().set(etag);
+                    conflict.set(None);
+                    saved += 1;
+
+                    toast_api.info(
+                        "Edited recipe".to_owned(),
+                        ToastOptions::new().duration(Duration::from_secs(3)),
+                    );
+                }
+                Ok(types::SaveOutcome::Conflict { current }) => {
+                    conflict.set(Some(*current));
+                }
+                Err(e) => {
+                    toast_api.error(
+                        "Saving recipe failed".to_owned(),
+                        ToastOptions::new()
+                            .description(e.to_string())
+                            .duration(Duration::from_secs(10)),
+                    );
+                }
+            }
+        },
+    );
+
     rsx! {
-        EditRecipeInner { recipe }
+        EditRecipeInner { recipe, catalog, appliances }
+
+        if let Some(current) = conflict() {
+            SaveConflict {
+                mine: recipe(),
+                theirs: current,
+                on_reload: move |_| {
+                    if let Some(current) = conflict.take() {
+                        recipe.set(current);
+                        saved += 1;
+                    }
+                },
+                on_overwrite: move |_| {
+                    // only overwrite the version we were shown, so a third
+                    // save in the meantime is still caught
+                    let local = recipe();
+                    let base_etag = conflict.peek().as_ref().map(|c| c.etag.clone());
+                    async move {
+                        if let Some(base_etag) = base_etag {
+                            save((local, base_etag)).await;
+                        }
+                    }
+                },
+            }
+        }
 
         div { class: "flex flex-row justify-end gap-4",
             Button {
@@ -1242,12 +2066,25 @@ pub fn EditRecipe(id: String) -> Element {
                     let id = UID::new().0;
                     recipe.id = id.clone();
                     async move {
-                        let _ = save_recipe_server(recipe, true).await;
-
-                        nav.replace(crate::Route::EditRecipe { id });
+                        if !ready_to_save(&recipe, &catalog.read(), &appliances.read()) {
+                            return;
+                        }
 
                         let toast_api = consume_toast();
 
+                        if let Err(e) = save_recipe_server(recipe, true, None).await {
+                            toast_api
+                                .error(
+                                    "Cloning recipe failed".to_owned(),
+                                    ToastOptions::new()
+                                        .description(e.to_string())
+                                        .duration(Duration::from_secs(10)),
+                                );
+                            return;
+                        }
+
+                        nav.replace(crate::Route::EditRecipe { id });
+
                         toast_api
                             .info(
                                 "Cloned recipe".to_owned(),
@@ -1263,65 +2100,199 @@ pub fn EditRecipe(id: String) -> Element {
 
             Button {
                 onclick: move |_| {
-                    let recipe = recipe();
-                    async {
-                        let _ = save_recipe_server(recipe, false).await;
+                    let local = recipe();
+                    let base_etag = local.etag.clone();
+                    async move {
+                        save((local, base_etag)).await;
+                    }
+                },
 
+                "Update recipe"
+            }
+
+            Button {
+                variant: ButtonVariant::Destructive,
+                onclick: move |_| {
+                    let id = recipe.id().cloned();
+                    async move {
                         let toast_api = consume_toast();
 
+                        if let Err(e) = crate::views::delete_recipe_server(id).await {
+                            toast_api
+                                .error(
+                                    "Deleting recipe failed".to_owned(),
+                                    ToastOptions::new()
+                                        .description(e.to_string())
+                                        .duration(Duration::from_secs(10)),
+                                );
+                            return;
+                        }
+
+                        nav.replace(crate::Route::Home {});
+
                         toast_api
                             .info(
-                                "Edited recipe".to_owned(),
+                                "Moved recipe to the trash".to_owned(),
                                 ToastOptions::new().duration(Duration::from_secs(3)),
                             );
                     }
                 },
 
-                "Update recipe"
+                "Delete recipe"
             }
         }
+
+        Translations { recipe }
+
+        RevisionHistory { recipe, saved }
     }
 }
 
-#[server]
-async fn upload_image(mut form: MultipartFormData) -> Result<()> {
-    use dioxus::CapturedError;
+/// Links to a recipe's other translations, and makes new ones.
+#[component]
+fn Translations(recipe: Store<types::Recipe>) -> Element {
+    let mut locale = use_signal(String::new);
+    let nav = use_navigator();
 
-    let mut recipe_id = None;
-    let mut data = None;
+    rsx! {
+        Card { class: "w-full",
+            CardHeader {
+                CardTitle { "Translations" }
+                CardDescription {
+                    "Copies of this recipe in other languages, which the device picks between"
+                }
+            }
 
-    while let Ok(Some(field)) = form.next_field().await {
-        if field.name() == Some("id") {
-            recipe_id = Some(field.text().await?);
-            continue;
-        }
+            CardContent { class: "flex flex-col gap-4",
+                for fork in recipe.forked_into_other_locales().cloned() {
+                    LinkButton {
+                        key: "{fork.id}",
+                        variant: ButtonVariant::Outline,
+                        to: crate::Route::EditRecipe {
+                            id: fork.id.clone(),
+                        },
+                        "{fork.locale}"
+                    }
+                }
 
-        if field.name() == Some("data") {
-            data = Some(field.bytes().await?);
-            continue;
+                div { class: "flex flex-row gap-4 items-center",
+                    Input {
+                        placeholder: "de-DE",
+                        value: "{locale}",
+                        oninput: move |e: FormEvent| locale.set(e.value()),
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        disabled: locale.read().trim().is_empty(),
+                        onclick: move |_| {
+                            let id = recipe.id().cloned();
+                            let locale = locale.read().trim().to_owned();
+                            async move {
+                                let toast_api = consume_toast();
+
+                                match create_translation_server(id, locale).await {
+                                    Ok(id) => {
+                                        nav.push(crate::Route::EditRecipe { id });
+
+                                        toast_api
+                                            .info(
+                                                "Created translation".to_owned(),
+                                                ToastOptions::new()
+                                                    .description("Redirected to the editor page")
+                                                    .duration(Duration::from_secs(3)),
+                                            );
+                                    }
+                                    Err(e) => {
+                                        toast_api
+                                            .error(
+                                                "Creating translation failed".to_owned(),
+                                                ToastOptions::new()
+                                                    .description(e.to_string())
+                                                    .duration(Duration::from_secs(10)),
+                                            );
+                                    }
+                                }
+                            }
+                        },
+                        "Create translation"
+                    }
+                }
+            }
         }
     }
+}
 
-    let (Some(recipe_id), Some(data)) = (recipe_id, data) else {
-        return Ok(());
-    };
-
-    let image = image::ImageReader::new(std::io::Cursor::new(&data))
-        .with_guessed_format()
-        .context("Guessing image format")?
-        .decode()
-        .context("Decoding image")?;
+/// Shown when saving lost out to someone else's save, with a choice between
+/// taking their version or keeping ours.
+#[component]
+fn SaveConflict(
+    mine: types::Recipe,
+    theirs: types::Recipe,
+    on_reload: EventHandler<()>,
+    on_overwrite: EventHandler<()>,
+) -> Element {
+    let mut show_diff = use_signal(|| false);
+    let changes = types::diff::diff_recipes(&theirs, &mine);
 
-    let mut data = Vec::<u8>::new();
-    image
-        .write_to(std::io::Cursor::new(&mut data), image::ImageFormat::WebP)
-        .context("Coverting image")?;
+    rsx! {
+        Card { class: "w-full border-red-500",
+            CardHeader {
+                CardTitle { "This recipe was changed by someone else" }
+                CardDescription {
+                    "Your changes weren't saved. Reload to take their version, or overwrite it with yours."
+                }
+            }
 
-    db::queries::images::set_image(crate::db::db(), &recipe_id, data)
-        .await
-        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+            CardContent { class: "flex flex-col gap-4",
+                div { class: "flex flex-row justify-end gap-4",
+                    Button {
+                        variant: ButtonVariant::Outline,
+                        onclick: move |_| show_diff.set(!show_diff()),
+                        if show_diff() {
+                            "Hide changes"
+                        } else {
+                            "View changes"
+                        }
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: move |_| on_reload.call(()),
+                        "Reload"
+                    }
+                    Button {
+                        variant: ButtonVariant::Destructive,
+                        onclick: move |_| on_overwrite.call(()),
+                        "Overwrite"
+                    }
+                }
 
-    Ok(())
+                if show_diff() {
+                    if changes.is_empty() {
+                        p { "Both versions are the same" }
+                    } else {
+                        table { class: "w-full text-left",
+                            thead {
+                                tr {
+                                    th { "What" }
+                                    th { "Theirs" }
+                                    th { "Yours" }
+                                }
+                            }
+                            tbody {
+                                for change in changes {
+                                    tr {
+                                        td { "{change.path}" }
+                                        td { class: "text-red-500", "{change.before.unwrap_or_default()}" }
+                                        td { class: "text-green-500", "{change.after.unwrap_or_default()}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[server]
@@ -1341,7 +2312,7 @@ async fn recipe_server(id: String) -> Result<types::Recipe> {
 }
 
 #[server]
-async fn ingredients_server() -> Result<Vec<types::Ingredient>> {
+pub(crate) async fn ingredients_server() -> Result<Vec<types::Ingredient>> {
     use dioxus::{
         logger::tracing::{info_span, Instrument as _},
         CapturedError,
@@ -1356,18 +2327,73 @@ async fn ingredients_server() -> Result<Vec<types::Ingredient>> {
 }
 
 #[server]
-async fn save_recipe_server(recipe: types::Recipe, create: bool) -> Result<()> {
+async fn conversions_server(ids: Vec<String>) -> Result<types::convert::Conversions> {
     use dioxus::{
         logger::tracing::{info_span, Instrument as _},
         CapturedError,
     };
 
-    let recipe = db::queries::recipes::set_recipe(crate::db::db(), recipe, create)
-        .instrument(info_span!("Setting recipe"))
+    let conversions = db::queries::ingredients::conversions(crate::db::db(), ids)
+        .instrument(info_span!("Loading unit conversions"))
         .await
         .map_err(|e| CapturedError::from_boxed(e.into()))?;
 
-    Ok(recipe)
+    Ok(conversions)
+}
+
+/// `base_etag` is the etag of the version being edited; saves made on top of
+/// an older version come back as a conflict instead of being written.
+#[post("/api/recipes/save", headers: http::HeaderMap)]
+async fn save_recipe_server(
+    mut recipe: types::Recipe,
+    create: bool,
+    base_etag: Option<String>,
+) -> Result<types::SaveOutcome> {
+    use dioxus::{
+        logger::tracing::{info_span, Instrument as _},
+        CapturedError,
+    };
+
+    recipe.sync_times();
+
+    let editor = crate::db::editor_from_headers(&headers);
+
+    let result = db::queries::recipes::set_recipe(
+        crate::db::db(),
+        recipe,
+        create,
+        base_etag.as_deref(),
+        editor,
+    )
+    .instrument(info_span!("Setting recipe"))
+    .await;
+
+    match result {
+        Ok(etag) => Ok(types::SaveOutcome::Saved { etag }),
+        Err(e) => match e.downcast::<db::queries::recipes::Conflict>() {
+            Ok(conflict) => Ok(types::SaveOutcome::Conflict {
+                current: Box::new(conflict.current),
+            }),
+            Err(e) if e.is::<db::queries::recipes::Invalid>() => {
+                Err(CapturedError::from_display(e))
+            }
+            Err(e) => Err(CapturedError::from_boxed(e.into())),
+        },
+    }
+}
+
+#[post("/api/recipes/translate", headers: http::HeaderMap)]
+async fn create_translation_server(recipe_id: String, locale: String) -> Result<String> {
+    use dioxus::CapturedError;
+
+    db::queries::recipes::create_translation(
+        crate::db::db(),
+        &recipe_id,
+        &locale,
+        crate::db::editor_from_headers(&headers),
+    )
+    .await
+    .map_err(CapturedError::from_display)
 }
 
 #[server]
@@ -1385,6 +2411,36 @@ async fn preparations_server() -> Result<Vec<types::ReferencePreparation>> {
     Ok(preparations)
 }
 
+#[server]
+async fn capability_schemas_server() -> Result<Vec<types::schema::CapabilitySchema>> {
+    use dioxus::{
+        logger::tracing::{info_span, Instrument as _},
+        CapturedError,
+    };
+
+    let schemas = db::queries::capabilities::list_capability_schemas(crate::db::db())
+        .instrument(info_span!("Loading capability schemas"))
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+
+    Ok(schemas)
+}
+
+#[server]
+async fn tags_server() -> Result<Vec<types::ReferenceTag>> {
+    use dioxus::{
+        logger::tracing::{info_span, Instrument as _},
+        CapturedError,
+    };
+
+    let tags = db::queries::tags::list_tags(crate::db::db())
+        .instrument(info_span!("Loading tags"))
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+
+    Ok(tags)
+}
+
 #[server]
 async fn allowed_units_server(ingredient_id: String) -> Result<Vec<types::IngredientAllowedUnit>> {
     use dioxus::{
@@ -8,8 +8,12 @@ use dioxus::prelude::*;
 pub fn Home() -> Element {
     let mut current_page = use_signal(|| Saturating(0u64));
     let mut show_all = use_signal(|| false);
-    let recipes =
-        use_loader(move || recipes_server(Some(current_page().0), Some(100), show_all()))?;
+    // bumped to reload the list after deleting a recipe
+    let mut generation = use_signal(|| 0u64);
+    let recipes = use_loader(move || {
+        generation();
+        recipes_server(Some(current_page().0), Some(100), show_all())
+    })?;
 
     rsx! {
         Toggle {
@@ -31,7 +35,7 @@ pub fn Home() -> Element {
             div { class: "flex flex-col gap-4",
 
                 for recipe in recipes.cloned() {
-                    RecipeItem { recipe }
+                    RecipeItem { recipe, on_delete: move |()| generation += 1 }
                 }
             }
         }
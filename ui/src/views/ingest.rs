@@ -124,6 +124,15 @@ async fn upload_data(mut form: MultipartFormData) -> Result<()> {
     ingest::insert_recipes(db, &recipes)
         .await
         .map_err(|e| CapturedError::from_boxed(e.into()))?;
+    ingest::insert_recipe_tags(db, &recipes)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+    ingest::insert_reference_catalog(db, &recipes)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
+    db::queries::capabilities::learn_capability_schemas(db)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))?;
 
     Ok(())
 }
@@ -2,6 +2,7 @@ mod home;
 pub use home::Home;
 
 mod edit_recipe;
+pub(crate) use edit_recipe::ingredients_server;
 pub use edit_recipe::{EditRecipe, NewRecipe};
 
 mod navbar;
@@ -9,3 +10,19 @@ pub use navbar::Navbar;
 
 mod ingest;
 pub use ingest::Ingest;
+
+mod authors;
+pub use authors::Authors;
+pub(crate) use authors::{authors_server, default_author_server};
+
+mod appliances;
+pub(crate) use appliances::appliances_server;
+pub use appliances::Appliances;
+
+mod catalog;
+pub(crate) use catalog::catalog_server;
+pub use catalog::Catalog;
+
+mod trash;
+pub(crate) use trash::delete_recipe_server;
+pub use trash::Trash;
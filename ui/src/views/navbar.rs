@@ -28,6 +28,34 @@ pub fn Navbar() -> Element {
 
                 "Ingest data"
             }
+
+            LinkButton {
+                variant: crate::components::button::ButtonVariant::Secondary,
+                to: Route::Authors {},
+
+                "Authors"
+            }
+
+            LinkButton {
+                variant: crate::components::button::ButtonVariant::Secondary,
+                to: Route::Catalog {},
+
+                "Catalog"
+            }
+
+            LinkButton {
+                variant: crate::components::button::ButtonVariant::Secondary,
+                to: Route::Appliances {},
+
+                "Appliances"
+            }
+
+            LinkButton {
+                variant: crate::components::button::ButtonVariant::Secondary,
+                to: Route::Trash {},
+
+                "Trash"
+            }
                 // }
         }
 
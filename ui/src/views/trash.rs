@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{consume_toast, ToastOptions};
+
+use crate::components::{
+    button::{Button, ButtonVariant},
+    card::*,
+};
+
+#[component]
+pub fn Trash() -> Element {
+    // bumped to reload the trash after restoring or purging
+    let mut generation = use_signal(|| 0u64);
+    let recipes = use_loader(move || {
+        generation();
+        trashed_recipes_server()
+    })?;
+
+    rsx! {
+        div { class: "flex flex-col gap-4",
+
+            if recipes.read().is_empty() {
+                p { "The trash is empty" }
+            }
+
+            for recipe in recipes.cloned() {
+                Card { key: "{recipe.id}", class: "w-full",
+
+                    CardHeader {
+                        CardTitle { "{recipe.name}" }
+                        CardDescription {
+                            "By {recipe.author_name}, deleted {recipe.deleted_at.format(\"%Y-%m-%d %H:%M\")}"
+                        }
+                        CardAction {
+                            div { class: "flex flex-row gap-2",
+                                Button {
+                                    variant: ButtonVariant::Secondary,
+                                    onclick: {
+                                        let id = recipe.id.clone();
+                                        move |_| {
+                                            let id = id.clone();
+                                            async move {
+                                                if let Err(e) = restore_recipe_server(id).await {
+                                                    consume_toast()
+                                                        .error(
+                                                            "Restoring recipe failed".to_owned(),
+                                                            ToastOptions::new()
+                                                                .description(e.to_string())
+                                                                .duration(Duration::from_secs(10)),
+                                                        );
+                                                }
+                                                generation += 1;
+                                            }
+                                        }
+                                    },
+
+                                    "Restore"
+                                }
+
+                                Button {
+                                    variant: ButtonVariant::Destructive,
+                                    onclick: {
+                                        let id = recipe.id.clone();
+                                        move |_| {
+                                            let id = id.clone();
+                                            async move {
+                                                if let Err(e) = purge_recipe_server(id).await {
+                                                    consume_toast()
+                                                        .error(
+                                                            "Deleting recipe failed".to_owned(),
+                                                            ToastOptions::new()
+                                                                .description(e.to_string())
+                                                                .duration(Duration::from_secs(10)),
+                                                        );
+                                                }
+                                                generation += 1;
+                                            }
+                                        }
+                                    },
+
+                                    "Delete forever"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[server]
+async fn trashed_recipes_server() -> Result<Vec<types::TrashedRecipe>> {
+    use dioxus::CapturedError;
+
+    db::queries::recipes::list_trashed_recipes(crate::db::db())
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn restore_recipe_server(id: String) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::recipes::restore_recipe(crate::db::db(), &id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+async fn purge_recipe_server(id: String) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::recipes::purge_recipe(crate::db::db(), &id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}
+
+#[server]
+pub(crate) async fn delete_recipe_server(id: String) -> Result<()> {
+    use dioxus::CapturedError;
+
+    db::queries::recipes::delete_recipe(crate::db::db(), &id)
+        .await
+        .map_err(|e| CapturedError::from_boxed(e.into()))
+}